@@ -0,0 +1,146 @@
+use flate2::bufread::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression of an input file, which is decompressed while it is read, or of an output file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Uncompressed,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Compression given by the extension of the file, e.g. `.csv.gz`
+    pub fn from_extension(file_name: &str) -> Option<Self> {
+        match file_name.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("gz" | "gzip") => Some(Compression::Gzip),
+            Some("zst" | "zstd") => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Compression of the file by its extension, or by its first bytes if the extension is not
+    /// a compression extension
+    pub fn detect(file_name: &str) -> std::io::Result<Self> {
+        if let Some(compression) = Self::from_extension(file_name) {
+            return Ok(compression);
+        }
+        let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+        File::open(file_name)?
+            .take(ZSTD_MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+        Ok(if magic.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if magic.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::Uncompressed
+        })
+    }
+
+    /// File name without the compression extension, to detect the format of the content
+    pub fn strip_extension(file_name: &str) -> &str {
+        match Self::from_extension(file_name) {
+            Some(_) => file_name.rsplit_once('.').unwrap().0,
+            None => file_name,
+        }
+    }
+}
+
+/// Opens the file for reading, compressed files are decompressed while streaming
+pub fn open_file(file_name: &str) -> std::io::Result<Box<dyn BufRead>> {
+    let compression = Compression::detect(file_name)?;
+    let file = BufReader::new(File::open(file_name)?);
+    Ok(match compression {
+        Compression::Uncompressed => Box::new(file),
+        // concatenated gzip members are read as one stream, like `cat a.gz b.gz` does
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(file)?)),
+    })
+}
+
+/// Output file, compressed while it is written
+pub enum FileWriter {
+    Uncompressed(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl FileWriter {
+    /// Writes the end of the compressed stream and flushes the file
+    pub fn finish(self) -> std::io::Result<()> {
+        match self {
+            FileWriter::Uncompressed(mut w) => w.flush(),
+            FileWriter::Gzip(w) => w.finish()?.flush(),
+            FileWriter::Zstd(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for FileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            FileWriter::Uncompressed(w) => w.write(buf),
+            FileWriter::Gzip(w) => w.write(buf),
+            FileWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            FileWriter::Uncompressed(w) => w.flush(),
+            FileWriter::Gzip(w) => w.flush(),
+            FileWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Creates the file for writing with the given compression
+pub fn create_file(file_name: &str, compression: Compression) -> std::io::Result<FileWriter> {
+    let file = BufWriter::new(File::create(file_name)?);
+    Ok(match compression {
+        Compression::Uncompressed => FileWriter::Uncompressed(file),
+        Compression::Gzip => FileWriter::Gzip(GzEncoder::new(file, Default::default())),
+        Compression::Zstd => FileWriter::Zstd(zstd::Encoder::new(file, 0)?),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::compression::{open_file, Compression};
+    use std::io::Read;
+
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(
+            Compression::detect("testdata/employee.csv.gz").unwrap(),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::detect("testdata/employee.csv").unwrap(),
+            Compression::Uncompressed
+        );
+        assert_eq!(
+            Compression::strip_extension("testdata/events.ndjson.zst"),
+            "testdata/events.ndjson"
+        );
+    }
+
+    #[test]
+    fn test_open_compressed_file() {
+        let expected = std::fs::read_to_string("testdata/employee.csv").unwrap();
+        for file_name in ["testdata/employee.csv.gz", "testdata/employee.csv.zst"] {
+            let mut content = String::new();
+            open_file(file_name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            assert_eq!(expected, content);
+        }
+    }
+}
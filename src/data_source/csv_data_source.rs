@@ -1,23 +1,150 @@
-use crate::data_source::DataSource;
+use crate::data_source::compression::{self, Compression, FileWriter};
+use crate::data_source::statistics::Statistics;
+use crate::data_source::{scan_and_filter, DataSource, FilterPushDown, LimitHint};
 use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
 use crate::datatypes::arrow_types::ArrowType;
 use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
 use crate::datatypes::column_vector::ColumnVector;
 use crate::datatypes::record_batch::RecordBatch;
 use crate::datatypes::schema::{Field, Schema};
-use arrow::array::ArrayBuilder;
-use arrow::datatypes::DataType;
-use csv::{Reader, ReaderBuilder, StringRecord, Terminator, Trim};
+use crate::logical_plan::expressions::Expr;
+use anyhow::{anyhow, bail};
+use csv::{Reader, ReaderBuilder, StringRecord, Terminator, Trim, Writer, WriterBuilder};
 use std::any::Any;
-use std::fs::File;
-use std::sync::Arc;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use tracing::info;
 
+/// Number of rows read to infer the column types when no schema is provided
+pub const DEFAULT_INFER_SCHEMA_ROWS: usize = 100;
+
+/// Dialect of a CSV file, the default is a comma separated file with a header row
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    pub has_headers: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    /// escape character for quotes inside quoted values, None means quotes are doubled
+    pub escape: Option<u8>,
+    /// rows starting with this character are ignored
+    pub comment: Option<u8>,
+    /// number of lines at the start of the file which are skipped before the header
+    pub skip_rows: usize,
+    /// trim whitespace around values and headers
+    pub trim: bool,
+    /// column names to use instead of the header or the generated `field_<i>` names
+    pub column_names: Option<Vec<String>>,
+    pub on_malformed_row: MalformedRowPolicy,
+}
+
+/// What a scan does with rows which have the wrong number of fields or values which can't be
+/// converted to the column type. Only the columns read by the scan are converted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MalformedRowPolicy {
+    /// end the scan with an error with the line number and reason, returned by `take_scan_error`
+    #[default]
+    Fail,
+    /// drop the row and add it to the rejected rows
+    Skip,
+    /// read the invalid or missing values as null and add the row to the rejected rows
+    Null,
+}
+
+/// Row rejected by a scan, `line` is the 1-based line number in the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct RejectedRow {
+    pub file_name: String,
+    pub line: u64,
+    pub reason: String,
+}
+
+impl Display for RejectedRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "malformed row at {}:{}: {}",
+            self.file_name, self.line, self.reason
+        )
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            has_headers: true,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            comment: None,
+            skip_rows: 0,
+            trim: true,
+            column_names: None,
+            on_malformed_row: MalformedRowPolicy::Fail,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Tab separated file with a header row
+    pub fn tsv() -> Self {
+        Self::default().with_delimiter(b'\t')
+    }
+
+    pub fn with_has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    pub fn with_comment(mut self, comment: u8) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn with_skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn with_on_malformed_row(mut self, policy: MalformedRowPolicy) -> Self {
+        self.on_malformed_row = policy;
+        self
+    }
+
+    pub fn with_column_names(mut self, column_names: Vec<&str>) -> Self {
+        self.column_names = Some(column_names.into_iter().map(String::from).collect());
+        self
+    }
+}
+
 pub struct CsvDataSource {
     pub file_name: String,
     pub schema: Arc<Schema>,
-    has_headers: bool,
+    options: CsvOptions,
     batch_size: usize,
+    rejected_rows: Arc<Mutex<Vec<RejectedRow>>>,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
 }
 
 impl DataSource for CsvDataSource {
@@ -26,96 +153,256 @@ impl DataSource for CsvDataSource {
     }
 
     fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
-        info!("scan() projection={}", projection.concat());
+        self.read(projection, None)
+    }
+
+    fn supports_filter(&self, _filter: &Arc<Expr>) -> FilterPushDown {
+        FilterPushDown::Exact
+    }
+
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        scan_and_filter(self, projection, filters, None)
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::from_file(&self.file_name)
+    }
+
+    fn location(&self) -> Option<String> {
+        Some(self.file_name.clone())
+    }
+
+    fn scan_with_limit(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+        limit: LimitHint,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        match filters.is_empty() {
+            true => self.read(projection, Some(limit)),
+            false => scan_and_filter(self, projection, filters, Some(limit)),
+        }
+    }
+
+    /// Rows rejected by the scans since the last call, the report is cleared
+    fn take_rejected_rows(&self) -> Vec<RejectedRow> {
+        std::mem::take(&mut self.rejected_rows.lock().unwrap())
+    }
+
+    /// File which couldn't be opened, unknown projected column or malformed row with
+    /// `MalformedRowPolicy::Fail`, which failed a scan
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        self.scan_error.lock().unwrap().take()
+    }
+}
+
+impl CsvDataSource {
+    /// Fails if no schema is given and it can't be inferred, e.g. because the file is missing
+    pub fn try_new(
+        file_name: impl Into<String>,
+        schema: Option<Arc<Schema>>,
+        options: CsvOptions,
+        batch_size: usize,
+    ) -> anyhow::Result<Self> {
+        let file_name = file_name.into();
+        let schema = match schema {
+            Some(schema) => schema,
+            None => Arc::new(Self::infer_schema(
+                &file_name,
+                &options,
+                DEFAULT_INFER_SCHEMA_ROWS,
+            )?),
+        };
+        Ok(CsvDataSource {
+            file_name,
+            schema,
+            options,
+            batch_size,
+            rejected_rows: Default::default(),
+            scan_error: Default::default(),
+        })
+    }
 
-        let file = Self::open_file(&self.file_name);
-        let reader = Self::get_default_reader(self.has_headers, file);
+    fn read(
+        &self,
+        projection: Vec<&str>,
+        limit: Option<LimitHint>,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        info!("scan() projection={}", projection.concat());
 
         let read_schema = if projection.is_empty() {
             self.schema.clone()
         } else {
-            Arc::from(self.schema.select(projection).expect("TODO: panic message"))
+            match self.schema.select(projection.clone()) {
+                Ok(schema) => Arc::new(schema),
+                Err(e) => {
+                    *self.scan_error.lock().unwrap() = Some(anyhow!(
+                        "cannot project {:?} from {}: {}",
+                        projection,
+                        self.file_name,
+                        e
+                    ));
+                    return Box::new(std::iter::empty());
+                }
+            }
+        };
+
+        // a file which can't be opened fails the scan like a malformed row
+        let reader = match Self::create_reader(&self.file_name, &self.options) {
+            Ok(reader) => reader,
+            Err(e) => {
+                *self.scan_error.lock().unwrap() = Some(e);
+                return Box::new(std::iter::empty());
+            }
         };
 
         let r = CsvReader {
+            file_name: self.file_name.clone(),
             full_schema: self.schema.clone(),
             read_schema,
             batch_size: self.batch_size,
+            limit,
+            options: self.options.clone(),
+            rejected_rows: self.rejected_rows.clone(),
+            scan_error: self.scan_error.clone(),
             reader,
         };
         Box::new(r.into_iter())
     }
-}
 
-impl CsvDataSource {
-    pub fn new(
-        file_name: impl Into<String>,
-        schema: Option<Arc<Schema>>,
-        has_headers: bool,
-        batch_size: usize,
-    ) -> Self {
-        let file_name = file_name.into();
-        let schema = schema.unwrap_or_else(|| {
-            Arc::from(Self::infer_schema(has_headers, file_name.as_str()).unwrap())
-        });
-        CsvDataSource {
-            file_name,
-            schema,
-            has_headers,
-            batch_size,
+    fn create_reader(
+        file_name: &str,
+        options: &CsvOptions,
+    ) -> anyhow::Result<Reader<Box<dyn BufRead>>> {
+        let mut file = compression::open_file(file_name)
+            .map_err(|e| anyhow!("failed to open {}: {}", file_name, e))?;
+        // skipped lines are not parsed at all, so they may contain anything
+        for _ in 0..options.skip_rows {
+            file.skip_until(b'\n')
+                .map_err(|e| anyhow!("failed to read {}: {}", file_name, e))?;
         }
+        Ok(ReaderBuilder::new()
+            .has_headers(options.has_headers)
+            .terminator(Terminator::CRLF)
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .escape(options.escape)
+            .double_quote(options.escape.is_none())
+            .flexible(true)
+            .comment(options.comment)
+            .trim(if options.trim { Trim::All } else { Trim::None })
+            .from_reader(file))
     }
 
-    fn get_default_reader(has_headers: bool, file: File) -> Reader<File> {
-        ReaderBuilder::new()
-            .has_headers(has_headers)
-            .terminator(Terminator::CRLF)
-            .delimiter(b',')
-            .trim(Trim::All)
-            .from_reader(file)
+    /// Infers the column types from the first `max_rows` rows. A column is Boolean, Int64 or
+    /// Double if all its non blank values parse as such, otherwise String. Columns with blank
+    /// values are nullable and read blank values as null.
+    pub fn infer_schema(
+        file_name: &str,
+        options: &CsvOptions,
+        max_rows: usize,
+    ) -> anyhow::Result<Schema> {
+        let mut reader = Self::create_reader(file_name, options)?;
+
+        let column_count = reader.headers()?.len();
+        let names: Vec<String> = match &options.column_names {
+            Some(names) if names.len() != column_count => bail!(
+                "{} column names given for {} columns of {}",
+                names.len(),
+                column_count,
+                file_name
+            ),
+            Some(names) => names.clone(),
+            None if options.has_headers => {
+                reader.headers()?.iter().map(ToString::to_string).collect()
+            }
+            None => (0..column_count).map(|i| format!("field_{i}")).collect(),
+        };
+
+        let mut columns = vec![ColumnInference::default(); names.len()];
+        for record in reader.records().take(max_rows) {
+            let record = record?;
+            for (column, value) in columns.iter_mut().zip(record.iter()) {
+                column.update(value);
+            }
+        }
+
+        let fields = names
+            .into_iter()
+            .zip(columns)
+            .map(|(name, column)| {
+                Arc::new(Field {
+                    name,
+                    data_type: column.data_type(),
+                    nullable: column.nullable,
+                })
+            })
+            .collect();
+        Ok(Schema { fields })
     }
+}
 
-    fn open_file(file_name: &str) -> File {
-        File::open(file_name).expect(format!("file not found: {}", file_name).as_str())
+/// Types the values of a column seen so far could be parsed as
+#[derive(Clone)]
+struct ColumnInference {
+    boolean: bool,
+    int64: bool,
+    double: bool,
+    has_values: bool,
+    nullable: bool,
+}
+
+impl Default for ColumnInference {
+    fn default() -> Self {
+        ColumnInference {
+            boolean: true,
+            int64: true,
+            double: true,
+            has_values: false,
+            nullable: false,
+        }
     }
+}
 
-    fn infer_schema(has_headers: bool, file_name: &str) -> anyhow::Result<Schema> {
-        let file = Self::open_file(file_name);
-        let mut reader = Self::get_default_reader(has_headers, file);
+impl ColumnInference {
+    fn update(&mut self, value: &str) {
+        if value.is_empty() {
+            self.nullable = true;
+            return;
+        }
+        self.has_values = true;
+        self.boolean &= value.parse::<bool>().is_ok();
+        self.int64 &= value.parse::<i64>().is_ok();
+        self.double &= value.parse::<f64>().is_ok();
+    }
 
-        let headers = reader.headers()?;
-        if has_headers {
-            let fields = headers
-                .iter()
-                .map(|column_name| {
-                    Arc::new(Field {
-                        name: column_name.to_string(),
-                        data_type: ArrowType::StringType,
-                    })
-                })
-                .collect();
-            Ok(Schema { fields })
-        } else {
-            let fields = headers
-                .iter()
-                .enumerate()
-                .map(|(i, _)| {
-                    Arc::new(Field {
-                        name: format!("field_{i}"),
-                        data_type: ArrowType::StringType,
-                    })
-                })
-                .collect();
-            Ok(Schema { fields })
+    fn data_type(&self) -> ArrowType {
+        match self {
+            ColumnInference {
+                has_values: false, ..
+            } => ArrowType::StringType,
+            ColumnInference { boolean: true, .. } => ArrowType::BooleanType,
+            ColumnInference { int64: true, .. } => ArrowType::Int64Type,
+            ColumnInference { double: true, .. } => ArrowType::DoubleType,
+            _ => ArrowType::StringType,
         }
     }
 }
 
 struct CsvReader {
+    file_name: String,
     full_schema: Arc<Schema>,
     read_schema: Arc<Schema>,
     batch_size: usize,
-    reader: Reader<File>,
+    limit: Option<LimitHint>,
+    options: CsvOptions,
+    rejected_rows: Arc<Mutex<Vec<RejectedRow>>>,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+    reader: Reader<Box<dyn BufRead>>,
 }
 
 impl IntoIterator for CsvReader {
@@ -123,35 +410,82 @@ impl IntoIterator for CsvReader {
     type IntoIter = CsvReaderIterator;
 
     fn into_iter(self) -> Self::IntoIter {
+        let columns = self
+            .read_schema
+            .fields
+            .iter()
+            .map(|field| {
+                let index = self
+                    .full_schema
+                    .fields
+                    .iter()
+                    .position(|f| f.name == field.name)
+                    .unwrap();
+                (index, field.data_type.clone(), field.nullable)
+            })
+            .collect();
         CsvReaderIterator {
-            file_schema: self.full_schema,
+            file_name: self.file_name,
+            column_count: self.full_schema.fields.len(),
             read_schema: self.read_schema,
+            columns,
             batch_size: self.batch_size,
+            limit: self.limit,
+            options: self.options,
+            rejected_rows: self.rejected_rows,
+            scan_error: self.scan_error,
+            failed: false,
             reader: self.reader.into_records(),
         }
     }
 }
 
 struct CsvReaderIterator {
-    file_schema: Arc<Schema>,
+    file_name: String,
+    column_count: usize,
     read_schema: Arc<Schema>,
+    /// position in the file, type and nullability of the columns which are read
+    columns: Vec<(usize, ArrowType, bool)>,
     batch_size: usize,
-    reader: csv::StringRecordsIntoIter<File>,
+    /// rows still needed by the consumer, the first batch is not larger if the limit is soft
+    limit: Option<LimitHint>,
+    options: CsvOptions,
+    rejected_rows: Arc<Mutex<Vec<RejectedRow>>>,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+    /// set once a malformed row failed the scan, no more rows are read
+    failed: bool,
+    reader: csv::StringRecordsIntoIter<Box<dyn BufRead>>,
 }
 
+type Row = Vec<Option<Arc<dyn Any>>>;
+
 impl Iterator for CsvReaderIterator {
     type Item = RecordBatch;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut rows = Vec::with_capacity(self.batch_size);
+        let batch_size = match self.limit {
+            Some(limit) => limit.rows().min(self.batch_size),
+            None => self.batch_size,
+        };
+        let mut rows = Vec::with_capacity(batch_size);
 
-        rows.extend(
-            self.reader
-                .by_ref()
-                .take(self.batch_size)
-                .filter_map(Result::ok),
-        );
+        while rows.len() < batch_size && !self.failed {
+            let Some(record) = self.reader.next() else {
+                break;
+            };
+            if let Some(row) = self.read_row(record) {
+                rows.push(row);
+            }
+        }
+        if self.failed {
+            return None;
+        }
 
+        self.limit = match self.limit {
+            // no more rows are read once the exact limit is reached
+            Some(LimitHint::Exact(rows_left)) => Some(LimitHint::Exact(rows_left - rows.len())),
+            _ => None,
+        };
         if rows.is_empty() {
             None
         } else {
@@ -161,38 +495,83 @@ impl Iterator for CsvReaderIterator {
 }
 
 impl CsvReaderIterator {
-    fn create_batch(&self, rows: Vec<StringRecord>) -> RecordBatch {
-        let initial_capacity = rows.len();
+    /// Converts the values of the columns which are read, None if the row is rejected
+    fn read_row(&mut self, record: csv::Result<StringRecord>) -> Option<Row> {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                self.reject(line, e.to_string());
+                return None;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line());
 
-        let mut field_builders: Vec<(usize, ArrowVectorBuilder)> = self
-            .read_schema
-            .fields
+        let mut errors = vec![];
+        if record.len() != self.column_count {
+            errors.push(format!(
+                "expected {} fields, got {}",
+                self.column_count,
+                record.len()
+            ));
+        }
+        let row = self
+            .columns
             .iter()
-            .map(|x| {
-                let pos = self // todo move position finding outside of iterator to just do it once
-                    .file_schema
-                    .fields
-                    .iter()
-                    .position(|s| s.name == x.name)
-                    .unwrap();
-                let array = ArrowArrayFactory::create(x.data_type.to_datatype(), initial_capacity);
-                let builder = ArrowVectorBuilder::new(array);
-                (pos, builder)
+            .map(|(index, data_type, nullable)| match record.get(*index) {
+                // blank values are null, except for non nullable strings where they are empty
+                Some("") if *nullable || *data_type != ArrowType::StringType => None,
+                Some(value) => parse_value(value, data_type)
+                    .map_err(|e| {
+                        errors.push(format!(
+                            "column {}: invalid {:?} '{}': {}",
+                            index, data_type, value, e
+                        ))
+                    })
+                    .ok(),
+                None => None,
             })
             .collect();
 
-        rows.iter().for_each(|row| {
-            for (index, field) in field_builders.iter_mut() {
-                let value = row
-                    .get(*index)
-                    .map(|str_value| Arc::new(str_value.to_string()) as Arc<dyn Any>);
-                field.append(value)
-            }
-        });
+        if errors.is_empty() {
+            return Some(row);
+        }
+        self.reject(line, errors.join(", "));
+        match self.options.on_malformed_row {
+            MalformedRowPolicy::Null => Some(row),
+            _ => None,
+        }
+    }
 
-        let fields: Vec<Arc<dyn ColumnVector>> = field_builders
-            .into_iter()
-            .map(|(_, field)| field.build())
+    /// Records the rejected row or fails the scan, depending on the policy
+    fn reject(&mut self, line: u64, reason: String) {
+        // the line numbers of the reader start after the skipped rows
+        let rejected = RejectedRow {
+            file_name: self.file_name.clone(),
+            line: line + self.options.skip_rows as u64,
+            reason,
+        };
+        if self.options.on_malformed_row == MalformedRowPolicy::Fail {
+            *self.scan_error.lock().unwrap() = Some(anyhow!("{}", rejected));
+            self.failed = true;
+            return;
+        }
+        self.rejected_rows.lock().unwrap().push(rejected);
+    }
+
+    fn create_batch(&self, rows: Vec<Row>) -> RecordBatch {
+        let fields: Vec<Arc<dyn ColumnVector>> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(c, (_, data_type, _))| {
+                let array = ArrowArrayFactory::create(data_type.to_datatype(), rows.len());
+                let mut builder = ArrowVectorBuilder::new(array);
+                for row in &rows {
+                    builder.append(row[c].clone());
+                }
+                builder.build()
+            })
             .collect();
 
         RecordBatch {
@@ -202,17 +581,159 @@ impl CsvReaderIterator {
     }
 }
 
+fn parse_value(value: &str, data_type: &ArrowType) -> Result<Arc<dyn Any>, String> {
+    fn parse<T: FromStr + 'static>(value: &str) -> Result<Arc<dyn Any>, String>
+    where
+        T::Err: Display,
+    {
+        value
+            .parse::<T>()
+            .map(|v| Arc::new(v) as Arc<dyn Any>)
+            .map_err(|e| e.to_string())
+    }
+    match data_type {
+        ArrowType::BooleanType => parse::<bool>(value),
+        ArrowType::Int8Type => parse::<i8>(value),
+        ArrowType::Int16Type => parse::<i16>(value),
+        ArrowType::Int32Type => parse::<i32>(value),
+        ArrowType::Int64Type => parse::<i64>(value),
+        ArrowType::UInt8Type => parse::<u8>(value),
+        ArrowType::UInt16Type => parse::<u16>(value),
+        ArrowType::UInt32Type => parse::<u32>(value),
+        ArrowType::UInt64Type => parse::<u64>(value),
+        ArrowType::FloatType => parse::<f32>(value),
+        ArrowType::DoubleType => parse::<f64>(value),
+        ArrowType::StringType => Ok(Arc::new(value.to_string())),
+    }
+}
+
+/// Dialect of a written CSV file, the default is a comma separated file with a header row,
+/// quotes only around values which need them and nulls written as empty values
+#[derive(Clone, Debug)]
+pub struct CsvWriteOptions {
+    pub has_headers: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    /// text written for null values
+    pub null_value: String,
+    /// None compresses by the extension of the file name, e.g. `.csv.gz`
+    pub compression: Option<Compression>,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> Self {
+        CsvWriteOptions {
+            has_headers: true,
+            delimiter: b',',
+            quote: b'"',
+            null_value: String::new(),
+            compression: None,
+        }
+    }
+}
+
+impl CsvWriteOptions {
+    pub fn with_has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_null_value(mut self, null_value: impl Into<String>) -> Self {
+        self.null_value = null_value.into();
+        self
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+}
+
+/// Writes record batches to a CSV file
+pub struct CsvWriter {
+    writer: Writer<FileWriter>,
+    schema: Arc<Schema>,
+    null_value: String,
+}
+
+impl CsvWriter {
+    pub fn try_new(
+        file_name: &str,
+        schema: Arc<Schema>,
+        options: CsvWriteOptions,
+    ) -> anyhow::Result<Self> {
+        let compression = options
+            .compression
+            .or_else(|| Compression::from_extension(file_name))
+            .unwrap_or(Compression::Uncompressed);
+        let mut writer = WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .from_writer(compression::create_file(file_name, compression)?);
+        if options.has_headers {
+            writer.write_record(schema.fields.iter().map(|f| &f.name))?;
+        }
+        Ok(CsvWriter {
+            writer,
+            schema,
+            null_value: options.null_value,
+        })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        for row in 0..batch.row_count() {
+            let record = self.schema.fields.iter().enumerate().map(|(i, f)| {
+                batch
+                    .field(i)
+                    .get_value(row)
+                    .and_then(|v| RecordBatch::value_to_string(v, &f.data_type))
+                    .unwrap_or_else(|| self.null_value.clone())
+            });
+            self.writer.write_record(record)?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the file and writes the end of the compressed stream
+    pub fn finish(self) -> anyhow::Result<()> {
+        self.writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .finish()?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::data_source::{DataSource, Source};
+    use crate::data_source::compression::{self, Compression};
+    use crate::data_source::csv_data_source::{
+        CsvDataSource, CsvOptions, CsvWriteOptions, CsvWriter, MalformedRowPolicy,
+    };
+    use crate::data_source::{DataSource, LimitHint, Source};
     use crate::datatypes::arrow_types::ArrowType;
     use crate::datatypes::record_batch::RecordBatch;
     use crate::datatypes::schema::{Field, Schema};
+    use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::lit_long;
+    use std::io::Read;
     use std::sync::Arc;
 
     #[test]
     fn test_read_csv_with_header() {
-        let ds = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let ds =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let result = ds.scan(vec![]).next().unwrap();
 
         assert_eq!(ds.schema().fields.len(), 6);
@@ -223,7 +744,13 @@ mod test {
 
     #[test]
     fn test_read_csv_without_header() {
-        let ds = Source::from_csv("testdata/employee_no_header.csv", None, false, 1024);
+        let ds = Source::from_csv(
+            "testdata/employee_no_header.csv",
+            None,
+            CsvOptions::default().with_has_headers(false),
+            1024,
+        )
+        .unwrap();
         let result = ds.scan(vec![]).next().unwrap();
 
         assert_eq!(ds.schema().fields.len(), 6);
@@ -234,7 +761,8 @@ mod test {
 
     #[test]
     fn test_read_csv_projection_with_header() {
-        let ds = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let ds =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let projection = vec!["id", "state", "salary"];
         let result = ds.scan(projection).next().unwrap();
 
@@ -250,7 +778,13 @@ mod test {
 
     #[test]
     fn test_read_csv_projection_without_header() {
-        let ds = Source::from_csv("testdata/employee_no_header.csv", None, false, 1024);
+        let ds = Source::from_csv(
+            "testdata/employee_no_header.csv",
+            None,
+            CsvOptions::default().with_has_headers(false),
+            1024,
+        )
+        .unwrap();
         let projection = vec!["field_0", "field_3", "field_5"];
         let result = ds.scan(projection).next().unwrap();
 
@@ -269,33 +803,45 @@ mod test {
         let field0 = Field {
             name: "id".to_string(),
             data_type: ArrowType::UInt16Type,
+            nullable: true,
         };
         let field1 = Field {
             name: "first_name".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         };
         let field2 = Field {
             name: "last_name".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         };
         let field3 = Field {
             name: "state".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         };
         let field4 = Field {
             name: "job_title".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         };
         let field5 = Field {
             name: "salary".to_string(),
             data_type: ArrowType::Int64Type,
+            nullable: true,
         };
         let fields = vec![field0, field1, field2, field3, field4, field5]
             .into_iter()
             .map(|f| Arc::new(f))
             .collect();
         let schema = Schema { fields };
-        let ds = Source::from_csv("testdata/employee.csv", Some(Arc::from(schema)), true, 1024);
+        let ds = Source::from_csv(
+            "testdata/employee.csv",
+            Some(Arc::from(schema)),
+            CsvOptions::default(),
+            1024,
+        )
+        .unwrap();
         let result = ds.scan(vec![]).next().unwrap();
 
         assert_eq!(ds.schema().fields.len(), 6);
@@ -316,13 +862,233 @@ mod test {
         //println!("{result}")
     }
 
+    #[test]
+    fn test_infer_schema() {
+        let ds = Source::from_csv("testdata/types.csv", None, CsvOptions::default(), 1024).unwrap();
+        let fields: Vec<(String, ArrowType, bool)> = ds
+            .schema()
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), f.data_type.clone(), f.nullable))
+            .collect();
+        assert_eq!(
+            vec![
+                ("flag".to_string(), ArrowType::BooleanType, false),
+                ("count".to_string(), ArrowType::Int64Type, true),
+                ("price".to_string(), ArrowType::DoubleType, false),
+                ("label".to_string(), ArrowType::StringType, false),
+                ("empty".to_string(), ArrowType::StringType, true),
+            ],
+            fields
+        );
+
+        // blank values of nullable columns are read as null, also for strings
+        let result = ds.scan(vec!["count", "empty"]).next().unwrap();
+        assert!(result.fields[0].get_value(1).is_none());
+        assert!(result.fields[1].get_value(0).is_none());
+
+        let sampled =
+            CsvDataSource::infer_schema("testdata/types.csv", &CsvOptions::default(), 1).unwrap();
+        assert!(!sampled.fields[1].nullable);
+    }
+
+    #[test]
+    fn test_read_tsv() {
+        let ds = Source::from_csv("testdata/employee.tsv", None, CsvOptions::tsv(), 1024).unwrap();
+        let result = ds.scan(vec!["job_title", "salary"]).next().unwrap();
+
+        assert_eq!(ds.schema().fields.len(), 6);
+        assert_eq!(ds.schema().fields[5].data_type, ArrowType::Int64Type);
+        assert_eq!(result.row_count(), 3);
+        assert_eq!(
+            "Manager,12000\nDriver,10000\nManager, Software,11500\n",
+            result.to_csv().unwrap()
+        );
+
+        let options = CsvOptions::tsv()
+            .with_has_headers(false)
+            .with_column_names(vec!["id", "first", "last", "state", "job", "salary"]);
+        let ds = Source::from_csv("testdata/employee_no_header.tsv", None, options, 1024).unwrap();
+        let result = ds.scan(vec!["last"]).next().unwrap();
+
+        assert_eq!(ds.schema().fields[1].name, "first");
+        assert_eq!("Hopkins\nLangford\nTravis\n", result.to_csv().unwrap());
+    }
+
+    #[test]
+    fn test_read_csv_dialect() {
+        let options = CsvOptions::default()
+            .with_delimiter(b';')
+            .with_quote(b'\'')
+            .with_escape(b'\\')
+            .with_comment(b'#')
+            .with_skip_rows(1)
+            .with_trim(false);
+        let ds = Source::from_csv("testdata/dialect.csv", None, options.clone(), 1024).unwrap();
+        let result = ds.scan(vec![]).next().unwrap();
+
+        assert_eq!(ds.schema().fields[2].name, "note");
+        assert_eq!("1, Bill ,it's\n2,Gregg,a;b\n", result.to_csv().unwrap());
+
+        let options = options.with_column_names(vec!["a", "b"]);
+        assert!(CsvDataSource::infer_schema("testdata/dialect.csv", &options, 10).is_err());
+    }
+
+    fn malformed(policy: MalformedRowPolicy) -> Arc<Source> {
+        let options = CsvOptions::default().with_on_malformed_row(policy);
+        let schema = CsvDataSource::infer_schema("testdata/malformed.csv", &options, 1)
+            .unwrap()
+            .with_data_type("salary", ArrowType::Int64Type)
+            .unwrap();
+        Source::from_csv("testdata/malformed.csv", Some(Arc::new(schema)), options, 2).unwrap()
+    }
+
+    #[test]
+    fn test_skip_malformed_rows() {
+        let ds = malformed(MalformedRowPolicy::Skip);
+        let result: String = ds.scan(vec![]).map(|b| b.to_csv().unwrap()).collect();
+
+        assert_eq!("1,Bill,12000\n4,Von,11500\n", result);
+        let rejected = ds.take_rejected_rows();
+        assert_eq!(
+            vec![3, 4],
+            rejected.iter().map(|r| r.line).collect::<Vec<_>>()
+        );
+        assert_eq!("expected 3 fields, got 2", rejected[0].reason);
+        assert_eq!(
+            "malformed row at testdata/malformed.csv:4: column 2: invalid Int64Type 'abc': \
+            invalid digit found in string",
+            rejected[1].to_string()
+        );
+        assert!(ds.take_rejected_rows().is_empty());
+
+        // only the columns which are read are converted, the number of fields is always checked
+        assert_eq!(
+            3,
+            ds.scan(vec!["name"]).map(|b| b.row_count()).sum::<usize>()
+        );
+        assert_eq!(1, ds.take_rejected_rows().len());
+    }
+
+    #[test]
+    fn test_null_malformed_values() {
+        let ds = malformed(MalformedRowPolicy::Null);
+        let result: String = ds.scan(vec![]).map(|b| b.to_csv().unwrap()).collect();
+
+        assert_eq!("1,Bill,12000\n2,Gregg,\n3,John,\n4,Von,11500\n", result);
+        assert_eq!(2, ds.take_rejected_rows().len());
+    }
+
+    #[test]
+    fn test_fail_on_malformed_row() {
+        let ds = malformed(MalformedRowPolicy::Fail);
+        assert!(ds.take_scan_error().is_none());
+
+        assert_eq!(0, ds.scan(vec![]).count());
+
+        assert_eq!(
+            "malformed row at testdata/malformed.csv:3: expected 3 fields, got 2",
+            ds.take_scan_error().unwrap().to_string()
+        );
+        assert!(ds.take_rejected_rows().is_empty());
+        assert!(ds.take_scan_error().is_none());
+    }
+
+    #[test]
+    fn test_missing_file_fails_scan() {
+        let schema =
+            CsvDataSource::infer_schema("testdata/employee.csv", &CsvOptions::default(), 10)
+                .unwrap();
+        let ds = Source::from_csv(
+            "testdata/missing.csv",
+            Some(Arc::new(schema)),
+            CsvOptions::default(),
+            1024,
+        )
+        .unwrap();
+
+        assert_eq!(0, ds.scan(vec![]).count());
+
+        assert!(ds
+            .take_scan_error()
+            .unwrap()
+            .to_string()
+            .starts_with("failed to open testdata/missing.csv: "));
+        assert!(
+            CsvDataSource::infer_schema("testdata/missing.csv", &CsvOptions::default(), 1).is_err()
+        );
+    }
+
+    #[test]
+    fn test_unknown_projected_column_fails_scan() {
+        let ds =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+
+        assert_eq!(0, ds.scan(vec!["id", "nosuch"]).count());
+
+        assert_eq!(
+            "cannot project [\"id\", \"nosuch\"] from testdata/employee.csv: Field name not found",
+            ds.take_scan_error().unwrap().to_string()
+        );
+    }
+
     #[test]
     fn test_read_csv_with_small_batch_size() {
-        let ds = Source::from_csv("testdata/employee.csv", None, true, 1);
+        let ds = Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1).unwrap();
 
         let batches: Vec<RecordBatch> = ds.scan(vec![]).collect();
 
         assert_eq!(batches.len(), 4);
         assert_eq!(batches.first().unwrap().row_count(), 1)
     }
+
+    #[test]
+    fn test_stop_reading_at_limit() {
+        let ds =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+
+        let row_counts = |limit| -> Vec<usize> {
+            ds.scan_with_limit(vec!["id"], &[], limit)
+                .map(|batch| batch.row_count())
+                .collect()
+        };
+        assert_eq!(vec![3], row_counts(LimitHint::Exact(3)));
+        // only the first batch is limited by a soft hint
+        assert_eq!(vec![1, 3], row_counts(LimitHint::Soft(1)));
+
+        // filtered rows are read with a soft hint, the first row does not match
+        let filters = [col("id").gt(lit_long(1))];
+        let batches: Vec<RecordBatch> = ds
+            .scan_with_limit(vec!["id"], &filters, LimitHint::Exact(1))
+            .collect();
+        assert_eq!(2, batches.len());
+        assert_eq!("2\n3\n4\n", batches[1].to_csv().unwrap());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let path = std::env::temp_dir().join("rs_query_write_malformed.csv.gz");
+        let file_name = path.to_str().unwrap();
+        let ds = malformed(MalformedRowPolicy::Null);
+        let options = CsvWriteOptions::default()
+            .with_delimiter(b';')
+            .with_null_value("NULL");
+        let mut writer = CsvWriter::try_new(file_name, ds.schema(), options).unwrap();
+        for batch in ds.scan(vec![]) {
+            writer.write(&batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        assert_eq!(Compression::Gzip, Compression::detect(file_name).unwrap());
+        let mut content = String::new();
+        compression::open_file(file_name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(
+            "id;name;salary\n1;Bill;12000\n2;Gregg;NULL\n3;John;NULL\n4;Von;11500\n",
+            content
+        );
+        std::fs::remove_file(path).unwrap();
+    }
 }
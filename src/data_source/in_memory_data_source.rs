@@ -1,6 +1,8 @@
-use crate::data_source::DataSource;
+use crate::data_source::statistics::Statistics;
+use crate::data_source::{scan_and_filter, DataSource, FilterPushDown};
 use crate::datatypes::record_batch::RecordBatch;
 use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::Expr;
 use std::sync::Arc;
 
 pub struct InMemoryDataSource {
@@ -36,4 +38,21 @@ impl DataSource for InMemoryDataSource {
             fields: projection_indices.iter().map(|i| batch.field(*i)).collect(),
         }))
     }
+
+    fn supports_filter(&self, _filter: &Arc<Expr>) -> FilterPushDown {
+        FilterPushDown::Exact
+    }
+
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        scan_and_filter(self, projection, filters, None)
+    }
+
+    /// Exact statistics computed from the data
+    fn statistics(&self) -> Statistics {
+        Statistics::compute(&self.schema, &self.data)
+    }
 }
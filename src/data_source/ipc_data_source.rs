@@ -0,0 +1,319 @@
+use crate::data_source::compression::{self, Compression};
+use crate::data_source::statistics::Statistics;
+use crate::data_source::{scan_and_filter, DataSource, FilterPushDown};
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::Expr;
+use anyhow::{anyhow, bail};
+use arrow::datatypes::{Field as ArrowField, Schema as ArrowSchema};
+use arrow::error::ArrowError;
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use arrow::record_batch::RecordBatch as ArrowRecordBatch;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Magic bytes at the start of an Arrow IPC file, streams start with a schema message instead
+const IPC_FILE_MAGIC: &[u8; 6] = b"ARROW1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpcFormat {
+    /// random access file format with a footer, usually `.arrow` or `.feather`
+    File,
+    /// streaming format without footer, usually `.arrows`
+    Stream,
+}
+
+/// Arrow IPC file or stream, the format is detected from the content of the file
+pub struct IpcDataSource {
+    pub file_name: String,
+    pub schema: Arc<Schema>,
+    pub format: IpcFormat,
+    /// schema as stored in the file, used to resolve the columns to decode
+    file_schema: Arc<ArrowSchema>,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+}
+
+impl DataSource for IpcDataSource {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        info!("scan() projection={}", projection.concat());
+
+        let read_schema = if projection.is_empty() {
+            self.schema.clone()
+        } else {
+            Arc::from(self.schema.select(projection.clone()).unwrap_or_else(|e| {
+                panic!(
+                    "cannot project {:?} from {}: {}",
+                    projection, self.file_name, e
+                )
+            }))
+        };
+
+        // only decode the columns which are part of the projection
+        let indices: Vec<usize> = read_schema
+            .fields
+            .iter()
+            .map(|f| self.file_schema.index_of(&f.name).unwrap())
+            .collect();
+        // a file which can't be opened anymore fails the scan like a batch which can't be read
+        let batches = match Self::open_reader(&self.file_name, self.format, Some(indices)) {
+            Ok(batches) => batches,
+            Err(e) => {
+                *self.scan_error.lock().unwrap() = Some(e);
+                return Box::new(std::iter::empty());
+            }
+        };
+
+        Box::new(
+            batches
+                .map(move |batch| {
+                    batch
+                        .map_err(anyhow::Error::from)
+                        .and_then(|batch| RecordBatch::from_arrow(read_schema.clone(), &batch))
+                })
+                .map_while(|batch| match batch {
+                    Ok(batch) => Some(batch),
+                    Err(e) => {
+                        *self.scan_error.lock().unwrap() =
+                            Some(anyhow!("failed to read {}: {}", self.file_name, e));
+                        None
+                    }
+                }),
+        )
+    }
+
+    fn supports_filter(&self, _filter: &Arc<Expr>) -> FilterPushDown {
+        FilterPushDown::Exact
+    }
+
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        scan_and_filter(self, projection, filters, None)
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::from_file(&self.file_name)
+    }
+
+    fn location(&self) -> Option<String> {
+        Some(self.file_name.clone())
+    }
+
+    /// File which couldn't be opened or a batch which couldn't be read, which failed a scan
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        self.scan_error.lock().unwrap().take()
+    }
+}
+
+impl IpcDataSource {
+    /// Fails if the file can't be opened or is not a valid Arrow IPC file or stream
+    pub fn try_new(file_name: impl Into<String>) -> anyhow::Result<Self> {
+        let file_name = file_name.into();
+        let format = Self::detect_format(&file_name)?;
+        let file_schema = match format {
+            IpcFormat::File => {
+                FileReader::try_new(Self::open_file(&file_name)?, None).map(|r| r.schema())
+            }
+            IpcFormat::Stream => {
+                StreamReader::try_new(Self::open_stream(&file_name)?, None).map(|r| r.schema())
+            }
+        }
+        .map_err(|e| anyhow!("not a valid arrow ipc file {}: {}", file_name, e))?;
+        let schema = Arc::new(Schema::from_arrow(&Schema::to_supported(&file_schema)));
+        Ok(IpcDataSource {
+            file_name,
+            schema,
+            format,
+            file_schema,
+            scan_error: Default::default(),
+        })
+    }
+
+    /// The file format needs random access to read its footer, so only streams can be compressed
+    fn open_file(file_name: &str) -> anyhow::Result<BufReader<File>> {
+        if !matches!(
+            Compression::detect(file_name),
+            Ok(Compression::Uncompressed)
+        ) {
+            bail!(
+                "compressed arrow ipc files are not supported, use the stream format: {}",
+                file_name
+            );
+        }
+        let file =
+            File::open(file_name).map_err(|e| anyhow!("failed to open {}: {}", file_name, e))?;
+        Ok(BufReader::new(file))
+    }
+
+    fn open_stream(file_name: &str) -> anyhow::Result<Box<dyn BufRead>> {
+        compression::open_file(file_name)
+            .map_err(|e| anyhow!("failed to open {}: {}", file_name, e))
+    }
+
+    fn detect_format(file_name: &str) -> anyhow::Result<IpcFormat> {
+        let mut magic = [0; 6];
+        Ok(match Self::open_stream(file_name)?.read_exact(&mut magic) {
+            Ok(_) if &magic == IPC_FILE_MAGIC => IpcFormat::File,
+            _ => IpcFormat::Stream,
+        })
+    }
+
+    fn open_reader(
+        file_name: &str,
+        format: IpcFormat,
+        projection: Option<Vec<usize>>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = Result<ArrowRecordBatch, ArrowError>>>> {
+        let reader: Result<Box<dyn Iterator<Item = _>>, ArrowError> = match format {
+            IpcFormat::File => FileReader::try_new(Self::open_file(file_name)?, projection)
+                .map(|r| Box::new(r) as Box<dyn Iterator<Item = _>>),
+            IpcFormat::Stream => StreamReader::try_new(Self::open_stream(file_name)?, projection)
+                .map(|r| Box::new(r) as Box<dyn Iterator<Item = _>>),
+        };
+        reader.map_err(|e| anyhow!("failed to read {}: {}", file_name, e))
+    }
+}
+
+enum IpcWriterImpl {
+    File(FileWriter<BufWriter<File>>),
+    Stream(StreamWriter<BufWriter<File>>),
+}
+
+/// Writes record batches to an Arrow IPC file or stream
+pub struct IpcWriter {
+    writer: IpcWriterImpl,
+}
+
+impl IpcWriter {
+    pub fn try_new(file_name: &str, schema: &Schema, format: IpcFormat) -> anyhow::Result<Self> {
+        // nullability is only known from samples (e.g. of inferred CSV schemas), so all columns
+        // are written as nullable
+        let fields: Vec<ArrowField> = schema
+            .to_arrow()
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone().with_nullable(true))
+            .collect();
+        let arrow_schema = ArrowSchema::new(fields);
+        let file = BufWriter::new(File::create(file_name)?);
+        let writer = match format {
+            IpcFormat::File => IpcWriterImpl::File(FileWriter::try_new(file, &arrow_schema)?),
+            IpcFormat::Stream => IpcWriterImpl::Stream(StreamWriter::try_new(file, &arrow_schema)?),
+        };
+        Ok(IpcWriter { writer })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        let batch = batch.to_arrow()?;
+        match &mut self.writer {
+            IpcWriterImpl::File(w) => w.write(&batch)?,
+            IpcWriterImpl::Stream(w) => w.write(&batch)?,
+        }
+        Ok(())
+    }
+
+    /// Writes the footer of files or the end of stream marker
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        match &mut self.writer {
+            IpcWriterImpl::File(w) => w.finish()?,
+            IpcWriterImpl::Stream(w) => w.finish()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::{CsvDataSource, CsvOptions};
+    use crate::data_source::ipc_data_source::{IpcDataSource, IpcFormat, IpcWriter};
+    use crate::data_source::DataSource;
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::datatypes::record_batch::RecordBatch;
+
+    fn write_employees(file_name: &str, format: IpcFormat) {
+        let csv = CsvDataSource::try_new("testdata/employee.csv", None, CsvOptions::default(), 2)
+            .unwrap();
+        let mut writer = IpcWriter::try_new(file_name, &csv.schema(), format).unwrap();
+        for batch in csv.scan(vec![]) {
+            writer.write(&batch).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_ipc_roundtrip() {
+        for (format, extension) in [(IpcFormat::File, "arrow"), (IpcFormat::Stream, "arrows")] {
+            let path = std::env::temp_dir().join(format!(
+                "rs_query_employee_{}.{}",
+                std::process::id(),
+                extension
+            ));
+            let file_name = path.to_str().unwrap();
+            write_employees(file_name, format);
+
+            let ds = IpcDataSource::try_new(file_name).unwrap();
+            assert_eq!(ds.format, format);
+            assert_eq!(ds.schema.fields.len(), 6);
+            assert_eq!(ds.schema.fields[0].data_type, ArrowType::Int64Type);
+
+            let batches: Vec<RecordBatch> = ds.scan(vec!["last_name", "id"]).collect();
+            assert_eq!(batches.len(), 2);
+            assert_eq!(batches[0].schema.fields[0].name, "last_name");
+            assert_eq!("Hopkins,1\nLangford,2\n", batches[0].to_csv().unwrap());
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_ipc_errors() {
+        let error = |file_name| IpcDataSource::try_new(file_name).err().unwrap().to_string();
+        assert!(error("testdata/missing.arrow").starts_with("failed to open "));
+        assert!(error("testdata/employee.csv").starts_with("not a valid arrow ipc file "));
+
+        // the file format needs random access, so it can't be read compressed
+        let path =
+            std::env::temp_dir().join(format!("rs_query_compressed_{}.arrow", std::process::id()));
+        write_employees(path.to_str().unwrap(), IpcFormat::File);
+        let compressed = format!("{}.gz", path.to_str().unwrap());
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&compressed).unwrap(),
+            flate2::Compression::default(),
+        );
+        std::io::copy(&mut std::fs::File::open(&path).unwrap(), &mut encoder).unwrap();
+        encoder.finish().unwrap();
+        let compressed_error = error(&compressed);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&compressed).unwrap();
+        assert!(
+            compressed_error.starts_with("compressed arrow ipc files are not supported"),
+            "{}",
+            compressed_error
+        );
+    }
+
+    #[test]
+    fn test_ipc_file_removed_before_scan() {
+        let path =
+            std::env::temp_dir().join(format!("rs_query_removed_{}.arrows", std::process::id()));
+        let file_name = path.to_str().unwrap();
+        write_employees(file_name, IpcFormat::Stream);
+        let ds = IpcDataSource::try_new(file_name).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(0, ds.scan(vec!["id"]).count());
+        assert!(ds
+            .take_scan_error()
+            .unwrap()
+            .to_string()
+            .starts_with("failed to open "));
+        assert!(ds.take_scan_error().is_none());
+    }
+}
@@ -0,0 +1,604 @@
+use crate::data_source::compression;
+use crate::data_source::statistics::Statistics;
+use crate::data_source::{scan_and_filter, DataSource, FilterPushDown};
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::{Field, Schema};
+use crate::logical_plan::expressions::Expr;
+use anyhow::{anyhow, bail};
+use serde::de::{DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::io::{BufRead, Lines};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Number of lines read to infer the column types when no schema is provided
+pub const DEFAULT_INFER_SCHEMA_LINES: usize = 100;
+
+/// Newline delimited JSON file with one object per line. Nested objects are flattened into
+/// columns with dotted names like `user.name`, arrays are read as JSON strings.
+pub struct JsonDataSource {
+    pub file_name: String,
+    pub schema: Arc<Schema>,
+    batch_size: usize,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+}
+
+impl DataSource for JsonDataSource {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        info!("scan() projection={}", projection.concat());
+
+        // a file which can't be opened fails the scan like an invalid line
+        let file = match Self::open_file(&self.file_name) {
+            Ok(file) => file,
+            Err(e) => {
+                *self.scan_error.lock().unwrap() = Some(e);
+                return Box::new(std::iter::empty());
+            }
+        };
+
+        let read_schema = if projection.is_empty() {
+            self.schema.clone()
+        } else {
+            Arc::from(self.schema.select(projection.clone()).unwrap_or_else(|e| {
+                panic!(
+                    "cannot project {:?} from {}: {}",
+                    projection, self.file_name, e
+                )
+            }))
+        };
+
+        Box::new(JsonReaderIterator {
+            file_name: self.file_name.clone(),
+            paths: PathTree::from_schema(&read_schema),
+            read_schema,
+            batch_size: self.batch_size,
+            lines: file.lines(),
+            line: 0,
+            scan_error: self.scan_error.clone(),
+            failed: false,
+        })
+    }
+
+    fn supports_filter(&self, _filter: &Arc<Expr>) -> FilterPushDown {
+        FilterPushDown::Exact
+    }
+
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        scan_and_filter(self, projection, filters, None)
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::from_file(&self.file_name)
+    }
+
+    fn location(&self) -> Option<String> {
+        Some(self.file_name.clone())
+    }
+
+    /// File which couldn't be opened or read, invalid JSON or a value which doesn't match the
+    /// column type, which failed a scan
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        self.scan_error.lock().unwrap().take()
+    }
+}
+
+impl JsonDataSource {
+    /// Fails if no schema is given and it can't be inferred, e.g. because the file is missing
+    pub fn try_new(
+        file_name: impl Into<String>,
+        schema: Option<Arc<Schema>>,
+        batch_size: usize,
+    ) -> anyhow::Result<Self> {
+        let file_name = file_name.into();
+        let schema = match schema {
+            Some(schema) => schema,
+            None => Arc::new(Self::infer_schema(&file_name, DEFAULT_INFER_SCHEMA_LINES)?),
+        };
+        Ok(JsonDataSource {
+            file_name,
+            schema,
+            batch_size,
+            scan_error: Default::default(),
+        })
+    }
+
+    fn open_file(file_name: &str) -> anyhow::Result<Box<dyn BufRead>> {
+        compression::open_file(file_name)
+            .map_err(|e| anyhow!("failed to open {}: {}", file_name, e))
+    }
+
+    /// Infers the columns from the first `max_lines` lines in the order they first appear. A
+    /// column is Boolean, Int64, Double or String if all its values are of that type, integers
+    /// mixed with floats are Double, anything else is String. Columns which are null or missing
+    /// in any line are nullable.
+    pub fn infer_schema(file_name: &str, max_lines: usize) -> anyhow::Result<Schema> {
+        let mut names: Vec<String> = vec![];
+        let mut columns: HashMap<String, JsonColumnInference> = HashMap::new();
+        let mut object_count = 0;
+
+        let lines = Self::open_file(file_name)?.lines().enumerate();
+        for (index, line) in lines.filter(|(_, l)| !matches!(l, Ok(l) if l.trim().is_empty())) {
+            if object_count == max_lines {
+                break;
+            }
+            let line = line?;
+            let value: Value = serde_json::from_str(&line)
+                .map_err(|e| anyhow!("invalid JSON at {}:{}: {}", file_name, index + 1, e))?;
+            let Value::Object(object) = value else {
+                bail!("expected a JSON object at {}:{}", file_name, index + 1);
+            };
+            object_count += 1;
+
+            let mut values = vec![];
+            flatten(None, &object, &mut values);
+            for (name, value) in values {
+                let column = columns.entry(name.clone()).or_insert_with(|| {
+                    names.push(name);
+                    JsonColumnInference::default()
+                });
+                column.update(value);
+            }
+        }
+
+        let fields = names
+            .into_iter()
+            .map(|name| {
+                let column = &columns[&name];
+                Arc::new(Field {
+                    data_type: column.data_type(),
+                    nullable: column.nullable || column.count < object_count,
+                    name,
+                })
+            })
+            .collect();
+        Ok(Schema { fields })
+    }
+}
+
+/// Collects the non object values with their dotted names
+fn flatten<'a>(
+    prefix: Option<&str>,
+    object: &'a Map<String, Value>,
+    out: &mut Vec<(String, &'a Value)>,
+) {
+    for (key, value) in object {
+        let name = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.clone(),
+        };
+        match value {
+            Value::Object(nested) => flatten(Some(&name), nested, out),
+            _ => out.push((name, value)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct JsonColumnInference {
+    boolean: bool,
+    int64: bool,
+    double: bool,
+    other: bool,
+    nullable: bool,
+    /// number of lines the column is present in
+    count: usize,
+}
+
+impl JsonColumnInference {
+    fn update(&mut self, value: &Value) {
+        self.count += 1;
+        match value {
+            Value::Null => self.nullable = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(n) if n.is_i64() => self.int64 = true,
+            Value::Number(_) => self.double = true,
+            _ => self.other = true,
+        }
+    }
+
+    fn data_type(&self) -> ArrowType {
+        match (self.boolean, self.int64, self.double, self.other) {
+            (true, false, false, false) => ArrowType::BooleanType,
+            (false, true, false, false) => ArrowType::Int64Type,
+            (false, _, true, false) => ArrowType::DoubleType,
+            _ => ArrowType::StringType,
+        }
+    }
+}
+
+/// Dotted column names as tree of object keys, so that parsing only descends into the nested
+/// objects which contain a column of the projection
+#[derive(Default)]
+struct PathTree {
+    column: Option<usize>,
+    children: HashMap<String, PathTree>,
+}
+
+impl PathTree {
+    fn from_schema(schema: &Schema) -> Self {
+        let mut root = PathTree::default();
+        for (index, field) in schema.fields.iter().enumerate() {
+            let node = field.name.split('.').fold(&mut root, |node, key| {
+                node.children.entry(key.to_string()).or_default()
+            });
+            node.column = Some(index);
+        }
+        root
+    }
+}
+
+/// Deserializes a JSON value into the columns of the tree, skipping everything else
+struct PathSeed<'a> {
+    tree: &'a PathTree,
+    row: &'a mut Vec<Option<Value>>,
+    root: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for PathSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl PathSeed<'_> {
+    fn set<E: Error>(self, value: Value) -> Result<(), E> {
+        if self.root {
+            return Err(E::custom("expected a JSON object"));
+        }
+        if let Some(column) = self.tree.column {
+            self.row[column] = Some(value);
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Visitor<'de> for PathSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "a JSON value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<(), E> {
+        self.set(Value::Bool(v))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<(), E> {
+        self.set(Value::from(v))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<(), E> {
+        self.set(Value::from(v))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<(), E> {
+        self.set(Value::from(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<(), E> {
+        self.set(Value::from(v))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<(), E> {
+        self.set(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        if self.tree.column.is_none() {
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            return self.set(Value::Null);
+        }
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        self.set(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        // a nested object in place of a leaf column is read as JSON string like an array
+        if self.tree.children.is_empty() && self.tree.column.is_some() {
+            let mut object = Map::new();
+            while let Some((key, value)) = map.next_entry()? {
+                object.insert(key, value);
+            }
+            return self.set(Value::Object(object));
+        }
+        while let Some(key) = map.next_key::<String>()? {
+            match self.tree.children.get(&key) {
+                Some(tree) => map.next_value_seed(PathSeed {
+                    tree,
+                    row: self.row,
+                    root: false,
+                })?,
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct JsonReaderIterator {
+    file_name: String,
+    read_schema: Arc<Schema>,
+    paths: PathTree,
+    batch_size: usize,
+    lines: Lines<Box<dyn BufRead>>,
+    line: usize,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+    /// set once a line failed the scan, no more lines are read
+    failed: bool,
+}
+
+type Row = Vec<Option<Arc<dyn Any>>>;
+
+impl Iterator for JsonReaderIterator {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut rows = Vec::with_capacity(self.batch_size);
+        while rows.len() < self.batch_size && !self.failed {
+            let Some(line) = self.lines.next() else {
+                break;
+            };
+            self.line += 1;
+            let row = line
+                .map_err(|e| anyhow!("failed to read {}: {}", self.file_name, e))
+                .and_then(|line| self.parse_line(&line));
+            match row {
+                Ok(Some(row)) => rows.push(row),
+                Ok(None) => {}
+                Err(e) => {
+                    *self.scan_error.lock().unwrap() = Some(e);
+                    self.failed = true;
+                }
+            }
+        }
+        if self.failed {
+            return None;
+        }
+
+        if rows.is_empty() {
+            None
+        } else {
+            Some(self.create_batch(rows))
+        }
+    }
+}
+
+impl JsonReaderIterator {
+    /// Converts the values of the columns which are read, None for a blank line
+    fn parse_line(&self, line: &str) -> anyhow::Result<Option<Row>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let mut row = vec![None; self.read_schema.fields.len()];
+        let seed = PathSeed {
+            tree: &self.paths,
+            row: &mut row,
+            root: true,
+        };
+        let mut deserializer = serde_json::Deserializer::from_str(line);
+        seed.deserialize(&mut deserializer)
+            .and_then(|_| deserializer.end())
+            .map_err(|e| anyhow!("invalid JSON at {}:{}: {}", self.file_name, self.line, e))?;
+
+        let row = self
+            .read_schema
+            .fields
+            .iter()
+            .zip(row)
+            .map(|(field, value)| match value {
+                Some(value) => convert(&value, &field.data_type).ok_or_else(|| {
+                    anyhow!(
+                        "invalid {:?} value {} for column {} at {}:{}",
+                        field.data_type,
+                        value,
+                        field.name,
+                        self.file_name,
+                        self.line
+                    )
+                }),
+                None => Ok(None),
+            })
+            .collect::<anyhow::Result<Row>>()?;
+        Ok(Some(row))
+    }
+
+    fn create_batch(&self, rows: Vec<Row>) -> RecordBatch {
+        let fields = self
+            .read_schema
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(c, field)| {
+                let array = ArrowArrayFactory::create(field.data_type.to_datatype(), rows.len());
+                let mut builder = ArrowVectorBuilder::new(array);
+                for row in &rows {
+                    builder.append(row[c].clone());
+                }
+                builder.build()
+            })
+            .collect();
+
+        RecordBatch {
+            schema: self.read_schema.clone(),
+            fields,
+        }
+    }
+}
+
+/// Converts a JSON value to the column type, Some(None) for null and None if not convertible
+fn convert(value: &Value, data_type: &ArrowType) -> Option<Option<Arc<dyn Any>>> {
+    fn int<T: TryFrom<i64> + TryFrom<u64> + 'static>(value: &Value) -> Option<Arc<dyn Any>> {
+        let v: T = match value {
+            Value::Number(n) if n.is_i64() => T::try_from(n.as_i64()?).ok()?,
+            Value::Number(n) => T::try_from(n.as_u64()?).ok()?,
+            _ => return None,
+        };
+        Some(Arc::new(v))
+    }
+    if value.is_null() {
+        return Some(None);
+    }
+    let converted: Option<Arc<dyn Any>> = match data_type {
+        ArrowType::BooleanType => value.as_bool().map(|v| Arc::new(v) as Arc<dyn Any>),
+        ArrowType::Int8Type => int::<i8>(value),
+        ArrowType::Int16Type => int::<i16>(value),
+        ArrowType::Int32Type => int::<i32>(value),
+        ArrowType::Int64Type => int::<i64>(value),
+        ArrowType::UInt8Type => int::<u8>(value),
+        ArrowType::UInt16Type => int::<u16>(value),
+        ArrowType::UInt32Type => int::<u32>(value),
+        ArrowType::UInt64Type => int::<u64>(value),
+        ArrowType::FloatType => value.as_f64().map(|v| Arc::new(v as f32) as Arc<dyn Any>),
+        ArrowType::DoubleType => value.as_f64().map(|v| Arc::new(v) as Arc<dyn Any>),
+        ArrowType::StringType => Some(match value {
+            Value::String(s) => Arc::new(s.clone()),
+            other => Arc::new(other.to_string()),
+        }),
+    };
+    converted.map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::json_data_source::JsonDataSource;
+    use crate::data_source::{DataSource, Source};
+    use crate::datatypes::arrow_types::ArrowType;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_infer_schema() {
+        let ds = Source::from_json("testdata/events.ndjson", None, 1024).unwrap();
+        let fields: Vec<(String, ArrowType, bool)> = ds
+            .schema()
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), f.data_type.clone(), f.nullable))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("id".to_string(), ArrowType::Int64Type, false),
+                ("type".to_string(), ArrowType::StringType, false),
+                ("user.name".to_string(), ArrowType::StringType, false),
+                ("user.age".to_string(), ArrowType::Int64Type, true),
+                ("value".to_string(), ArrowType::DoubleType, true),
+                ("ok".to_string(), ArrowType::BooleanType, true),
+                ("tags".to_string(), ArrowType::StringType, true),
+                (
+                    "user.address.state".to_string(),
+                    ArrowType::StringType,
+                    true
+                ),
+            ],
+            fields
+        );
+
+        let sampled = JsonDataSource::infer_schema("testdata/events.ndjson", 1).unwrap();
+        assert_eq!(6, sampled.fields.len());
+    }
+
+    #[test]
+    fn test_scan_nested_projection() {
+        let ds = Source::from_json("testdata/events.ndjson", None, 2).unwrap();
+        let batches: Vec<String> = ds
+            .scan(vec!["user.address.state", "tags", "value", "user.name"])
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+
+        assert_eq!(
+            vec![",,1.5,Bill\n,[\"a\",\"b\"],2,Gregg\n", "CO,,,John\n"],
+            batches
+        );
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let ds = JsonDataSource::try_new(
+            "testdata/employee.csv",
+            Some(
+                Source::from_json("testdata/events.ndjson", None, 1)
+                    .unwrap()
+                    .schema(),
+            ),
+            1024,
+        )
+        .unwrap();
+        assert_eq!(0, ds.scan(vec![]).count());
+        let error = ds.take_scan_error().unwrap().to_string();
+        assert!(
+            error.starts_with("invalid JSON at testdata/employee.csv:1"),
+            "{}",
+            error
+        );
+        assert!(ds.take_scan_error().is_none());
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let file = std::env::temp_dir().join(format!(
+            "rs_query_invalid_value_{}.ndjson",
+            std::process::id()
+        ));
+        std::fs::write(&file, "{\"id\":1}\n\n{\"id\":\"x\"}\n{\"id\":3}\n").unwrap();
+        // the type is inferred from the first line only
+        let schema = JsonDataSource::infer_schema(file.to_str().unwrap(), 1).unwrap();
+        let ds =
+            JsonDataSource::try_new(file.to_str().unwrap(), Some(Arc::new(schema)), 1).unwrap();
+
+        let batches: Vec<String> = ds.scan(vec![]).map(|b| b.to_csv().unwrap()).collect();
+        let error = ds.take_scan_error().unwrap().to_string();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(vec!["1\n"], batches);
+        assert_eq!(
+            format!(
+                "invalid Int64Type value \"x\" for column id at {}:3",
+                file.to_str().unwrap()
+            ),
+            error
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let ds = JsonDataSource::try_new(
+            "testdata/missing.ndjson",
+            Some(
+                Source::from_json("testdata/events.ndjson", None, 1)
+                    .unwrap()
+                    .schema(),
+            ),
+            1024,
+        )
+        .unwrap();
+        assert_eq!(0, ds.scan(vec![]).count());
+        let error = ds.take_scan_error().unwrap().to_string();
+        assert!(
+            error.starts_with("failed to open testdata/missing.ndjson"),
+            "{}",
+            error
+        );
+        assert!(JsonDataSource::infer_schema("testdata/missing.ndjson", 10).is_err());
+    }
+}
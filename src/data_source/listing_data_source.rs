@@ -0,0 +1,671 @@
+use crate::data_source::compression::Compression;
+use crate::data_source::csv_data_source::{CsvDataSource, CsvOptions, RejectedRow};
+use crate::data_source::json_data_source::JsonDataSource;
+use crate::data_source::parquet_data_source::ParquetDataSource;
+use crate::data_source::statistics::Statistics;
+use crate::data_source::{filter_columns, DataSource, FilterPushDown, LimitHint, Source};
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+use crate::datatypes::column_vector::ColumnVector;
+use crate::datatypes::literal_value_vector::LiteralValueVector;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::{Field, Schema};
+use crate::logical_plan::expressions::Expr;
+use crate::logical_plan::scan::Scan;
+use crate::query_planner::QueryPlanner;
+use anyhow::{anyhow, bail};
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::info;
+
+/// Format of the files of a listing table
+#[derive(Clone, Debug)]
+pub enum ListingFormat {
+    Csv(CsvOptions),
+    Json,
+    Parquet,
+    Ipc,
+}
+
+impl ListingFormat {
+    /// Format of a file by its extension, ignoring compression extensions like `.gz`. Files
+    /// without a known extension are read as CSV
+    pub fn from_extension(file_name: &str, csv_options: CsvOptions) -> Self {
+        match Compression::strip_extension(file_name)
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+        {
+            Some("parquet") => ListingFormat::Parquet,
+            Some("json" | "ndjson" | "jsonl") => ListingFormat::Json,
+            Some("arrow" | "arrows" | "ipc" | "feather") => ListingFormat::Ipc,
+            _ => ListingFormat::Csv(csv_options),
+        }
+    }
+}
+
+/// File of a listing table with the values of the partition columns taken from its path
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionedFile {
+    pub path: String,
+    pub partition_values: Vec<String>,
+}
+
+/// Table made of all files in a directory or matching a glob pattern like `sales/*/*.csv`.
+/// The files share one schema, which is read from the first file. Directories named
+/// `key=value` add the partition column `key` with the value of the directory to each row.
+pub struct ListingDataSource {
+    pub path: String,
+    /// columns of the files followed by the partition columns
+    pub schema: Arc<Schema>,
+    pub partition_columns: Vec<Arc<Field>>,
+    pub files: Vec<PartitionedFile>,
+    format: ListingFormat,
+    file_schema: Arc<Schema>,
+    batch_size: usize,
+    rejected_rows: Arc<Mutex<Vec<RejectedRow>>>,
+    scan_error: Mutex<Option<anyhow::Error>>,
+    /// source of each file, created when the file is scanned for the first time
+    sources: Vec<OnceLock<Arc<Source>>>,
+}
+
+impl DataSource for ListingDataSource {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan_with_filters(projection, &[])
+    }
+
+    /// Filters on partition columns are applied exactly by skipping the files of partitions not
+    /// matching them, filters on the columns of the files are passed to the scans of the files
+    fn supports_filter(&self, filter: &Arc<Expr>) -> FilterPushDown {
+        let columns = filter_columns(filter, self.schema.clone());
+        if columns.iter().all(|c| self.is_partition_column(c)) {
+            FilterPushDown::Exact
+        } else if columns.iter().any(|c| self.is_partition_column(c)) {
+            FilterPushDown::Unsupported
+        } else {
+            match self.format {
+                ListingFormat::Parquet => ParquetDataSource::filter_push_down(filter),
+                _ => FilterPushDown::Exact,
+            }
+        }
+    }
+
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan_files(projection, filters, None)
+    }
+
+    /// The limit is passed to the scans of the files, each of which needs at most as many rows
+    fn scan_with_limit(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+        limit: LimitHint,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan_files(projection, filters, Some(limit))
+    }
+
+    /// Total size of the files
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            byte_size: self
+                .files
+                .iter()
+                .map(|file| Statistics::from_file(&file.path).byte_size)
+                .sum(),
+            ..Default::default()
+        }
+    }
+
+    fn location(&self) -> Option<String> {
+        Some(self.path.clone())
+    }
+
+    /// Rows rejected by the scans of CSV files since the last call
+    fn take_rejected_rows(&self) -> Vec<RejectedRow> {
+        std::mem::take(&mut *self.rejected_rows.lock().unwrap())
+    }
+
+    /// Error which failed the scan of one of the files
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        self.scan_error.lock().unwrap().take()
+    }
+}
+
+impl ListingDataSource {
+    fn scan_files(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+        limit: Option<LimitHint>,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        info!("scan() projection={}", projection.concat());
+
+        let read_schema = if projection.is_empty() {
+            self.schema.clone()
+        } else {
+            Arc::from(self.schema.select(projection.clone()).unwrap_or_else(|e| {
+                panic!("cannot project {:?} from {}: {}", projection, self.path, e)
+            }))
+        };
+
+        let (partition_filters, file_filters): (Vec<Arc<Expr>>, Vec<Arc<Expr>>) =
+            filters.iter().cloned().partition(|filter| {
+                filter_columns(filter, self.schema.clone())
+                    .iter()
+                    .all(|c| self.is_partition_column(c))
+            });
+        let files = self.pruned_file_indices(&partition_filters);
+        Box::new(
+            files
+                .into_iter()
+                // a failed scan of a file ends the scan of the table
+                .take_while(|_| self.scan_error.lock().unwrap().is_none())
+                .flat_map(move |index| {
+                    self.scan_file(index, read_schema.clone(), file_filters.clone(), limit)
+                }),
+        )
+    }
+    pub fn try_new(
+        path: impl Into<String>,
+        format: ListingFormat,
+        infer_schema_rows: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<Self> {
+        let path = path.into();
+        let (root, paths) = list_files(&path)?;
+        let first = paths
+            .first()
+            .ok_or_else(|| anyhow!("no files found: {}", path))?;
+
+        let mut partition_names: Option<Vec<String>> = None;
+        let mut files = vec![];
+        for file in &paths {
+            let (names, values) = partition_values(&root, file);
+            match &partition_names {
+                Some(expected) if *expected != names => bail!(
+                    "partition columns of {} differ from {:?}",
+                    file.display(),
+                    expected
+                ),
+                Some(_) => {}
+                None => partition_names = Some(names),
+            }
+            files.push(PartitionedFile {
+                path: file.to_string_lossy().to_string(),
+                partition_values: values,
+            });
+        }
+
+        let file_schema = Arc::new(Self::read_schema(
+            &files[0].path,
+            &format,
+            infer_schema_rows,
+        )?);
+        let partition_columns: Vec<Arc<Field>> = partition_names
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                // values which are all integers become Int64 columns, like inferred CSV columns
+                let is_long = files
+                    .iter()
+                    .all(|f| f.partition_values[i].parse::<i64>().is_ok());
+                let data_type = match is_long {
+                    true => ArrowType::Int64Type,
+                    false => ArrowType::StringType,
+                };
+                Arc::new(Field {
+                    name,
+                    data_type,
+                    nullable: false,
+                })
+            })
+            .collect();
+        if let Some(duplicate) = partition_columns
+            .iter()
+            .find(|p| file_schema.fields.iter().any(|f| f.name == p.name))
+        {
+            bail!(
+                "partition column {} is also a column of {}",
+                duplicate.name,
+                first.display()
+            );
+        }
+
+        let fields = file_schema
+            .fields
+            .iter()
+            .chain(partition_columns.iter())
+            .cloned()
+            .collect();
+        Ok(ListingDataSource {
+            path,
+            schema: Arc::new(Schema { fields }),
+            partition_columns,
+            format,
+            file_schema,
+            batch_size,
+            rejected_rows: Arc::new(Mutex::new(vec![])),
+            scan_error: Mutex::new(None),
+            sources: files.iter().map(|_| OnceLock::new()).collect(),
+            files,
+        })
+    }
+
+    pub fn is_partition_column(&self, name: &str) -> bool {
+        self.partition_columns.iter().any(|p| p.name == name)
+    }
+
+    /// Files of the partitions which match all filters on partition columns
+    pub fn pruned_files(&self, partition_filters: &[Arc<Expr>]) -> Vec<PartitionedFile> {
+        self.pruned_file_indices(partition_filters)
+            .into_iter()
+            .map(|i| self.files[i].clone())
+            .collect()
+    }
+
+    fn pruned_file_indices(&self, partition_filters: &[Arc<Expr>]) -> Vec<usize> {
+        if partition_filters.is_empty() {
+            return (0..self.files.len()).collect();
+        }
+
+        // evaluate the filters on a batch with one row of partition values per file
+        let schema = Arc::new(Schema {
+            fields: self.partition_columns.clone(),
+        });
+        let fields = self
+            .partition_columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let array =
+                    ArrowArrayFactory::create(column.data_type.to_datatype(), self.files.len());
+                let mut vector = ArrowVectorBuilder::new(array);
+                for file in &self.files {
+                    vector.append(Some(partition_value(
+                        &file.partition_values[i],
+                        &column.data_type,
+                    )));
+                }
+                vector.build()
+            })
+            .collect();
+        let batch = RecordBatch {
+            schema: schema.clone(),
+            fields,
+        };
+        let input = Scan::new(
+            self.path.clone(),
+            Source::from_in_memory(schema, vec![]),
+            vec![],
+        );
+        let results: Vec<Arc<dyn ColumnVector>> = partition_filters
+            .iter()
+            .map(|filter| QueryPlanner::create_physical_expr(filter.clone(), input.as_ref()))
+            .map(|filter| filter.evaluate(&batch))
+            .collect();
+
+        (0..self.files.len())
+            .filter(|i| {
+                results.iter().all(|result| {
+                    result
+                        .get_value(*i)
+                        .and_then(|v| v.downcast_ref::<bool>().cloned())
+                        .unwrap_or(false)
+                })
+            })
+            .collect()
+    }
+
+    fn read_schema(
+        file_name: &str,
+        format: &ListingFormat,
+        infer_schema_rows: usize,
+    ) -> anyhow::Result<Schema> {
+        Ok(match format {
+            ListingFormat::Csv(options) => {
+                CsvDataSource::infer_schema(file_name, options, infer_schema_rows)?
+            }
+            ListingFormat::Json => JsonDataSource::infer_schema(file_name, infer_schema_rows)?,
+            ListingFormat::Parquet => Source::from_parquet(file_name, 1)?
+                .schema()
+                .as_ref()
+                .clone(),
+            ListingFormat::Ipc => Source::from_ipc(file_name)?.schema().as_ref().clone(),
+        })
+    }
+
+    /// Streams the batches of a file with the partition columns added, the rejected rows and
+    /// the error of the file's source are taken once its batches are exhausted
+    fn scan_file(
+        &self,
+        index: usize,
+        read_schema: Arc<Schema>,
+        filters: Vec<Arc<Expr>>,
+        limit: Option<LimitHint>,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        let file = &self.files[index];
+        let source = match self.sources[index].get() {
+            Some(source) => source,
+            None => match self.create_source(file) {
+                Ok(source) => self.sources[index].get_or_init(|| source),
+                Err(e) => {
+                    *self.scan_error.lock().unwrap() = Some(e);
+                    return Box::new(std::iter::empty());
+                }
+            },
+        };
+
+        // partition columns are not stored in the files, an empty projection reads all columns
+        let file_projection: Vec<&str> = read_schema
+            .fields
+            .iter()
+            .filter(|f| !self.is_partition_column(&f.name))
+            .map(|f| f.name.as_str())
+            .collect();
+        let batches = match limit {
+            Some(limit) => source.scan_with_limit(file_projection, &filters, limit),
+            None => source.scan_with_filters(file_projection, &filters),
+        };
+        let batches = batches.map(move |batch| {
+            let fields = read_schema
+                .fields
+                .iter()
+                .map(|f| match self.partition_index(&f.name) {
+                    Some(i) => Arc::new(LiteralValueVector {
+                        arrow_type: f.data_type.clone(),
+                        value: Some(partition_value(&file.partition_values[i], &f.data_type)),
+                        size: batch.row_count(),
+                    }) as Arc<dyn ColumnVector>,
+                    None => {
+                        let i = batch
+                            .schema
+                            .fields
+                            .iter()
+                            .position(|c| c.name == f.name)
+                            .unwrap();
+                        batch.field(i)
+                    }
+                })
+                .collect();
+            RecordBatch {
+                schema: read_schema.clone(),
+                fields,
+            }
+        });
+        let finish = std::iter::from_fn(move || {
+            self.rejected_rows
+                .lock()
+                .unwrap()
+                .extend(source.take_rejected_rows());
+            if let Some(error) = source.take_scan_error() {
+                *self.scan_error.lock().unwrap() = Some(error);
+            }
+            None
+        });
+        Box::new(batches.chain(finish))
+    }
+
+    fn create_source(&self, file: &PartitionedFile) -> anyhow::Result<Arc<Source>> {
+        Ok(match &self.format {
+            ListingFormat::Csv(options) => Source::from_csv(
+                file.path.clone(),
+                Some(self.file_schema.clone()),
+                options.clone(),
+                self.batch_size,
+            )?,
+            ListingFormat::Json => Source::from_json(
+                file.path.clone(),
+                Some(self.file_schema.clone()),
+                self.batch_size,
+            )?,
+            ListingFormat::Parquet => Source::from_parquet(file.path.clone(), self.batch_size)?,
+            ListingFormat::Ipc => Source::from_ipc(file.path.clone())?,
+        })
+    }
+
+    fn partition_index(&self, name: &str) -> Option<usize> {
+        self.partition_columns.iter().position(|p| p.name == name)
+    }
+}
+
+fn partition_value(value: &str, data_type: &ArrowType) -> Arc<dyn Any> {
+    match data_type {
+        ArrowType::Int64Type => Arc::new(value.parse::<i64>().unwrap()),
+        _ => Arc::new(value.to_string()),
+    }
+}
+
+/// Names and values of the `key=value` directories between `root` and the file
+fn partition_values(root: &Path, file: &Path) -> (Vec<String>, Vec<String>) {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    relative
+        .parent()
+        .into_iter()
+        .flat_map(|dir| dir.components())
+        .filter_map(|c| {
+            let (key, value) = c.as_os_str().to_str()?.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .unzip()
+}
+
+/// Returns the directory partitions are resolved against and the sorted files of `path`,
+/// which is a file, a directory that is listed recursively, or a glob pattern
+fn list_files(path: &str) -> anyhow::Result<(PathBuf, Vec<PathBuf>)> {
+    let path_buf = PathBuf::from(path);
+    if !path.contains(['*', '?']) {
+        if path_buf.is_file() {
+            let root = path_buf.parent().map(Path::to_path_buf).unwrap_or_default();
+            return Ok((root, vec![path_buf]));
+        }
+        if !path_buf.is_dir() {
+            bail!("path not found: {}", path);
+        }
+        let mut files = vec![];
+        walk(&path_buf, &mut files)?;
+        files.sort();
+        return Ok((path_buf, files));
+    }
+
+    let components: Vec<String> = path_buf
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let split = components
+        .iter()
+        .position(|c| c.contains(['*', '?']))
+        .unwrap();
+    let root: PathBuf = components[..split].iter().collect();
+    let mut files = vec![];
+    expand(&root, &components[split..], &mut files)?;
+    files.sort();
+    Ok((root, files))
+}
+
+/// Data files below `dir`, hidden files and files starting with `_` (e.g. `_SUCCESS`) are skipped
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_string_lossy();
+        if name.starts_with(['.', '_']) {
+            continue;
+        }
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn expand(dir: &Path, patterns: &[String], files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let dir = match dir.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => dir,
+    };
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.starts_with(['.', '_']) || !matches_pattern(&patterns[0], &name) {
+            continue;
+        }
+        let path = path
+            .strip_prefix("./")
+            .map(Path::to_path_buf)
+            .unwrap_or(path);
+        match (patterns.len(), path.is_dir()) {
+            (1, false) => files.push(path),
+            (1, true) => {}
+            (_, true) => expand(&path, &patterns[1..], files)?,
+            (_, false) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Matches a single path segment, `*` matches any characters and `?` exactly one
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+            }
+            (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::{CsvOptions, MalformedRowPolicy};
+    use crate::data_source::listing_data_source::{
+        matches_pattern, ListingDataSource, ListingFormat,
+    };
+    use crate::data_source::{DataSource, FilterPushDown, LimitHint};
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::{lit_double, lit_long, lit_str};
+
+    fn sales(path: &str) -> ListingDataSource {
+        let format = ListingFormat::Csv(CsvOptions::default());
+        ListingDataSource::try_new(path, format, 100, 1024).unwrap()
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*.csv", "part-0.csv"));
+        assert!(matches_pattern("year=202?", "year=2024"));
+        assert!(matches_pattern("*", ""));
+        assert!(!matches_pattern("*.csv", "part-0.json"));
+        assert!(!matches_pattern("year=202?", "year=20245"));
+    }
+
+    #[test]
+    fn test_listing_schema_and_scan() {
+        let ds = sales("testdata/sales");
+        assert_eq!(ds.files.len(), 3);
+        let names: Vec<&str> = ds.schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(vec!["id", "product", "amount", "year", "region"], names);
+        assert_eq!(ds.schema.fields[3].data_type, ArrowType::Int64Type);
+        assert_eq!(ds.schema.fields[4].data_type, ArrowType::StringType);
+
+        let csv: String = ds
+            .scan(vec!["region", "id"])
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+        assert_eq!("eu,1\neu,2\nus,3\neu,4\neu,5\n", csv);
+    }
+
+    #[test]
+    fn test_listing_glob() {
+        let ds = sales("testdata/sales/year=2023/*/*.csv");
+        assert_eq!(ds.files.len(), 2);
+        assert_eq!(
+            ds.files[1].path,
+            "testdata/sales/year=2023/region=us/part-0.csv"
+        );
+        assert_eq!(ds.partition_columns.len(), 1);
+        assert_eq!(ds.partition_columns[0].name, "region");
+    }
+
+    #[test]
+    fn test_partition_pruning() {
+        let ds = sales("testdata/sales");
+        let year = col("year").eq(lit_long(2023));
+        let region = col("region").eq(lit_str("eu"));
+        let amount = col("amount").gt(lit_double(100.0));
+        assert_eq!(ds.supports_filter(&year), FilterPushDown::Exact);
+        assert_eq!(ds.supports_filter(&amount), FilterPushDown::Exact);
+        assert_eq!(
+            ds.supports_filter(&col("year").eq(col("id"))),
+            FilterPushDown::Unsupported
+        );
+
+        let files = ds.pruned_files(&[year.clone(), region.clone()]);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].partition_values, vec!["2023", "eu"]);
+        let csv: String = ds
+            .scan_with_filters(vec!["id"], &[year, region, amount])
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+        assert_eq!("1\n", csv);
+    }
+
+    #[test]
+    fn test_failed_file_scan_ends_listing_scan() {
+        let options = CsvOptions::default().with_on_malformed_row(MalformedRowPolicy::Fail);
+        let format = ListingFormat::Csv(options);
+        let ds = ListingDataSource::try_new("testdata/malformed.csv", format, 100, 1024).unwrap();
+
+        assert_eq!(0, ds.scan(vec![]).count());
+
+        let error = ds.take_scan_error().unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("malformed row at testdata/malformed.csv:3"));
+        assert!(ds.take_scan_error().is_none());
+    }
+
+    #[test]
+    fn test_batches_are_streamed_before_a_file_fails() {
+        let options = CsvOptions::default().with_on_malformed_row(MalformedRowPolicy::Fail);
+        let format = ListingFormat::Csv(options);
+        let ds = ListingDataSource::try_new("testdata/malformed.csv", format, 100, 1).unwrap();
+
+        let mut batches = ds.scan(vec!["id"]);
+        assert_eq!("1\n", batches.next().unwrap().to_csv().unwrap());
+        // the error of the file is taken once its batches are exhausted
+        assert!(ds.take_scan_error().is_none());
+        assert!(batches.next().is_none());
+        assert!(ds.take_scan_error().is_some());
+    }
+
+    #[test]
+    fn test_limit_is_passed_to_files() {
+        let ds = sales("testdata/sales");
+        let batches: Vec<String> = ds
+            .scan_with_limit(vec!["id", "year"], &[], LimitHint::Exact(1))
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+
+        assert_eq!(vec!["1,2023\n", "3,2023\n", "4,2024\n"], batches);
+    }
+}
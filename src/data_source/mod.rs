@@ -1,13 +1,67 @@
+pub mod compression;
 pub mod csv_data_source;
 pub mod in_memory_data_source;
+pub mod ipc_data_source;
+pub mod json_data_source;
+pub mod listing_data_source;
+pub mod parquet_data_source;
+pub mod statistics;
 
-use crate::data_source::csv_data_source::CsvDataSource;
+use crate::data_source::csv_data_source::{CsvDataSource, CsvOptions, RejectedRow};
 use crate::data_source::in_memory_data_source::InMemoryDataSource;
+use crate::data_source::ipc_data_source::IpcDataSource;
+use crate::data_source::json_data_source::JsonDataSource;
+use crate::data_source::listing_data_source::{ListingDataSource, ListingFormat};
+use crate::data_source::parquet_data_source::ParquetDataSource;
+use crate::data_source::statistics::Statistics;
 use crate::datatypes::record_batch::RecordBatch;
 use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::binary_expr::and;
+use crate::logical_plan::expressions::Expr;
+use crate::logical_plan::scan::Scan;
+use crate::optimizer::extract_columns;
+use crate::physical_plan::selection_exec::filter;
+use crate::query_planner::QueryPlanner;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
 use std::sync::Arc;
 
-// todo maybe i can remove Source enum of enum here since i switched to Boxed dyn iterator
+/// How a data source applies a filter pushed down into its scan
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterPushDown {
+    /// the filter is ignored and has to be applied after the scan
+    Unsupported,
+    /// the scan may skip rows not matching the filter, but still has to be filtered afterwards
+    Inexact,
+    /// the scan only returns rows matching the filter
+    Exact,
+}
+
+/// Number of rows the consumer of a scan needs, pushed down from a limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitHint {
+    /// the scan may stop after producing this many rows
+    Exact(usize),
+    /// the rows are filtered after the scan, so all rows may be needed, but probably this many
+    Soft(usize),
+}
+
+impl LimitHint {
+    pub fn rows(&self) -> usize {
+        match self {
+            LimitHint::Exact(rows) | LimitHint::Soft(rows) => *rows,
+        }
+    }
+}
+
+impl Display for LimitHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitHint::Exact(rows) => write!(f, "{}", rows),
+            LimitHint::Soft(rows) => write!(f, "{} (soft)", rows),
+        }
+    }
+}
 
 pub trait DataSource {
     /// Return the schema for the underlying data source
@@ -15,40 +69,264 @@ pub trait DataSource {
 
     /// Scan the data source, selecting the specified columns
     fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_>;
+
+    /// How `scan_with_filters` applies the filter, which refers to columns by name
+    fn supports_filter(&self, _filter: &Arc<Expr>) -> FilterPushDown {
+        FilterPushDown::Unsupported
+    }
+
+    /// Scan the data source, selecting the specified columns of the rows matching all filters
+    /// as far as the filters are supported
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        _filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan(projection)
+    }
+
+    /// Scan like `scan_with_filters` for a consumer needing only some of the rows, so the source
+    /// can stop reading early. The limit is applied again after the scan.
+    fn scan_with_limit(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+        _limit: LimitHint,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan_with_filters(projection, filters)
+    }
+
+    /// Statistics the source knows without scanning its data, unknown by default
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    /// File or directory the source reads, which identifies its cached statistics
+    fn location(&self) -> Option<String> {
+        None
+    }
+
+    /// Rows rejected by the scans of the source since the last call
+    fn take_rejected_rows(&self) -> Vec<RejectedRow> {
+        vec![]
+    }
+
+    /// Error which ended a scan of the source early since the last call. Scans can't return
+    /// errors, so a failing scan stores its error and ends, and the caller turns it into an
+    /// `Err` after the scan.
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        None
+    }
+}
+
+/// Creates the data source of a table stored at a location in a custom format, so tables of
+/// formats the engine does not know can be registered by name
+pub trait TableProvider {
+    fn create(
+        &self,
+        location: &str,
+        options: &HashMap<String, String>,
+    ) -> anyhow::Result<Arc<dyn DataSource>>;
+}
+
+/// Names of the columns used by a filter
+pub(crate) fn filter_columns(filter: &Arc<Expr>, schema: Arc<Schema>) -> HashSet<String> {
+    let input = Scan::new("filter", Source::from_in_memory(schema, vec![]), vec![]);
+    let mut columns = HashSet::new();
+    extract_columns(filter.clone(), input, &mut columns);
+    columns
+}
+
+/// Scans the projected columns and the columns used by the filters, and removes the rows not
+/// matching all filters, for sources which apply filters exactly after reading the rows. The
+/// limit of the filtered rows is passed to the unfiltered scan as a soft hint.
+pub(crate) fn scan_and_filter<'a>(
+    source: &'a dyn DataSource,
+    projection: Vec<&str>,
+    filters: &[Arc<Expr>],
+    limit: Option<LimitHint>,
+) -> Box<dyn Iterator<Item = RecordBatch> + 'a> {
+    let Some(predicate) = filters.iter().cloned().reduce(and) else {
+        return source.scan(projection);
+    };
+
+    let schema = source.schema();
+    let output_schema = match projection.is_empty() {
+        true => schema.clone(),
+        false => Arc::new(schema.select(projection.clone()).unwrap_or_else(|e| {
+            panic!(
+                "cannot project {:?} from {}: {}",
+                projection,
+                source
+                    .location()
+                    .unwrap_or_else(|| "in-memory source".to_string()),
+                e
+            )
+        })),
+    };
+    let filter_columns = filter_columns(&predicate, schema.clone());
+    let mut read_columns: Vec<String> = output_schema
+        .fields
+        .iter()
+        .map(|f| f.name.clone())
+        .collect();
+    let mut filter_columns: Vec<String> = filter_columns
+        .into_iter()
+        .filter(|name| !read_columns.contains(name))
+        .collect();
+    filter_columns.sort();
+    read_columns.extend(filter_columns);
+
+    let input = Scan::new(
+        "filter",
+        Source::from_in_memory(schema, vec![]),
+        read_columns.clone(),
+    );
+    let predicate = QueryPlanner::create_physical_expr(predicate, input.as_ref());
+    let read_columns = read_columns.iter().map(String::as_str).collect();
+    let batches = match limit {
+        Some(limit) => source.scan_with_limit(read_columns, &[], LimitHint::Soft(limit.rows())),
+        None => source.scan(read_columns),
+    };
+    Box::new(batches.map(move |batch| {
+        let selection = predicate.evaluate(&batch);
+        // the output columns are the first of the read columns
+        let fields = (0..output_schema.fields.len())
+            .map(|i| filter(batch.field(i), selection.clone()))
+            .collect();
+        RecordBatch {
+            schema: output_schema.clone(),
+            fields,
+        }
+    }))
 }
 
 pub enum Source {
     CSV(CsvDataSource),
     InMemory(InMemoryDataSource),
+    Parquet(ParquetDataSource),
+    Json(JsonDataSource),
+    Ipc(IpcDataSource),
+    Listing(ListingDataSource),
 }
 
-impl Source {
-    pub fn schema(&self) -> Arc<Schema> {
+impl DataSource for Source {
+    fn schema(&self) -> Arc<Schema> {
         match self {
             Source::CSV(s) => s.schema.clone(),
             Source::InMemory(s) => s.schema.clone(),
+            Source::Parquet(s) => s.schema.clone(),
+            Source::Json(s) => s.schema.clone(),
+            Source::Ipc(s) => s.schema.clone(),
+            Source::Listing(s) => s.schema.clone(),
+        }
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        match self {
+            Source::CSV(s) => s.scan(projection),
+            Source::InMemory(s) => s.scan(projection),
+            Source::Parquet(s) => s.scan(projection),
+            Source::Json(s) => s.scan(projection),
+            Source::Ipc(s) => s.scan(projection),
+            Source::Listing(s) => s.scan(projection),
         }
     }
 
-    pub fn scan<'a>(
-        &'a self,
-        projection: Vec<&'a str>,
-    ) -> Box<dyn Iterator<Item = RecordBatch> + 'a> {
+    fn supports_filter(&self, filter: &Arc<Expr>) -> FilterPushDown {
         match self {
-            Source::CSV(s) => Box::new(s.scan(projection)),
-            Source::InMemory(s) => Box::new(s.scan(projection)),
+            Source::CSV(s) => s.supports_filter(filter),
+            Source::InMemory(s) => s.supports_filter(filter),
+            Source::Parquet(s) => s.supports_filter(filter),
+            Source::Json(s) => s.supports_filter(filter),
+            Source::Ipc(s) => s.supports_filter(filter),
+            Source::Listing(s) => s.supports_filter(filter),
         }
     }
 
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        match self {
+            Source::CSV(s) => s.scan_with_filters(projection, filters),
+            Source::InMemory(s) => s.scan_with_filters(projection, filters),
+            Source::Parquet(s) => s.scan_with_filters(projection, filters),
+            Source::Json(s) => s.scan_with_filters(projection, filters),
+            Source::Ipc(s) => s.scan_with_filters(projection, filters),
+            Source::Listing(s) => s.scan_with_filters(projection, filters),
+        }
+    }
+
+    fn scan_with_limit(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+        limit: LimitHint,
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        match self {
+            Source::CSV(s) => s.scan_with_limit(projection, filters, limit),
+            Source::InMemory(s) => s.scan_with_limit(projection, filters, limit),
+            Source::Parquet(s) => s.scan_with_limit(projection, filters, limit),
+            Source::Json(s) => s.scan_with_limit(projection, filters, limit),
+            Source::Ipc(s) => s.scan_with_limit(projection, filters, limit),
+            Source::Listing(s) => s.scan_with_limit(projection, filters, limit),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        match self {
+            Source::CSV(s) => s.statistics(),
+            Source::InMemory(s) => s.statistics(),
+            Source::Parquet(s) => s.statistics(),
+            Source::Json(s) => s.statistics(),
+            Source::Ipc(s) => s.statistics(),
+            Source::Listing(s) => s.statistics(),
+        }
+    }
+
+    fn location(&self) -> Option<String> {
+        match self {
+            Source::CSV(s) => s.location(),
+            Source::InMemory(s) => s.location(),
+            Source::Parquet(s) => s.location(),
+            Source::Json(s) => s.location(),
+            Source::Ipc(s) => s.location(),
+            Source::Listing(s) => s.location(),
+        }
+    }
+
+    fn take_rejected_rows(&self) -> Vec<RejectedRow> {
+        match self {
+            Source::CSV(s) => s.take_rejected_rows(),
+            Source::Listing(s) => s.take_rejected_rows(),
+            _ => vec![],
+        }
+    }
+
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        match self {
+            Source::CSV(s) => s.take_scan_error(),
+            Source::Json(s) => s.take_scan_error(),
+            Source::Parquet(s) => s.take_scan_error(),
+            Source::Ipc(s) => s.take_scan_error(),
+            Source::Listing(s) => s.take_scan_error(),
+            _ => None,
+        }
+    }
+}
+
+impl Source {
     // Associated function to create a CSV source
     pub fn from_csv(
         file_name: impl Into<String>,
         schema: Option<Arc<Schema>>,
-        has_headers: bool,
+        options: CsvOptions,
         batch_size: usize,
-    ) -> Arc<Self> {
-        let ds = CsvDataSource::new(file_name, schema, has_headers, batch_size);
-        Arc::from(Source::CSV(ds))
+    ) -> anyhow::Result<Arc<Self>> {
+        let ds = CsvDataSource::try_new(file_name, schema, options, batch_size)?;
+        Ok(Arc::from(Source::CSV(ds)))
     }
 
     // Associated function to create an InMemory source
@@ -56,4 +334,40 @@ impl Source {
         let ds = InMemoryDataSource::new(schema, data);
         Arc::from(Source::InMemory(ds))
     }
+
+    // Associated function to create a Parquet source
+    pub fn from_parquet(
+        file_name: impl Into<String>,
+        batch_size: usize,
+    ) -> anyhow::Result<Arc<Self>> {
+        let ds = ParquetDataSource::try_new(file_name, batch_size)?;
+        Ok(Arc::from(Source::Parquet(ds)))
+    }
+
+    // Associated function to create a newline delimited JSON source
+    pub fn from_json(
+        file_name: impl Into<String>,
+        schema: Option<Arc<Schema>>,
+        batch_size: usize,
+    ) -> anyhow::Result<Arc<Self>> {
+        let ds = JsonDataSource::try_new(file_name, schema, batch_size)?;
+        Ok(Arc::from(Source::Json(ds)))
+    }
+
+    // Associated function to create an Arrow IPC file or stream source
+    pub fn from_ipc(file_name: impl Into<String>) -> anyhow::Result<Arc<Self>> {
+        let ds = IpcDataSource::try_new(file_name)?;
+        Ok(Arc::from(Source::Ipc(ds)))
+    }
+
+    // Associated function to create a source of all files in a directory or matching a glob
+    pub fn from_listing(
+        path: impl Into<String>,
+        format: ListingFormat,
+        infer_schema_rows: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<Arc<Self>> {
+        let ds = ListingDataSource::try_new(path, format, infer_schema_rows, batch_size)?;
+        Ok(Arc::from(Source::Listing(ds)))
+    }
 }
@@ -0,0 +1,796 @@
+use crate::data_source::compression::Compression;
+use crate::data_source::statistics::{bound, ColumnStatistics, Statistics, StatisticsValue};
+use crate::data_source::{DataSource, FilterPushDown};
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::{Field, Schema};
+use crate::logical_plan::expressions::binary_expr::{Base, BinaryExpr};
+use crate::logical_plan::expressions::literal_expr::LiteralExpr;
+use crate::logical_plan::expressions::Expr;
+use anyhow::{anyhow, bail};
+use arrow::array::UInt32Array;
+use arrow::compute::take_record_batch;
+use arrow::datatypes::{Field as ArrowField, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch as ArrowRecordBatch;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::basic::{Compression as ParquetCompression, GzipLevel, ZstdLevel};
+use parquet::data_type::ByteArray;
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::properties::{WriterProperties, DEFAULT_MAX_ROW_GROUP_SIZE};
+use parquet::file::statistics::Statistics as ParquetStatistics;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+pub struct ParquetDataSource {
+    pub file_name: String,
+    pub schema: Arc<Schema>,
+    /// schema as stored in the file, used to resolve the columns to decode
+    file_schema: Arc<ArrowSchema>,
+    batch_size: usize,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+}
+
+impl DataSource for ParquetDataSource {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        self.scan_with_filters(projection, &[])
+    }
+
+    fn supports_filter(&self, filter: &Arc<Expr>) -> FilterPushDown {
+        Self::filter_push_down(filter)
+    }
+
+    /// Row groups are skipped if the statistics show that no row matches a filter, the rows of
+    /// the remaining row groups are not filtered
+    fn scan_with_filters(
+        &self,
+        projection: Vec<&str>,
+        filters: &[Arc<Expr>],
+    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        info!("scan() projection={}", projection.concat());
+
+        let read_schema = if projection.is_empty() {
+            self.schema.clone()
+        } else {
+            Arc::from(self.schema.select(projection.clone()).unwrap_or_else(|e| {
+                panic!(
+                    "cannot project {:?} from {}: {}",
+                    projection, self.file_name, e
+                )
+            }))
+        };
+
+        // only decode the columns which are part of the projection
+        let indices: Vec<usize> = read_schema
+            .fields
+            .iter()
+            .map(|f| self.file_schema.index_of(&f.name).unwrap())
+            .collect();
+        // a file which can't be opened anymore fails the scan
+        let builder = match Self::open_reader_builder(&self.file_name) {
+            Ok(builder) => builder,
+            Err(e) => {
+                *self.scan_error.lock().unwrap() = Some(e);
+                return Box::new(std::iter::empty());
+            }
+        };
+        let comparisons: Vec<ColumnComparison> = filters
+            .iter()
+            .filter_map(|filter| ColumnComparison::try_new(filter))
+            .collect();
+        let row_groups: Vec<usize> = builder
+            .metadata()
+            .row_groups()
+            .iter()
+            .enumerate()
+            .filter(|(_, row_group)| comparisons.iter().all(|c| c.may_match(row_group)))
+            .map(|(i, _)| i)
+            .collect();
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        let reader = builder
+            .with_projection(mask)
+            .with_row_groups(row_groups)
+            .with_batch_size(self.batch_size)
+            .build();
+        let reader = match reader {
+            Ok(reader) => reader,
+            Err(e) => {
+                *self.scan_error.lock().unwrap() =
+                    Some(anyhow!("failed to read {}: {}", self.file_name, e));
+                return Box::new(std::iter::empty());
+            }
+        };
+
+        Box::new(ParquetReaderIterator {
+            file_name: self.file_name.clone(),
+            read_schema,
+            reader,
+            scan_error: self.scan_error.clone(),
+            failed: false,
+        })
+    }
+
+    /// Row count, null counts and bounds of the columns from the footer of the file, unknown if
+    /// the file can't be read anymore
+    fn statistics(&self) -> Statistics {
+        let Ok(builder) = Self::open_reader_builder(&self.file_name) else {
+            return Statistics::default();
+        };
+        let row_groups = builder.metadata().row_groups();
+        let columns = self
+            .schema
+            .fields
+            .iter()
+            .map(|field| {
+                let chunks: Vec<Option<&ParquetStatistics>> = row_groups
+                    .iter()
+                    .map(|row_group| {
+                        row_group
+                            .columns()
+                            .iter()
+                            .find(|c| c.column_descr().name() == field.name)
+                            .and_then(|c| c.statistics())
+                    })
+                    .collect();
+                Self::column_statistics(&chunks)
+            })
+            .collect();
+        Statistics {
+            row_count: Some(builder.metadata().file_metadata().num_rows() as usize),
+            byte_size: Statistics::from_file(&self.file_name).byte_size,
+            columns,
+            is_exact: true,
+        }
+    }
+
+    fn location(&self) -> Option<String> {
+        Some(self.file_name.clone())
+    }
+
+    /// File which couldn't be opened or a batch which couldn't be read, which failed a scan
+    fn take_scan_error(&self) -> Option<anyhow::Error> {
+        self.scan_error.lock().unwrap().take()
+    }
+}
+
+impl ParquetDataSource {
+    /// Fails if the file can't be opened or has a column of a type without a counterpart in
+    /// `ArrowType`
+    pub fn try_new(file_name: impl Into<String>, batch_size: usize) -> anyhow::Result<Self> {
+        let file_name = file_name.into();
+        let file_schema = Self::open_reader_builder(&file_name)?.schema().clone();
+        let schema =
+            Schema::try_from_arrow(&file_schema).map_err(|e| anyhow!("{} in {}", e, file_name))?;
+        Ok(ParquetDataSource {
+            file_name,
+            schema: Arc::new(schema),
+            file_schema,
+            batch_size,
+            scan_error: Default::default(),
+        })
+    }
+
+    /// Comparisons of a column with a literal can skip row groups by their statistics, other
+    /// filters are not supported
+    pub fn filter_push_down(filter: &Arc<Expr>) -> FilterPushDown {
+        match ColumnComparison::try_new(filter) {
+            Some(_) => FilterPushDown::Inexact,
+            None => FilterPushDown::Unsupported,
+        }
+    }
+
+    /// Combines the statistics of the column chunks of all row groups, a bound or count is only
+    /// known if it is known for every row group
+    fn column_statistics(chunks: &[Option<&ParquetStatistics>]) -> ColumnStatistics {
+        let mut column = ColumnStatistics {
+            null_count: Some(0),
+            ..Default::default()
+        };
+        let mut bounds = Some((None, None));
+        for chunk in chunks {
+            let Some(statistics) = chunk else {
+                return ColumnStatistics::default();
+            };
+            column.null_count = column
+                .null_count
+                .zip(statistics.null_count_opt())
+                .map(|(a, b)| a + b as usize);
+            bounds = match (bounds, ColumnComparison::min_max(statistics)) {
+                (Some((min, max)), (Some(chunk_min), Some(chunk_max))) => Some((
+                    Some(bound(min, chunk_min, Ordering::Less)),
+                    Some(bound(max, chunk_max, Ordering::Greater)),
+                )),
+                _ => None,
+            };
+        }
+        if let Some((min, max)) = bounds {
+            column.min = min;
+            column.max = max;
+        }
+        // distinct counts of several row groups can not be combined
+        if let [Some(statistics)] = chunks {
+            column.distinct_count = statistics.distinct_count_opt().map(|n| n as usize);
+        }
+        column
+    }
+
+    /// Parquet files are read by random access to their footer and column chunks, which are
+    /// compressed by parquet itself, so compressed parquet files are not supported
+    fn open_reader_builder(
+        file_name: &str,
+    ) -> anyhow::Result<ParquetRecordBatchReaderBuilder<File>> {
+        if Compression::from_extension(file_name).is_some() {
+            bail!(
+                "compressed parquet files are not supported, parquet compresses its pages: {}",
+                file_name
+            );
+        }
+        let file =
+            File::open(file_name).map_err(|e| anyhow!("failed to open {}: {}", file_name, e))?;
+        ParquetRecordBatchReaderBuilder::try_new(file)
+            .map_err(|e| anyhow!("not a valid parquet file {}: {}", file_name, e))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ComparisonOp {
+    Eq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// Filter of the form `column <op> literal`
+struct ColumnComparison {
+    column: String,
+    op: ComparisonOp,
+    value: StatisticsValue,
+}
+
+impl ColumnComparison {
+    fn try_new(filter: &Expr) -> Option<Self> {
+        let Expr::Binary(bin) = filter else {
+            return None;
+        };
+        let op = match bin {
+            BinaryExpr::Eq(_) => ComparisonOp::Eq,
+            BinaryExpr::Lt(_) => ComparisonOp::Lt,
+            BinaryExpr::LtEq(_) => ComparisonOp::LtEq,
+            BinaryExpr::Gt(_) => ComparisonOp::Gt,
+            BinaryExpr::GtEq(_) => ComparisonOp::GtEq,
+            _ => return None,
+        };
+        let (left, right) = (bin.get_left(), bin.get_right());
+        match (left.as_ref(), Self::literal(&right)) {
+            (Expr::Column(col), Some(value)) => Some(ColumnComparison {
+                column: col.name.clone(),
+                op,
+                value,
+            }),
+            // `literal <op> column` is turned around
+            _ => match (Self::literal(&left), right.as_ref()) {
+                (Some(value), Expr::Column(col)) => Some(ColumnComparison {
+                    column: col.name.clone(),
+                    op: match op {
+                        ComparisonOp::Eq => ComparisonOp::Eq,
+                        ComparisonOp::Lt => ComparisonOp::Gt,
+                        ComparisonOp::LtEq => ComparisonOp::GtEq,
+                        ComparisonOp::Gt => ComparisonOp::Lt,
+                        ComparisonOp::GtEq => ComparisonOp::LtEq,
+                    },
+                    value,
+                }),
+                _ => None,
+            },
+        }
+    }
+
+    fn literal(expr: &Expr) -> Option<StatisticsValue> {
+        match expr {
+            Expr::Literal(LiteralExpr::LiteralLong(l)) => Some(StatisticsValue::Number(l.i as f64)),
+            Expr::Literal(LiteralExpr::LiteralFloat(f)) => {
+                Some(StatisticsValue::Number(f.i as f64))
+            }
+            Expr::Literal(LiteralExpr::LiteralDouble(d)) => Some(StatisticsValue::Number(d.i)),
+            Expr::Literal(LiteralExpr::LiteralString(s)) => {
+                Some(StatisticsValue::Text(s.str.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// False if the statistics of the row group show that no row matches, row groups without
+    /// statistics for the column may always match
+    fn may_match(&self, row_group: &RowGroupMetaData) -> bool {
+        let Some(statistics) = row_group
+            .columns()
+            .iter()
+            .find(|c| c.column_descr().name() == self.column)
+            .and_then(|c| c.statistics())
+        else {
+            return true;
+        };
+        let (Some(min), Some(max)) = Self::min_max(statistics) else {
+            return true;
+        };
+        let value = &self.value;
+        match (value.compare(&min), value.compare(&max)) {
+            (Some(to_min), Some(to_max)) => match self.op {
+                ComparisonOp::Eq => to_min != Ordering::Less && to_max != Ordering::Greater,
+                ComparisonOp::Lt => to_min == Ordering::Greater,
+                ComparisonOp::LtEq => to_min != Ordering::Less,
+                ComparisonOp::Gt => to_max == Ordering::Less,
+                ComparisonOp::GtEq => to_max != Ordering::Greater,
+            },
+            _ => true,
+        }
+    }
+
+    fn min_max(
+        statistics: &ParquetStatistics,
+    ) -> (Option<StatisticsValue>, Option<StatisticsValue>) {
+        let number = |v: f64| Some(StatisticsValue::Number(v));
+        match statistics {
+            ParquetStatistics::Int32(s) => (
+                s.min_opt().and_then(|v| number(*v as f64)),
+                s.max_opt().and_then(|v| number(*v as f64)),
+            ),
+            ParquetStatistics::Int64(s) => (
+                s.min_opt().and_then(|v| number(*v as f64)),
+                s.max_opt().and_then(|v| number(*v as f64)),
+            ),
+            ParquetStatistics::Float(s) => (
+                s.min_opt().and_then(|v| number(*v as f64)),
+                s.max_opt().and_then(|v| number(*v as f64)),
+            ),
+            ParquetStatistics::Double(s) => (
+                s.min_opt().and_then(|v| number(*v)),
+                s.max_opt().and_then(|v| number(*v)),
+            ),
+            ParquetStatistics::ByteArray(s) => {
+                let text = |v: &ByteArray| {
+                    v.as_utf8()
+                        .ok()
+                        .map(|t| StatisticsValue::Text(t.to_string()))
+                };
+                (s.min_opt().and_then(text), s.max_opt().and_then(text))
+            }
+            _ => (None, None),
+        }
+    }
+}
+
+struct ParquetReaderIterator {
+    file_name: String,
+    read_schema: Arc<Schema>,
+    reader: ParquetRecordBatchReader,
+    scan_error: Arc<Mutex<Option<anyhow::Error>>>,
+    /// set once a batch failed the scan, no more batches are read
+    failed: bool,
+}
+
+impl Iterator for ParquetReaderIterator {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        // the reader returns the columns in file order, so they are looked up by name
+        let batch = self
+            .reader
+            .next()?
+            .map_err(anyhow::Error::from)
+            .and_then(|batch| RecordBatch::from_arrow(self.read_schema.clone(), &batch));
+        match batch {
+            Ok(batch) => Some(batch),
+            Err(e) => {
+                *self.scan_error.lock().unwrap() =
+                    Some(anyhow!("failed to read {}: {}", self.file_name, e));
+                self.failed = true;
+                None
+            }
+        }
+    }
+}
+
+/// Compression codec of the pages of written Parquet files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetCodec {
+    Uncompressed,
+    #[default]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl ParquetCodec {
+    fn to_parquet(self) -> ParquetCompression {
+        match self {
+            ParquetCodec::Uncompressed => ParquetCompression::UNCOMPRESSED,
+            ParquetCodec::Snappy => ParquetCompression::SNAPPY,
+            ParquetCodec::Gzip => ParquetCompression::GZIP(GzipLevel::default()),
+            ParquetCodec::Zstd => ParquetCompression::ZSTD(ZstdLevel::default()),
+        }
+    }
+}
+
+/// Layout of written Parquet files, the default writes snappy compressed, dictionary encoded
+/// row groups of up to 1M rows to a single file
+#[derive(Clone, Debug)]
+pub struct ParquetWriteOptions {
+    pub codec: ParquetCodec,
+    /// maximum number of rows of a row group
+    pub row_group_size: usize,
+    /// dictionary encode the columns, the writer falls back to plain encoding for large
+    /// dictionaries
+    pub dictionary: bool,
+    /// columns whose values select the `key=value` directory a row is written to, they are not
+    /// stored in the files
+    pub partition_columns: Vec<String>,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        ParquetWriteOptions {
+            codec: ParquetCodec::default(),
+            row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+            dictionary: true,
+            partition_columns: vec![],
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    pub fn with_codec(mut self, codec: ParquetCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    pub fn with_dictionary(mut self, dictionary: bool) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    pub fn with_partition_columns(mut self, partition_columns: Vec<&str>) -> Self {
+        self.partition_columns = partition_columns.into_iter().map(String::from).collect();
+        self
+    }
+}
+
+/// Writes record batches to a Parquet file. With partition columns the path is a directory
+/// which gets a `part-0.parquet` file in a `key=value` directory per partition, which can be
+/// read back as listing table.
+pub struct ParquetWriter {
+    path: PathBuf,
+    properties: WriterProperties,
+    /// schema of the files, without the partition columns
+    file_schema: Arc<ArrowSchema>,
+    file_columns: Vec<usize>,
+    partition_columns: Vec<Arc<Field>>,
+    partition_indices: Vec<usize>,
+    /// open files by the values of their partition
+    writers: BTreeMap<Vec<String>, ArrowWriter<File>>,
+}
+
+impl ParquetWriter {
+    pub fn try_new(
+        path: &str,
+        schema: &Schema,
+        options: ParquetWriteOptions,
+    ) -> anyhow::Result<Self> {
+        let mut partition_indices = vec![];
+        for name in &options.partition_columns {
+            match schema.fields.iter().position(|f| &f.name == name) {
+                Some(i) => partition_indices.push(i),
+                None => bail!("partition column not found: {}", name),
+            }
+        }
+        let file_columns: Vec<usize> = (0..schema.fields.len())
+            .filter(|i| !partition_indices.contains(i))
+            .collect();
+        if file_columns.is_empty() {
+            bail!("all columns are partition columns");
+        }
+
+        // nullability is only known from samples (e.g. of inferred CSV schemas), so all columns
+        // are written as nullable
+        let arrow_schema = schema.to_arrow();
+        let fields: Vec<ArrowField> = file_columns
+            .iter()
+            .map(|i| arrow_schema.field(*i).clone().with_nullable(true))
+            .collect();
+        let properties = WriterProperties::builder()
+            .set_compression(options.codec.to_parquet())
+            .set_max_row_group_size(options.row_group_size)
+            .set_dictionary_enabled(options.dictionary)
+            .build();
+        let mut writer = ParquetWriter {
+            path: PathBuf::from(path),
+            properties,
+            file_schema: Arc::new(ArrowSchema::new(fields)),
+            file_columns,
+            partition_columns: partition_indices
+                .iter()
+                .map(|i| schema.fields[*i].clone())
+                .collect(),
+            partition_indices,
+            writers: BTreeMap::new(),
+        };
+        if writer.partition_columns.is_empty() {
+            // an empty result is written as a file without rows
+            writer.writer(vec![])?;
+        } else {
+            std::fs::create_dir_all(path)?;
+        }
+        Ok(writer)
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        let arrow_batch = batch.to_arrow()?;
+        let columns = self
+            .file_columns
+            .iter()
+            .map(|i| arrow_batch.column(*i).clone())
+            .collect();
+        let file_batch = ArrowRecordBatch::try_new(self.file_schema.clone(), columns)?;
+        if self.partition_columns.is_empty() {
+            return Ok(self.writer(vec![])?.write(&file_batch)?);
+        }
+
+        let mut partitions: BTreeMap<Vec<String>, Vec<u32>> = BTreeMap::new();
+        for row in 0..batch.row_count() {
+            let values = self
+                .partition_indices
+                .iter()
+                .zip(&self.partition_columns)
+                .map(|(i, column)| {
+                    batch
+                        .field(*i)
+                        .get_value(row)
+                        .and_then(|v| RecordBatch::value_to_string(v, &column.data_type))
+                        .ok_or_else(|| anyhow!("null value in partition column {}", column.name))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            partitions.entry(values).or_default().push(row as u32);
+        }
+        for (values, rows) in partitions {
+            let rows = take_record_batch(&file_batch, &UInt32Array::from(rows))?;
+            self.writer(values)?.write(&rows)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the footers of all files
+    pub fn finish(self) -> anyhow::Result<()> {
+        for writer in self.writers.into_values() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+
+    /// Writer of the partition with the given values, the file is created on first use
+    fn writer(&mut self, values: Vec<String>) -> anyhow::Result<&mut ArrowWriter<File>> {
+        if !self.writers.contains_key(&values) {
+            let mut path = self.path.clone();
+            for (column, value) in self.partition_columns.iter().zip(&values) {
+                // the listing table reads the directory names as they are
+                let valid =
+                    !matches!(value.as_str(), "" | "." | "..") && !value.contains(['/', '\\']);
+                if !valid {
+                    bail!(
+                        "value {:?} of partition column {} is not a valid directory name",
+                        value,
+                        column.name
+                    );
+                }
+                path.push(format!("{}={}", column.name, value));
+            }
+            if !values.is_empty() {
+                std::fs::create_dir_all(&path)?;
+                path.push("part-0.parquet");
+            }
+            let writer = ArrowWriter::try_new(
+                File::create(&path)?,
+                self.file_schema.clone(),
+                Some(self.properties.clone()),
+            )?;
+            self.writers.insert(values.clone(), writer);
+        }
+        Ok(self.writers.get_mut(&values).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::{CsvDataSource, CsvOptions};
+    use crate::data_source::parquet_data_source::{
+        ParquetCodec, ParquetDataSource, ParquetWriteOptions, ParquetWriter,
+    };
+    use crate::data_source::statistics::{ColumnStatistics, StatisticsValue};
+    use crate::data_source::{DataSource, FilterPushDown};
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::datatypes::record_batch::RecordBatch;
+    use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::lit_long;
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::record_batch::RecordBatch as ArrowRecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::{Compression as ParquetCompression, Encoding};
+    use parquet::file::properties::WriterProperties;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parquet_schema() {
+        let ds = ParquetDataSource::try_new("testdata/alltypes_utf8.parquet", 1024).unwrap();
+        let schema = ds.schema();
+
+        assert_eq!(schema.fields.len(), 10);
+        assert_eq!(schema.fields[0].name, "id");
+        assert_eq!(schema.fields[0].data_type, ArrowType::Int32Type);
+        assert_eq!(schema.fields[1].data_type, ArrowType::BooleanType);
+        assert_eq!(schema.fields[9].name, "string_col");
+        assert_eq!(schema.fields[9].data_type, ArrowType::StringType);
+    }
+
+    #[test]
+    fn test_parquet_scan_with_projection() {
+        let ds = ParquetDataSource::try_new("testdata/alltypes_utf8.parquet", 3).unwrap();
+        let batches: Vec<RecordBatch> = ds.scan(vec!["string_col", "id"]).collect();
+
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].column_count(), 2);
+        assert_eq!(batches[0].schema.fields[0].name, "string_col");
+        assert_eq!("0,4\n1,5\n0,6\n", batches[0].to_csv().unwrap());
+        assert_eq!(8, batches.iter().map(|b| b.row_count()).sum::<usize>());
+    }
+
+    #[test]
+    fn test_parquet_errors() {
+        let error = |file_name| {
+            ParquetDataSource::try_new(file_name, 1024)
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        // binary and INT96 timestamp columns have no counterpart in ArrowType
+        assert_eq!(
+            "unsupported type Binary of column date_string_col in testdata/alltypes_plain.parquet",
+            error("testdata/alltypes_plain.parquet")
+        );
+        assert!(error("testdata/missing.parquet").starts_with("failed to open "));
+        assert!(error("testdata/employee.csv").starts_with("not a valid parquet file "));
+        assert!(error("testdata/employee.csv.gz").starts_with("compressed parquet files "));
+    }
+
+    #[test]
+    fn test_parquet_file_removed_before_scan() {
+        let path = write_row_groups("rs_query_removed.parquet");
+        let ds = ParquetDataSource::try_new(path.to_str().unwrap(), 1024).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(0, ds.scan(vec!["id"]).count());
+        assert!(ds
+            .take_scan_error()
+            .unwrap()
+            .to_string()
+            .starts_with("failed to open "));
+        assert!(ds.take_scan_error().is_none());
+        assert_eq!(None, ds.statistics().row_count);
+    }
+
+    /// Writes two row groups with the ids 0 to 4 and 5 to 9
+    fn write_row_groups(file_name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(file_name);
+        let ids: ArrayRef = Arc::new(Int64Array::from_iter_values(0..10));
+        let batch = ArrowRecordBatch::try_from_iter(vec![("id", ids)]).unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(5)
+            .build();
+        let file = File::create(&path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parquet_row_group_pruning() {
+        let path = write_row_groups("rs_query_row_groups.parquet");
+        let ds = ParquetDataSource::try_new(path.to_str().unwrap(), 1024).unwrap();
+        let filter = col("id").gt(lit_long(6));
+        assert_eq!(ds.supports_filter(&filter), FilterPushDown::Inexact);
+        assert_eq!(
+            ds.supports_filter(&col("id").neq(lit_long(6))),
+            FilterPushDown::Unsupported
+        );
+
+        let rows = |filter| -> usize {
+            ds.scan_with_filters(vec!["id"], &[filter])
+                .map(|b| b.row_count())
+                .sum()
+        };
+        assert_eq!(rows(filter), 5);
+        assert_eq!(rows(lit_long(3).gt(col("id"))), 5);
+        assert_eq!(rows(col("id").eq(lit_long(10))), 0);
+        assert_eq!(rows(col("id").gteq(lit_long(4))), 10);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_parquet_statistics_from_footer() {
+        let path = write_row_groups("rs_query_statistics.parquet");
+        let ds = ParquetDataSource::try_new(path.to_str().unwrap(), 1024).unwrap();
+
+        let statistics = ds.statistics();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(Some(10), statistics.row_count);
+        assert!(statistics.is_exact);
+        assert!(statistics.byte_size.is_some());
+        assert_eq!(
+            ColumnStatistics {
+                min: Some(StatisticsValue::Number(0.0)),
+                max: Some(StatisticsValue::Number(9.0)),
+                null_count: Some(0),
+                distinct_count: None,
+            },
+            statistics.columns[0]
+        );
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let path = std::env::temp_dir().join("rs_query_write_employee.parquet");
+        let file_name = path.to_str().unwrap();
+        let csv =
+            CsvDataSource::try_new("testdata/employee.csv", None, CsvOptions::default(), 1024)
+                .unwrap();
+        let options = ParquetWriteOptions::default()
+            .with_codec(ParquetCodec::Zstd)
+            .with_row_group_size(3)
+            .with_dictionary(false);
+        let mut writer = ParquetWriter::try_new(file_name, &csv.schema(), options).unwrap();
+        for batch in csv.scan(vec![]) {
+            writer.write(&batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let metadata = ParquetDataSource::open_reader_builder(file_name)
+            .unwrap()
+            .metadata()
+            .clone();
+        assert_eq!(2, metadata.num_row_groups());
+        let column = metadata.row_group(0).column(0);
+        assert!(matches!(column.compression(), ParquetCompression::ZSTD(_)));
+        assert!(!column.encodings().contains(&Encoding::RLE_DICTIONARY));
+
+        let ds = ParquetDataSource::try_new(file_name, 1024).unwrap();
+        assert_eq!(ds.schema().fields[5].data_type, ArrowType::Int64Type);
+        let result: String = ds
+            .scan(vec!["id", "job_title"])
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            "1,Manager\n2,Driver\n3,Manager, Software\n4,Defensive End\n",
+            result
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::Schema;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Minimum or maximum of a column, numbers of all types are compared as f64
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatisticsValue {
+    Number(f64),
+    Text(String),
+}
+
+impl StatisticsValue {
+    /// None for values of different kinds, which are not comparable
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (StatisticsValue::Number(a), StatisticsValue::Number(b)) => a.partial_cmp(b),
+            (StatisticsValue::Text(a), StatisticsValue::Text(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Value of a column, booleans have no minimum or maximum
+    fn from_value(value: &str, data_type: &ArrowType) -> Option<Self> {
+        match data_type {
+            ArrowType::BooleanType => None,
+            ArrowType::StringType => Some(StatisticsValue::Text(value.to_string())),
+            _ => value.parse().ok().map(StatisticsValue::Number),
+        }
+    }
+}
+
+impl Display for StatisticsValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatisticsValue::Number(n) => write!(f, "{}", n),
+            StatisticsValue::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Statistics of a column, unknown values are None
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnStatistics {
+    /// lower bound of the values
+    pub min: Option<StatisticsValue>,
+    /// upper bound of the values
+    pub max: Option<StatisticsValue>,
+    pub null_count: Option<usize>,
+    /// estimated number of distinct non null values
+    pub distinct_count: Option<usize>,
+}
+
+/// Size of a table or of the output of a plan, unknown values are None. The row count, byte
+/// size and null counts are exact if `is_exact` is set, otherwise they are estimates.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub row_count: Option<usize>,
+    pub byte_size: Option<usize>,
+    /// statistics of the columns in schema order, empty if no column statistics are known
+    pub columns: Vec<ColumnStatistics>,
+    pub is_exact: bool,
+}
+
+impl Statistics {
+    /// Statistics of a file whose size is its only cheaply known statistic
+    pub fn from_file(file_name: &str) -> Self {
+        Statistics {
+            byte_size: std::fs::metadata(file_name)
+                .ok()
+                .map(|metadata| metadata.len() as usize),
+            ..Default::default()
+        }
+    }
+
+    /// Statistics of the batches, computed from all values
+    pub fn compute(schema: &Schema, batches: &[RecordBatch]) -> Self {
+        let mut collector = StatisticsCollector::new(schema);
+        for batch in batches {
+            collector.update(batch);
+        }
+        collector.finish()
+    }
+
+    /// Statistics of the columns at the given indices of the input with `input_columns` columns,
+    /// e.g. of a projection. The byte size is unknown unless all columns are kept.
+    pub fn project(&self, indices: &[Option<usize>], input_columns: usize) -> Self {
+        let columns = match self.columns.is_empty() {
+            true => vec![],
+            false => indices
+                .iter()
+                .map(|i| i.and_then(|i| self.columns.get(i).cloned()))
+                .map(Option::unwrap_or_default)
+                .collect(),
+        };
+        let all_columns = indices.iter().copied().eq((0..input_columns).map(Some));
+        Statistics {
+            row_count: self.row_count,
+            byte_size: self.byte_size.filter(|_| all_columns),
+            columns,
+            is_exact: self.is_exact,
+        }
+    }
+
+    /// Statistics of a subset of the rows, the counts become upper bounds
+    pub fn inexact(&self) -> Self {
+        Statistics {
+            is_exact: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// Statistics collected by scanning a data source, see `ExecutionContext::analyze`
+#[derive(Clone, Debug)]
+pub struct AnalyzedStatistics {
+    pub statistics: Statistics,
+    /// file or directory of the source
+    pub location: Option<String>,
+    /// modification time of the location when the statistics were collected
+    pub modified: Option<SystemTime>,
+}
+
+impl AnalyzedStatistics {
+    /// False once the files at the location have been modified
+    pub fn is_current(&self) -> bool {
+        self.location.as_deref().and_then(last_modified) == self.modified
+    }
+}
+
+/// Latest modification time of the file, or of the files in the directory and its
+/// subdirectories
+pub fn last_modified(location: &str) -> Option<SystemTime> {
+    fn visit(path: &Path) -> Option<SystemTime> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        if !metadata.is_dir() {
+            return Some(modified);
+        }
+        std::fs::read_dir(path)
+            .ok()?
+            .filter_map(|entry| visit(&entry.ok()?.path()))
+            .chain(std::iter::once(modified))
+            .max()
+    }
+    visit(Path::new(location))
+}
+
+/// Collects the statistics of all batches of a scan, the distinct counts are estimated in
+/// constant memory
+pub struct StatisticsCollector {
+    data_types: Vec<ArrowType>,
+    row_count: usize,
+    byte_size: usize,
+    columns: Vec<(ColumnStatistics, DistinctCounter)>,
+}
+
+impl StatisticsCollector {
+    pub fn new(schema: &Schema) -> Self {
+        let column = || {
+            let statistics = ColumnStatistics {
+                null_count: Some(0),
+                ..Default::default()
+            };
+            (statistics, DistinctCounter::new())
+        };
+        StatisticsCollector {
+            data_types: schema.fields.iter().map(|f| f.data_type.clone()).collect(),
+            row_count: 0,
+            byte_size: 0,
+            columns: schema.fields.iter().map(|_| column()).collect(),
+        }
+    }
+
+    pub fn update(&mut self, batch: &RecordBatch) {
+        self.row_count += batch.row_count();
+        for (i, (column, distinct)) in self.columns.iter_mut().enumerate() {
+            let data_type = &self.data_types[i];
+            let width = data_type.to_datatype().primitive_width();
+            let vector = batch.field(i);
+            for row in 0..vector.size() {
+                let Some(value) = vector
+                    .get_value(row)
+                    .and_then(|v| RecordBatch::value_to_string(v, data_type))
+                else {
+                    column.null_count = column.null_count.map(|n| n + 1);
+                    continue;
+                };
+                self.byte_size += width.unwrap_or(value.len());
+                distinct.insert(&value);
+                if let Some(value) = StatisticsValue::from_value(&value, data_type) {
+                    column.min = Some(bound(column.min.take(), value.clone(), Ordering::Less));
+                    column.max = Some(bound(column.max.take(), value, Ordering::Greater));
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> Statistics {
+        let columns = self
+            .columns
+            .into_iter()
+            .map(|(column, distinct)| ColumnStatistics {
+                distinct_count: Some(distinct.estimate()),
+                ..column
+            })
+            .collect();
+        Statistics {
+            row_count: Some(self.row_count),
+            byte_size: Some(self.byte_size),
+            columns,
+            is_exact: true,
+        }
+    }
+}
+
+const DISTINCT_PRECISION: u32 = 12;
+
+/// HyperLogLog sketch of the values of a column
+struct DistinctCounter {
+    registers: Vec<u8>,
+}
+
+impl DistinctCounter {
+    fn new() -> Self {
+        DistinctCounter {
+            registers: vec![0; 1 << DISTINCT_PRECISION],
+        }
+    }
+
+    fn insert(&mut self, value: &str) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        // the first bits select the register, which keeps the longest run of leading zeros
+        // in the remaining bits
+        let index = (hash >> (64 - DISTINCT_PRECISION)) as usize;
+        let rest = (hash << DISTINCT_PRECISION) | (1 << (DISTINCT_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        let empty = self.registers.iter().filter(|r| **r == 0).count();
+        // linear counting is more accurate for small numbers of values
+        if estimate <= 2.5 * m && empty > 0 {
+            (m * (m / empty as f64).ln()).round() as usize
+        } else {
+            estimate.round() as usize
+        }
+    }
+}
+
+/// The smaller (or greater) of the current bound and the value
+pub(crate) fn bound(
+    current: Option<StatisticsValue>,
+    value: StatisticsValue,
+    order: Ordering,
+) -> StatisticsValue {
+    match current {
+        Some(current) if value.compare(&current) != Some(order) => current,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::statistics::{ColumnStatistics, Statistics, StatisticsValue};
+    use crate::data_source::{DataSource, Source};
+
+    #[test]
+    fn test_compute_statistics() {
+        let csv = Source::from_csv("testdata/employee.csv", None, Default::default(), 2).unwrap();
+        let batches: Vec<_> = csv.scan(vec!["id", "salary"]).collect();
+        let schema = batches[0].schema.clone();
+
+        let statistics = Statistics::compute(&schema, &batches);
+
+        assert_eq!(Some(4), statistics.row_count);
+        assert!(statistics.is_exact);
+        assert_eq!(
+            ColumnStatistics {
+                min: Some(StatisticsValue::Number(1.0)),
+                max: Some(StatisticsValue::Number(4.0)),
+                null_count: Some(0),
+                distinct_count: Some(4),
+            },
+            statistics.columns[0]
+        );
+        assert_eq!(
+            Some(StatisticsValue::Number(12000.0)),
+            statistics.columns[1].max
+        );
+        assert_eq!(Some(3), statistics.columns[1].distinct_count);
+        assert_eq!(Some(64), statistics.byte_size);
+    }
+}
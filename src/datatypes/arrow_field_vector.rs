@@ -1,10 +1,11 @@
 use crate::datatypes::arrow_types::ArrowType;
 use crate::datatypes::column_vector::ColumnVector;
 use arrow::array::{
-    Array, ArrayAccessor, ArrayBuilder, BooleanArray, BooleanBuilder, Float32Array, Float32Builder,
-    Float64Array, Float64Builder, Int16Array, Int16Builder, Int32Array, Int32Builder, Int64Array,
-    Int64Builder, Int8Array, Int8Builder, StringArray, StringBuilder, UInt16Array, UInt16Builder,
-    UInt32Array, UInt32Builder, UInt64Array, UInt64Builder, UInt8Array, UInt8Builder,
+    Array, ArrayAccessor, ArrayBuilder, ArrayRef, BooleanArray, BooleanBuilder, Float32Array,
+    Float32Builder, Float64Array, Float64Builder, Int16Array, Int16Builder, Int32Array,
+    Int32Builder, Int64Array, Int64Builder, Int8Array, Int8Builder, StringArray, StringBuilder,
+    UInt16Array, UInt16Builder, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder, UInt8Array,
+    UInt8Builder,
 };
 use arrow::datatypes::DataType;
 use std::any::Any;
@@ -38,6 +39,34 @@ impl ArrowArrayFactory {
 
 pub struct ArrowFieldVector(pub Arc<Mutex<dyn Array>>);
 
+impl ArrowFieldVector {
+    /// Wraps an array produced by an arrow reader, e.g. for parquet files
+    pub fn from_array(array: &ArrayRef) -> Self {
+        fn wrap<T: Array + Clone + 'static>(array: &ArrayRef) -> Arc<Mutex<dyn Array>> {
+            Arc::new(Mutex::new(
+                array.as_any().downcast_ref::<T>().unwrap().clone(),
+            ))
+        }
+
+        let array = match array.data_type() {
+            DataType::Boolean => wrap::<BooleanArray>(array),
+            DataType::Int8 => wrap::<Int8Array>(array),
+            DataType::Int16 => wrap::<Int16Array>(array),
+            DataType::Int32 => wrap::<Int32Array>(array),
+            DataType::Int64 => wrap::<Int64Array>(array),
+            DataType::UInt8 => wrap::<UInt8Array>(array),
+            DataType::UInt16 => wrap::<UInt16Array>(array),
+            DataType::UInt32 => wrap::<UInt32Array>(array),
+            DataType::UInt64 => wrap::<UInt64Array>(array),
+            DataType::Float32 => wrap::<Float32Array>(array),
+            DataType::Float64 => wrap::<Float64Array>(array),
+            DataType::Utf8 => wrap::<StringArray>(array),
+            _ => panic!("Unsupported data type"),
+        };
+        ArrowFieldVector(array)
+    }
+}
+
 impl ColumnVector for ArrowFieldVector {
     fn get_type(&self) -> ArrowType {
         ArrowType::from_datatype(&self.0.lock().unwrap().data_type().clone())
@@ -49,6 +78,9 @@ impl ColumnVector for ArrowFieldVector {
         }
 
         let guard = self.0.lock().unwrap();
+        if guard.is_null(i) {
+            return None;
+        }
         let value: Arc<dyn Any> = match guard.data_type() {
             DataType::Boolean => {
                 let array = guard.as_any().downcast_ref::<BooleanArray>().unwrap();
@@ -17,6 +17,11 @@ pub enum ArrowType {
 }
 
 impl ArrowType {
+    /// Integer and floating point types, the operands of arithmetic and numeric aggregates
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self, ArrowType::BooleanType | ArrowType::StringType)
+    }
+
     pub fn to_datatype(&self) -> DataType {
         match self {
             ArrowType::BooleanType => DataType::Boolean,
@@ -35,7 +40,12 @@ impl ArrowType {
     }
 
     pub fn from_datatype(data_type: &DataType) -> Self {
-        match data_type {
+        Self::try_from_datatype(data_type).expect("Unsupported data type")
+    }
+
+    /// None for types without a counterpart, e.g. binary, timestamp or nested types
+    pub fn try_from_datatype(data_type: &DataType) -> Option<Self> {
+        Some(match data_type {
             DataType::Boolean => ArrowType::BooleanType,
             DataType::Int8 => ArrowType::Int8Type,
             DataType::Int16 => ArrowType::Int16Type,
@@ -48,7 +58,7 @@ impl ArrowType {
             DataType::Float32 => ArrowType::FloatType,
             DataType::Float64 => ArrowType::DoubleType,
             DataType::Utf8 => ArrowType::StringType,
-            _ => panic!("Unsupported data type"),
-        }
+            _ => return None,
+        })
     }
 }
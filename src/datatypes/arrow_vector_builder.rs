@@ -243,6 +243,11 @@ impl ArrowVectorBuilder {
             self.arrow_array_builder.finish(),
         ))))
     }
+
+    /// Finishes the builder into a plain arrow array, e.g. for writers
+    pub fn finish(mut self) -> ArrayRef {
+        self.arrow_array_builder.finish()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,14 @@
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_field_vector::ArrowFieldVector;
 use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
 use crate::datatypes::column_vector::ColumnVector;
 use crate::datatypes::schema::Schema;
 use anyhow::anyhow;
-use arrow::array::Array;
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::cast;
+use arrow::datatypes::{Field as ArrowField, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch as ArrowRecordBatch;
 use std::any::Any;
 use std::fmt::Display;
 use std::sync::Arc;
@@ -25,6 +31,71 @@ impl RecordBatch {
         self.fields[i].clone()
     }
 
+    /// Converts an arrow record batch, the columns are looked up by the names of the schema and
+    /// cast to its types
+    pub fn from_arrow(schema: Arc<Schema>, batch: &ArrowRecordBatch) -> anyhow::Result<Self> {
+        let fields = schema
+            .fields
+            .iter()
+            .map(|f| {
+                let column = batch
+                    .column_by_name(&f.name)
+                    .ok_or_else(|| anyhow!("column not found: {}", f.name))?;
+                let column: ArrayRef = cast(column, &f.data_type.to_datatype())?;
+                Ok(Arc::new(ArrowFieldVector::from_array(&column)) as Arc<dyn ColumnVector>)
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(RecordBatch { schema, fields })
+    }
+
+    /// Converts to an arrow record batch, e.g. to write it with the arrow writers. Columns
+    /// containing nulls are marked nullable, even if the schema was inferred without them
+    pub fn to_arrow(&self) -> anyhow::Result<ArrowRecordBatch> {
+        let (fields, columns): (Vec<ArrowField>, Vec<ArrayRef>) = self
+            .schema
+            .fields
+            .iter()
+            .zip(self.fields.iter())
+            .map(|(f, field)| {
+                let array = ArrowArrayFactory::create(f.data_type.to_datatype(), field.size());
+                let mut vector = ArrowVectorBuilder::new(array);
+                for i in 0..field.size() {
+                    vector.append(field.get_value(i));
+                }
+                let array = vector.finish();
+                let nullable = f.nullable || array.null_count() > 0;
+                (
+                    ArrowField::new(&f.name, f.data_type.to_datatype(), nullable),
+                    array,
+                )
+            })
+            .unzip();
+        Ok(ArrowRecordBatch::try_new(
+            Arc::new(ArrowSchema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Returns a copy of `length` rows of this batch starting at row `offset`
+    pub fn slice(&self, offset: usize, length: usize) -> RecordBatch {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| {
+                let array = ArrowArrayFactory::create(field.get_type().to_datatype(), length);
+                let mut vector = ArrowVectorBuilder::new(array);
+                for i in offset..offset + length {
+                    vector.append(field.get_value(i));
+                }
+                vector.build()
+            })
+            .collect();
+        RecordBatch {
+            schema: self.schema.clone(),
+            fields,
+        }
+    }
+
     /// prints without headers for testing purposes
     pub fn to_csv(&self) -> Option<String> {
         let mut csv = String::new();
@@ -32,7 +103,11 @@ impl RecordBatch {
             let mut row = Vec::new();
             for j in 0..self.column_count() {
                 let data_type = self.schema.fields.get(j)?.data_type.clone();
-                let any_value = self.field(j).get_value(i)?;
+                // nulls are written as empty values
+                let Some(any_value) = self.field(j).get_value(i) else {
+                    row.push(String::new());
+                    continue;
+                };
                 match data_type {
                     ArrowType::BooleanType => {
                         row.push(any_value.downcast_ref::<bool>()?.to_string().to_owned());
@@ -116,8 +191,10 @@ impl RecordBatch {
         for i in 0..self.row_count() {
             for j in 0..self.column_count() {
                 let data_type = self.schema.fields.get(j)?.data_type.clone();
-                let any_value = self.field(j).get_value(i)?;
-                if let Some(value_str) = Self::value_to_string(any_value, &data_type) {
+                let any_value = self.field(j).get_value(i);
+                if let Some(value_str) =
+                    any_value.and_then(|v| Self::value_to_string(v, &data_type))
+                {
                     if value_str.len() > max_lengths[j] {
                         max_lengths[j] = value_str.len();
                     }
@@ -143,8 +220,10 @@ impl RecordBatch {
             let mut row = Vec::new();
             for j in 0..self.column_count() {
                 let data_type = self.schema.fields.get(j)?.data_type.clone();
-                let any_value = self.field(j).get_value(i)?;
-                if let Some(value_str) = Self::value_to_string(any_value, &data_type) {
+                let any_value = self.field(j).get_value(i);
+                if let Some(value_str) =
+                    any_value.and_then(|v| Self::value_to_string(v, &data_type))
+                {
                     row.push(format!("{:width$}", value_str, width = max_lengths[j]));
                 } else {
                     row.push(format!("{:width$}", "", width = max_lengths[j]));
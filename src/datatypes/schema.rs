@@ -1,7 +1,9 @@
 use crate::datatypes::arrow_types::ArrowType;
+use anyhow::anyhow;
 use arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::Deref;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -19,12 +21,61 @@ impl Schema {
                 Arc::new(Field {
                     name: f.name().clone(),
                     data_type: ArrowType::from_datatype(f.data_type()),
+                    nullable: f.is_nullable(),
                 })
             })
             .collect();
         Schema { fields }
     }
 
+    /// Converts from Arrow's Schema, fails for a column of a type without a counterpart in
+    /// `ArrowType`
+    pub fn try_from_arrow(arrow_schema: &ArrowSchema) -> anyhow::Result<Self> {
+        let fields = arrow_schema
+            .fields()
+            .iter()
+            .map(|f| {
+                let data_type = ArrowType::try_from_datatype(f.data_type()).ok_or_else(|| {
+                    anyhow!("unsupported type {} of column {}", f.data_type(), f.name())
+                })?;
+                Ok(Arc::new(Field {
+                    name: f.name().clone(),
+                    data_type,
+                    nullable: f.is_nullable(),
+                }))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Schema { fields })
+    }
+
+    /// Types without a counterpart in `ArrowType` (e.g. binary or timestamp columns) are
+    /// replaced by strings, so files with such columns can still be read
+    pub fn to_supported(arrow_schema: &ArrowSchema) -> ArrowSchema {
+        let fields: Vec<ArrowField> = arrow_schema
+            .fields()
+            .iter()
+            .map(|f| {
+                let data_type = match f.data_type() {
+                    DataType::Boolean
+                    | DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64
+                    | DataType::UInt8
+                    | DataType::UInt16
+                    | DataType::UInt32
+                    | DataType::UInt64
+                    | DataType::Float32
+                    | DataType::Float64
+                    | DataType::Utf8 => f.data_type().clone(),
+                    _ => DataType::Utf8,
+                };
+                ArrowField::new(f.name(), data_type, f.is_nullable())
+            })
+            .collect();
+        ArrowSchema::new(fields)
+    }
+
     // Convert to Arrow's Schema
     pub fn to_arrow(&self) -> ArrowSchema {
         let fields: Vec<_> = self.fields.iter().map(|f| f.to_arrow()).collect();
@@ -52,6 +103,21 @@ impl Schema {
         }
         Ok(Schema { fields })
     }
+
+    // Replace the data type of the field with the given name, e.g. to override an inferred type
+    pub fn with_data_type(&self, name: &str, data_type: ArrowType) -> Result<Schema, &'static str> {
+        let index = self
+            .fields
+            .iter()
+            .position(|f| f.name == name)
+            .ok_or("Field name not found")?;
+        let mut fields = self.fields.clone();
+        fields[index] = Arc::new(Field {
+            data_type,
+            ..fields[index].deref().clone()
+        });
+        Ok(Schema { fields })
+    }
 }
 
 impl fmt::Display for Schema {
@@ -71,12 +137,13 @@ impl fmt::Display for Schema {
 pub struct Field {
     pub name: String,
     pub data_type: ArrowType,
+    pub nullable: bool,
 }
 
 impl Field {
     // Convert to Arrow's Field
     fn to_arrow(&self) -> ArrowField {
-        ArrowField::new(&self.name, self.data_type.to_datatype(), true)
+        ArrowField::new(&self.name, self.data_type.to_datatype(), self.nullable)
     }
 }
 
@@ -84,7 +151,7 @@ impl Field {
 mod test {
     use crate::datatypes::arrow_types::ArrowType;
     use crate::datatypes::schema::{Field, Schema};
-    use arrow::datatypes::DataType;
+    use arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
     use std::sync::Arc;
 
     #[test]
@@ -92,10 +159,12 @@ mod test {
         let field1 = Arc::new(Field {
             name: "test1".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         });
         let field2 = Arc::new(Field {
             name: "test2".to_string(),
             data_type: ArrowType::Int64Type,
+            nullable: true,
         });
         let schema = Schema {
             fields: vec![field1, field2],
@@ -110,15 +179,40 @@ mod test {
         assert_eq!(num_fields, 2)
     }
 
+    #[test]
+    fn test_try_from_arrow() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int64, false),
+            ArrowField::new("name", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from_arrow(&arrow_schema).unwrap();
+        assert_eq!(ArrowType::Int64Type, schema.fields[0].data_type);
+        assert!(!schema.fields[0].nullable);
+
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int64, false),
+            ArrowField::new("data", DataType::Binary, true),
+        ]);
+        assert_eq!(
+            "unsupported type Binary of column data",
+            Schema::try_from_arrow(&arrow_schema)
+                .err()
+                .unwrap()
+                .to_string()
+        );
+    }
+
     #[test]
     fn test_display_schema() {
         let field1 = Arc::new(Field {
             name: "test1".to_string(),
             data_type: ArrowType::StringType,
+            nullable: true,
         });
         let field2 = Arc::new(Field {
             name: "test2".to_string(),
             data_type: ArrowType::Int64Type,
+            nullable: true,
         });
         let schema = Schema {
             fields: vec![field1, field2],
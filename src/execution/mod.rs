@@ -1,20 +1,42 @@
-use crate::data_source::{DataSource, Source};
+use crate::data_source::csv_data_source::{
+    CsvDataSource, CsvOptions, CsvWriteOptions, CsvWriter, RejectedRow, DEFAULT_INFER_SCHEMA_ROWS,
+};
+use crate::data_source::ipc_data_source::{IpcFormat, IpcWriter};
+use crate::data_source::json_data_source::JsonDataSource;
+use crate::data_source::listing_data_source::ListingFormat;
+use crate::data_source::parquet_data_source::{ParquetWriteOptions, ParquetWriter};
+use crate::data_source::statistics::{
+    last_modified, AnalyzedStatistics, Statistics, StatisticsCollector,
+};
+use crate::data_source::{DataSource, Source, TableProvider};
+use crate::datatypes::arrow_types::ArrowType;
 use crate::datatypes::record_batch::RecordBatch;
 use crate::datatypes::schema::Schema;
 use crate::logical_plan::data_frame::{DataFrame, DataFrameImpl};
 use crate::logical_plan::scan::Scan;
 use crate::logical_plan::LogicalPlan;
-use crate::optimizer::Optimizer;
+use crate::optimizer::{Optimizer, OptimizerRule};
 use crate::query_planner::QueryPlanner;
+use crate::sql_planner::SqlPlanner;
+use anyhow::{anyhow, bail};
+use sqlparser::ast::Statement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 /// Execution Context
 pub struct ExecutionContext {
     pub settings: HashMap<String, String>,
     tables: HashMap<String, Arc<dyn DataFrame>>,
+    optimizer_rules: Vec<Arc<dyn OptimizerRule>>,
+    table_providers: HashMap<String, Arc<dyn TableProvider>>,
+    /// statistics of analyzed tables by the location and modification time of their files
+    statistics_cache: HashMap<(String, SystemTime), Statistics>,
     batch_size: usize,
+    infer_schema_rows: usize,
 }
 
 impl ExecutionContext {
@@ -23,28 +45,135 @@ impl ExecutionContext {
             .get("csv.batch_size")
             .and_then(|s| s.parse().ok())
             .unwrap_or(1024);
+        let infer_schema_rows = settings
+            .get("csv.infer_schema_rows")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_INFER_SCHEMA_ROWS);
         ExecutionContext {
             settings,
             tables: Default::default(),
+            optimizer_rules: vec![],
+            table_providers: Default::default(),
+            statistics_cache: Default::default(),
             batch_size,
+            infer_schema_rows,
         }
     }
 
     /// Create a DataFrame for the given SQL Select
-    pub fn sql(sql: &str) -> Arc<dyn DataFrame> {
-        todo!()
+    pub fn sql(&self, sql: &str) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql)?;
+        if statements.len() != 1 {
+            bail!(
+                "expected exactly one SQL statement, got {}",
+                statements.len()
+            );
+        }
+        match statements.remove(0) {
+            Statement::Query(query) => SqlPlanner::create_data_frame(&query, &self.tables),
+            other => bail!("statement not supported: {}", other),
+        }
     }
 
-    /// Get a DataFrame representing the specified CSV file
-    pub fn csv(&self, file_name: impl Into<String>, has_headers: bool) -> Arc<dyn DataFrame> {
+    /// Get a DataFrame representing the specified CSV file, the column types are inferred
+    pub fn csv(
+        &self,
+        file_name: impl Into<String>,
+        options: CsvOptions,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
         let file_name = file_name.into();
-        Arc::new(DataFrameImpl {
-            plan: Scan::new(
-                file_name.clone(),
-                Source::from_csv(file_name, None, has_headers, self.batch_size),
-                vec![],
-            ),
-        })
+        let schema = self.infer_csv_schema(&file_name, &options)?;
+        self.csv_with_schema(file_name, options, schema)
+    }
+
+    /// Get a DataFrame representing the specified CSV file read with the given schema
+    pub fn csv_with_schema(
+        &self,
+        file_name: impl Into<String>,
+        options: CsvOptions,
+        schema: Arc<Schema>,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let file_name = file_name.into();
+        let source = Source::from_csv(file_name.clone(), Some(schema), options, self.batch_size)?;
+        Ok(Arc::new(DataFrameImpl {
+            plan: Scan::new(file_name, source, vec![]),
+        }))
+    }
+
+    /// Get a DataFrame representing the specified CSV file with the inferred types of the
+    /// given columns replaced
+    pub fn csv_with_types(
+        &self,
+        file_name: impl Into<String>,
+        options: CsvOptions,
+        types: &[(&str, ArrowType)],
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let file_name = file_name.into();
+        let mut schema = self.infer_csv_schema(&file_name, &options)?.deref().clone();
+        for (name, data_type) in types {
+            schema = schema
+                .with_data_type(name, data_type.clone())
+                .map_err(|e| anyhow!("{}: {}", e, name))?;
+        }
+        self.csv_with_schema(file_name, options, Arc::new(schema))
+    }
+
+    /// Schema of the CSV file as inferred from the first `csv.infer_schema_rows` rows
+    pub fn infer_csv_schema(
+        &self,
+        file_name: &str,
+        options: &CsvOptions,
+    ) -> anyhow::Result<Arc<Schema>> {
+        let schema = CsvDataSource::infer_schema(file_name, options, self.infer_schema_rows)?;
+        Ok(Arc::new(schema))
+    }
+
+    /// Get a DataFrame representing the specified Parquet file
+    pub fn parquet(&self, file_name: impl Into<String>) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let file_name = file_name.into();
+        let source = Source::from_parquet(file_name.clone(), self.batch_size)?;
+        Ok(Arc::new(DataFrameImpl {
+            plan: Scan::new(file_name, source, vec![]),
+        }))
+    }
+
+    /// Get a DataFrame representing the specified newline delimited JSON file, the columns are
+    /// inferred from the first `csv.infer_schema_rows` lines
+    pub fn json(&self, file_name: impl Into<String>) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let file_name = file_name.into();
+        let schema = JsonDataSource::infer_schema(&file_name, self.infer_schema_rows)?;
+        let source = Source::from_json(file_name.clone(), Some(Arc::new(schema)), self.batch_size)?;
+        Ok(Arc::new(DataFrameImpl {
+            plan: Scan::new(file_name, source, vec![]),
+        }))
+    }
+
+    /// Get a DataFrame representing the specified Arrow IPC file or stream
+    pub fn ipc(&self, file_name: impl Into<String>) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let file_name = file_name.into();
+        let source = Source::from_ipc(file_name.clone())?;
+        Ok(Arc::new(DataFrameImpl {
+            plan: Scan::new(file_name, source, vec![]),
+        }))
+    }
+
+    /// Get a DataFrame representing all files in a directory or matching a glob pattern, with a
+    /// partition column for each `key=value` directory in their paths
+    pub fn listing(
+        &self,
+        path: impl Into<String>,
+        format: ListingFormat,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let path = path.into();
+        let source = Source::from_listing(
+            path.clone(),
+            format,
+            self.infer_schema_rows,
+            self.batch_size,
+        )?;
+        Ok(Arc::new(DataFrameImpl {
+            plan: Scan::new(path, source, vec![]),
+        }))
     }
 
     /// Get a DataFrame representing a specified RecordBatch
@@ -58,15 +187,90 @@ impl ExecutionContext {
         })
     }
 
-    /// Register a DataFrame with the context
+    /// Get a registered table
+    pub fn table(&self, table_name: &str) -> Option<Arc<dyn DataFrame>> {
+        self.tables.get(table_name).cloned()
+    }
+
+    /// Register a DataFrame with the context, a scan of files analyzed before gets their
+    /// cached statistics if the files have not been modified since
     pub fn register(&mut self, table_name: impl Into<String>, df: Arc<dyn DataFrame>) {
+        let plan = df.clone().logical_plan();
+        let df = match plan.as_any().downcast_ref::<Scan>() {
+            Some(scan) => self.cached_statistics(scan).map_or(df, |analyzed| {
+                Arc::new(DataFrameImpl {
+                    plan: scan.with_statistics(analyzed),
+                })
+            }),
+            None => df,
+        };
         self.tables.insert(table_name.into(), df);
     }
 
+    /// Scan all rows of a registered table to collect the statistics of its columns, which are
+    /// cached for the later plans of the table and for tables registered on the same files
+    pub fn analyze(&mut self, table_name: &str) -> anyhow::Result<Statistics> {
+        let df = self
+            .table(table_name)
+            .ok_or_else(|| anyhow!("table not found: {}", table_name))?;
+        let plan = df.logical_plan();
+        let scan = plan
+            .as_any()
+            .downcast_ref::<Scan>()
+            .ok_or_else(|| anyhow!("table {} is not a scan of a data source", table_name))?;
+
+        let source = scan.datasource.clone();
+        let location = source.location();
+        let modified = location.as_deref().and_then(last_modified);
+        let schema = source.schema();
+        let mut collector = StatisticsCollector::new(&schema);
+        for batch in source.scan(schema.fields.iter().map(|f| f.name.as_str()).collect()) {
+            collector.update(&batch);
+        }
+        // rejected rows are reported for queries, not for the analysis
+        source.take_rejected_rows();
+        if let Some(error) = source.take_scan_error() {
+            return Err(error);
+        }
+        let statistics = collector.finish();
+
+        if let (Some(location), Some(modified)) = (&location, modified) {
+            self.statistics_cache
+                .insert((location.clone(), modified), statistics.clone());
+        }
+        let analyzed = AnalyzedStatistics {
+            statistics: statistics.clone(),
+            location,
+            modified,
+        };
+        self.tables.insert(
+            table_name.to_string(),
+            Arc::new(DataFrameImpl {
+                plan: scan.with_statistics(analyzed),
+            }),
+        );
+        Ok(statistics)
+    }
+
+    fn cached_statistics(&self, scan: &Scan) -> Option<AnalyzedStatistics> {
+        let location = scan.datasource.location()?;
+        let modified = last_modified(&location)?;
+        let statistics = self
+            .statistics_cache
+            .get(&(location.clone(), modified))?
+            .clone();
+        Some(AnalyzedStatistics {
+            statistics,
+            location: Some(location),
+            modified: Some(modified),
+        })
+    }
+
+    /// Register a data source, which may be a custom implementation of `DataSource`
     pub fn register_data_source(
         &mut self,
         table_name: impl Into<String>,
-        data_source: Arc<Source>,
+        data_source: Arc<dyn DataSource>,
     ) {
         let table_name = table_name.into();
         let df = Arc::new(DataFrameImpl {
@@ -79,32 +283,212 @@ impl ExecutionContext {
         &mut self,
         table_name: impl Into<String>,
         file_name: impl Into<String>,
-        has_headers: bool,
+        options: CsvOptions,
+    ) -> anyhow::Result<()> {
+        let df = self.csv(file_name, options)?;
+        self.register(table_name, df);
+        Ok(())
+    }
+
+    pub fn register_parquet(
+        &mut self,
+        table_name: impl Into<String>,
+        file_name: impl Into<String>,
+    ) -> anyhow::Result<()> {
+        let df = self.parquet(file_name)?;
+        self.register(table_name, df);
+        Ok(())
+    }
+
+    pub fn register_json(
+        &mut self,
+        table_name: impl Into<String>,
+        file_name: impl Into<String>,
+    ) -> anyhow::Result<()> {
+        let df = self.json(file_name)?;
+        self.register(table_name, df);
+        Ok(())
+    }
+
+    pub fn register_ipc(
+        &mut self,
+        table_name: impl Into<String>,
+        file_name: impl Into<String>,
+    ) -> anyhow::Result<()> {
+        let df = self.ipc(file_name)?;
+        self.register(table_name, df);
+        Ok(())
+    }
+
+    pub fn register_listing(
+        &mut self,
+        table_name: impl Into<String>,
+        path: impl Into<String>,
+        format: ListingFormat,
+    ) -> anyhow::Result<()> {
+        let df = self.listing(path, format)?;
+        self.register(table_name, df);
+        Ok(())
+    }
+
+    /// Register a provider creating the data sources of tables stored in a custom format
+    pub fn register_table_provider(
+        &mut self,
+        format: impl Into<String>,
+        provider: Arc<dyn TableProvider>,
     ) {
-        self.register(table_name, self.csv(file_name, has_headers))
+        self.table_providers
+            .insert(format.into().to_lowercase(), provider);
+    }
+
+    /// Register a table at the location with the provider of the format
+    pub fn register_table(
+        &mut self,
+        table_name: impl Into<String>,
+        format: &str,
+        location: &str,
+        options: &HashMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let provider = self
+            .table_providers
+            .get(&format.to_lowercase())
+            .ok_or_else(|| anyhow!("no table provider for format {}", format))?;
+        let data_source = provider.create(location, options)?;
+        self.register_data_source(table_name, data_source);
+        Ok(())
+    }
+
+    /// Register a custom rule. Custom rules are appended after the default optimizer rules in
+    /// the order they are registered, so in every pass they see the plan the default rules
+    /// produced; a rule can't be placed in between the default rules.
+    pub fn register_optimizer_rule(&mut self, rule: Arc<dyn OptimizerRule>) {
+        self.optimizer_rules.push(rule);
+    }
+
+    /// Optimizer with the registered rules, configured by the `optimizer.*` settings
+    pub fn optimizer(&self) -> Optimizer {
+        Optimizer::from_settings(&self.settings, self.optimizer_rules.clone())
+    }
+
+    /// Rows rejected by the scans of the DataFrame's sources since the last call
+    pub fn take_rejected_rows(&self, df: Arc<dyn DataFrame>) -> Vec<RejectedRow> {
+        Self::data_sources(df.logical_plan().as_ref())
+            .iter()
+            .flat_map(|source| source.take_rejected_rows())
+            .collect()
+    }
+
+    /// Err with the error which ended a scan of the DataFrame's sources early since the last
+    /// call, e.g. a malformed row with `MalformedRowPolicy::Fail`. The batches of the execution
+    /// are incomplete in that case.
+    pub fn take_scan_error(&self, df: Arc<dyn DataFrame>) -> anyhow::Result<()> {
+        Self::take_plan_scan_error(df.logical_plan().as_ref())
+    }
+
+    fn take_plan_scan_error(plan: &dyn LogicalPlan) -> anyhow::Result<()> {
+        let mut errors = Self::data_sources(plan)
+            .into_iter()
+            .filter_map(|source| source.take_scan_error());
+        match errors.next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn data_sources(plan: &dyn LogicalPlan) -> Vec<Arc<dyn DataSource>> {
+        let mut sources = vec![];
+        if let Some(scan) = plan.as_any().downcast_ref::<Scan>() {
+            sources.push(scan.datasource.clone());
+        }
+        for child in plan.children() {
+            sources.extend(Self::data_sources(child.as_ref()));
+        }
+        sources
     }
 
-    /// Execute the logical plan represented by a DataFrame
+    /// Execute the logical plan represented by a DataFrame, fails if a scan failed
     pub fn execute(
         &self,
         df: Arc<dyn DataFrame>,
         optimize: bool,
-    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+    ) -> anyhow::Result<Box<dyn Iterator<Item = RecordBatch> + '_>> {
         let plan = if optimize {
-            Optimizer::optimize(df.logical_plan())
+            self.optimizer().run(df.logical_plan()).plan
         } else {
             df.logical_plan()
         };
         self.execute_logical_plan(plan.deref())
     }
 
-    /// Execute the provided logical plan
+    /// Execute the optimized DataFrame and write its batches to an Arrow IPC file or stream,
+    /// returns the number of written rows
+    pub fn write_ipc(
+        &self,
+        df: Arc<dyn DataFrame>,
+        file_name: &str,
+        format: IpcFormat,
+    ) -> anyhow::Result<usize> {
+        let mut writer = IpcWriter::try_new(file_name, &df.clone().schema(), format)?;
+        let rows = self.write_batches(df, |batch| writer.write(batch))?;
+        writer.finish()?;
+        Ok(rows)
+    }
+
+    /// Execute the optimized DataFrame and write its batches to a CSV file, returns the number
+    /// of written rows
+    pub fn write_csv(
+        &self,
+        df: Arc<dyn DataFrame>,
+        file_name: &str,
+        options: CsvWriteOptions,
+    ) -> anyhow::Result<usize> {
+        let mut writer = CsvWriter::try_new(file_name, df.clone().schema(), options)?;
+        let rows = self.write_batches(df, |batch| writer.write(batch))?;
+        writer.finish()?;
+        Ok(rows)
+    }
+
+    /// Execute the optimized DataFrame and write its batches to a Parquet file, or to a directory
+    /// with a file per partition if partition columns are given, returns the number of written
+    /// rows
+    pub fn write_parquet(
+        &self,
+        df: Arc<dyn DataFrame>,
+        path: &str,
+        options: ParquetWriteOptions,
+    ) -> anyhow::Result<usize> {
+        let mut writer = ParquetWriter::try_new(path, &df.clone().schema(), options)?;
+        let rows = self.write_batches(df, |batch| writer.write(batch))?;
+        writer.finish()?;
+        Ok(rows)
+    }
+
+    /// Execute the optimized DataFrame and hand each batch to `write` as soon as it is produced,
+    /// so that the result is never held in memory as a whole. Returns the number of rows.
+    fn write_batches(
+        &self,
+        df: Arc<dyn DataFrame>,
+        mut write: impl FnMut(&RecordBatch) -> anyhow::Result<()>,
+    ) -> anyhow::Result<usize> {
+        let plan = self.optimizer().run(df.clone().logical_plan()).plan;
+        let physical_plan = QueryPlanner::create_physical_plan(plan.deref());
+        let mut rows = 0;
+        for batch in physical_plan.execute() {
+            write(&batch)?;
+            rows += batch.row_count();
+        }
+        self.take_scan_error(df)?;
+        Ok(rows)
+    }
+
+    /// Execute the provided logical plan, fails if a scan failed
     pub fn execute_logical_plan(
         &self,
         plan: &dyn LogicalPlan,
-    ) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+    ) -> anyhow::Result<Box<dyn Iterator<Item = RecordBatch> + '_>> {
         let physical_plan = QueryPlanner::create_physical_plan(plan);
         let batches: Vec<RecordBatch> = physical_plan.execute().collect(); // todo think about better solution
-        Box::new(batches.into_iter())
+        Self::take_plan_scan_error(plan)?;
+        Ok(Box::new(batches.into_iter()))
     }
 }
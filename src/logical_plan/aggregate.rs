@@ -1,7 +1,8 @@
+use crate::data_source::statistics::Statistics;
 use crate::datatypes::schema::{Field, Schema};
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
-use crate::logical_plan::LogicalPlan;
+use crate::logical_plan::{column_index, LogicalPlan};
 use arrow::array::Array;
 use std::any::Any;
 use std::fmt::Display;
@@ -62,6 +63,29 @@ impl LogicalPlan for Aggregate {
         Arc::from(Schema { fields })
     }
 
+    /// One row without grouping, otherwise at most one row per distinct value of a single group
+    /// column or per input row
+    fn statistics(&self) -> Statistics {
+        if self.group_expr.is_empty() {
+            return Statistics {
+                row_count: Some(1),
+                is_exact: true,
+                ..Default::default()
+            };
+        }
+        let input = self.input.statistics();
+        let groups = match self.group_expr.as_slice() {
+            [expr] => column_index(expr, &self.input.schema())
+                .and_then(|i| input.columns.get(i))
+                .and_then(|c| Some(c.distinct_count? + usize::from(c.null_count? > 0))),
+            _ => None,
+        };
+        Statistics {
+            row_count: groups.or(input.row_count),
+            ..Default::default()
+        }
+    }
+
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
         vec![self.input.clone()]
     }
@@ -73,6 +97,7 @@ impl LogicalPlan for Aggregate {
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::data_source::Source;
     use crate::datatypes::arrow_types::ArrowType;
     use crate::logical_plan::aggregate::Aggregate;
@@ -83,7 +108,8 @@ mod test {
 
     #[test]
     fn test_logical_selection() {
-        let csv = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let scan = Scan::new("employee".to_string(), csv, vec![]);
 
         let group_expr = vec![col("state")];
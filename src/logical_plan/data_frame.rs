@@ -1,8 +1,13 @@
+use crate::data_source::csv_data_source::CsvWriteOptions;
 use crate::datatypes::schema::Schema;
+use crate::execution::ExecutionContext;
 use crate::logical_plan::aggregate::Aggregate;
 use crate::logical_plan::expressions::Expr;
+use crate::logical_plan::join::{Join, JoinType};
+use crate::logical_plan::limit::Limit;
 use crate::logical_plan::projection::Projection;
 use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::{Sort, SortExpr};
 use crate::logical_plan::LogicalPlan;
 use std::sync::Arc;
 
@@ -20,11 +25,38 @@ pub trait DataFrame {
         aggregate_expr: Vec<Arc<Expr>>,
     ) -> Arc<dyn DataFrame>;
 
+    /// Join with another DataFrame on pairs of equal columns of this and the other DataFrame.
+    /// Columns present on both sides are qualified with the name of their table.
+    fn join(
+        self: Arc<Self>,
+        right: Arc<dyn DataFrame>,
+        join_type: JoinType,
+        on: Vec<(&str, &str)>,
+    ) -> Arc<dyn DataFrame>;
+
+    /// Sort all rows by the given sort keys
+    fn sort(self: Arc<Self>, sort_expr: Vec<SortExpr>) -> Arc<dyn DataFrame>;
+
+    /// Return at most the given number of rows
+    fn limit(self: Arc<Self>, limit: usize) -> Arc<dyn DataFrame>;
+
+    /// Skip the given number of rows
+    fn offset(self: Arc<Self>, offset: usize) -> Arc<dyn DataFrame>;
+
     /// Returns the schema of the data that will be produced by this DataFrame.
     fn schema(self: Arc<Self>) -> Arc<Schema>;
 
     /// Get the logical plan
     fn logical_plan(self: Arc<Self>) -> Arc<dyn LogicalPlan>;
+
+    /// Execute the DataFrame in the context and write its batches to a CSV file, returns the
+    /// number of written rows
+    fn write_csv(
+        self: Arc<Self>,
+        ctx: &ExecutionContext,
+        file_name: &str,
+        options: CsvWriteOptions,
+    ) -> anyhow::Result<usize>;
 }
 
 pub struct DataFrameImpl {
@@ -54,6 +86,47 @@ impl DataFrame for DataFrameImpl {
         })
     }
 
+    fn join(
+        self: Arc<Self>,
+        right: Arc<dyn DataFrame>,
+        join_type: JoinType,
+        on: Vec<(&str, &str)>,
+    ) -> Arc<dyn DataFrame> {
+        let right = right.logical_plan();
+        let on = on
+            .into_iter()
+            .map(|(l, r)| (l.to_string(), r.to_string()))
+            .collect();
+        Arc::new(DataFrameImpl {
+            plan: Join::new(
+                self.plan.clone(),
+                right.clone(),
+                join_type,
+                on,
+                Join::qualifier(self.plan.as_ref()),
+                Join::qualifier(right.as_ref()),
+            ),
+        })
+    }
+
+    fn sort(self: Arc<Self>, sort_expr: Vec<SortExpr>) -> Arc<dyn DataFrame> {
+        Arc::new(DataFrameImpl {
+            plan: Sort::new(self.plan.clone(), sort_expr),
+        })
+    }
+
+    fn limit(self: Arc<Self>, limit: usize) -> Arc<dyn DataFrame> {
+        Arc::new(DataFrameImpl {
+            plan: Limit::new(self.plan.clone(), 0, Some(limit)),
+        })
+    }
+
+    fn offset(self: Arc<Self>, offset: usize) -> Arc<dyn DataFrame> {
+        Arc::new(DataFrameImpl {
+            plan: Limit::new(self.plan.clone(), offset, None),
+        })
+    }
+
     fn schema(self: Arc<Self>) -> Arc<Schema> {
         self.plan.schema()
     }
@@ -61,10 +134,20 @@ impl DataFrame for DataFrameImpl {
     fn logical_plan(self: Arc<Self>) -> Arc<dyn LogicalPlan> {
         self.plan.clone()
     }
+
+    fn write_csv(
+        self: Arc<Self>,
+        ctx: &ExecutionContext,
+        file_name: &str,
+        options: CsvWriteOptions,
+    ) -> anyhow::Result<usize> {
+        ctx.write_csv(self, file_name, options)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::data_source::Source;
     use crate::logical_plan::data_frame::{DataFrame, DataFrameImpl};
     use crate::logical_plan::expressions::aggr_expr::{count, max, min};
@@ -130,11 +213,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_limit_data_frame() {
+        let df = test_csv()
+            .project(vec![col("id"), col("first_name")])
+            .offset(1)
+            .limit(2);
+
+        assert_eq!(
+            "Limit: 2\n\
+            \tLimit: None; offset=1\n\
+            \t\tProjection: id, first_name\n\
+            \t\t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
     fn test_csv() -> Arc<dyn DataFrame> {
         Arc::new(DataFrameImpl {
             plan: Scan::new(
                 "testdata/employee.csv".to_string(),
-                Source::from_csv("testdata/employee.csv", None, true, 1024),
+                Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024)
+                    .unwrap(),
                 vec![],
             ),
         })
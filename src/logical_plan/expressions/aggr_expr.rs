@@ -3,6 +3,7 @@ use crate::datatypes::schema::Field;
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::LogicalPlan;
+use anyhow::bail;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
@@ -45,23 +46,58 @@ impl LogicalExpr for AggrExpr {
 pub struct AggregationExprBase {
     pub name: String,
     pub expr: Arc<Expr>,
+    /// fixed output type of the aggregation, if None the type of the input expression is used
+    pub data_type: Option<ArrowType>,
 }
 
 impl AggregationExprBase {
     fn new(name: String, expr: Arc<Expr>) -> Self {
-        Self { name, expr }
+        Self {
+            name,
+            expr,
+            data_type: None,
+        }
+    }
+
+    fn with_data_type(name: String, expr: Arc<Expr>, data_type: ArrowType) -> Self {
+        Self {
+            name,
+            expr,
+            data_type: Some(data_type),
+        }
     }
 }
 
 impl LogicalExpr for AggregationExprBase {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let data_type = match &self.data_type {
+            Some(data_type) => data_type.clone(),
+            None => self.expr.to_field(input)?.data_type.clone(),
+        };
         Ok(Arc::from(Field {
             name: format!("{}({})", self.name, self.expr), // todo check if always valid when not using just self.name
-            data_type: self.expr.to_field(input)?.data_type.clone(),
+            data_type,
+            nullable: true,
         }))
     }
 }
 
+impl AggregationExprBase {
+    /// Field of an aggregate the accumulators only implement for numbers
+    fn numeric_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let data_type = self.expr.to_field(input.clone())?.data_type.clone();
+        if !data_type.is_numeric() {
+            bail!(
+                "{} is not supported for {:?}: {}",
+                self.name,
+                data_type,
+                self
+            );
+        }
+        self.to_field(input)
+    }
+}
+
 impl Display for AggregationExprBase {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}({})", self.name, self.expr)
@@ -90,7 +126,7 @@ impl Max {
 
 impl LogicalExpr for Max {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
-        self.base.to_field(input)
+        self.base.numeric_field(input)
     }
 }
 
@@ -119,7 +155,7 @@ impl Min {
 
 impl LogicalExpr for Min {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
-        self.base.to_field(input)
+        self.base.numeric_field(input)
     }
 }
 
@@ -149,7 +185,7 @@ impl Sum {
 
 impl LogicalExpr for Sum {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
-        self.base.to_field(input)
+        self.base.numeric_field(input)
     }
 }
 
@@ -171,14 +207,18 @@ pub fn avg(expr: Arc<Expr>) -> Arc<Expr> {
 impl Avg {
     fn new(input: Arc<Expr>) -> Self {
         Self {
-            base: AggregationExprBase::new("AVG".to_string(), input),
+            base: AggregationExprBase::with_data_type(
+                "AVG".to_string(),
+                input,
+                ArrowType::DoubleType,
+            ),
         }
     }
 }
 
 impl LogicalExpr for Avg {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
-        self.base.to_field(input)
+        self.base.numeric_field(input)
     }
 }
 
@@ -201,17 +241,18 @@ pub fn count(expr: Arc<Expr>) -> Arc<Expr> {
 impl Count {
     fn new(input: Arc<Expr>) -> Self {
         Self {
-            base: AggregationExprBase::new("COUNT".to_string(), input),
+            base: AggregationExprBase::with_data_type(
+                "COUNT".to_string(),
+                input,
+                ArrowType::Int64Type,
+            ),
         }
     }
 }
 
 impl LogicalExpr for Count {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
-        Ok(Arc::from(Field {
-            name: "COUNT".to_string(),
-            data_type: ArrowType::Int32Type,
-        }))
+        self.base.to_field(input)
     }
 }
 
@@ -236,7 +277,11 @@ pub fn count_distinct(expr: Arc<Expr>) -> Arc<Expr> {
 impl CountDistinct {
     fn new(input: Arc<Expr>) -> Self {
         Self {
-            base: AggregationExprBase::new("COUNT DISTINCT".to_string(), input),
+            base: AggregationExprBase::with_data_type(
+                "COUNT DISTINCT".to_string(),
+                input,
+                ArrowType::Int64Type,
+            ),
         }
     }
 }
@@ -244,8 +289,9 @@ impl CountDistinct {
 impl LogicalExpr for CountDistinct {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
         Ok(Arc::from(Field {
-            name: "COUNT_DISTINCT".to_string(),
-            data_type: ArrowType::Int32Type,
+            name: self.to_string(),
+            data_type: self.base.to_field(input)?.data_type.clone(),
+            nullable: false,
         }))
     }
 }
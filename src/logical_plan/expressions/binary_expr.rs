@@ -7,6 +7,7 @@ use crate::datatypes::schema::Field;
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::LogicalPlan;
+use anyhow::bail;
 use arrow::compute::kernels::cmp::gt;
 use std::fmt;
 use std::fmt::{Display, Formatter};
@@ -153,10 +154,38 @@ impl BooleanBinaryExpr {
 }
 
 impl LogicalExpr for BooleanBinaryExpr {
-    fn to_field(&self, _input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+    /// Fails for operands the physical expression can't evaluate, AND and OR need booleans
+    /// and comparisons operands of the same type other than boolean
+    fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let l = self.base.l.to_field(input.clone())?.data_type.clone();
+        let r = self.base.r.to_field(input)?.data_type.clone();
+        match self.base.op.as_str() {
+            "AND" | "OR" if l != ArrowType::BooleanType || r != ArrowType::BooleanType => bail!(
+                "{} requires boolean operands, got {:?} and {:?}: {}",
+                self.base.op,
+                l,
+                r,
+                self.base
+            ),
+            "AND" | "OR" => {}
+            _ if l != r => bail!(
+                "cannot compare values of different type {:?} and {:?}: {}",
+                l,
+                r,
+                self.base
+            ),
+            _ if l == ArrowType::BooleanType => bail!(
+                "operator {} is not supported for {:?}: {}",
+                self.base.op,
+                l,
+                self.base
+            ),
+            _ => {}
+        }
         Ok(Arc::from(Field {
             name: self.base.name.clone(),
             data_type: ArrowType::BooleanType,
+            nullable: true,
         }))
     }
 }
@@ -12,6 +12,7 @@ pub enum LiteralExpr {
     LiteralLong(LiteralLong),
     LiteralFloat(LiteralFloat),
     LiteralDouble(LiteralDouble),
+    LiteralBoolean(LiteralBoolean),
 }
 impl Display for LiteralExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -20,6 +21,7 @@ impl Display for LiteralExpr {
             LiteralExpr::LiteralLong(l) => l.fmt(f),
             LiteralExpr::LiteralFloat(l) => l.fmt(f),
             LiteralExpr::LiteralDouble(l) => l.fmt(f),
+            LiteralExpr::LiteralBoolean(l) => l.fmt(f),
         }
     }
 }
@@ -31,6 +33,7 @@ impl LogicalExpr for LiteralExpr {
             LiteralExpr::LiteralLong(l) => l.to_field(input),
             LiteralExpr::LiteralFloat(l) => l.to_field(input),
             LiteralExpr::LiteralDouble(l) => l.to_field(input),
+            LiteralExpr::LiteralBoolean(l) => l.to_field(input),
         }
     }
 }
@@ -58,6 +61,7 @@ impl LogicalExpr for LiteralString {
         Ok(Arc::from(Field {
             name: self.str.clone(),
             data_type: ArrowType::StringType,
+            nullable: false,
         }))
     }
 }
@@ -85,6 +89,7 @@ impl LogicalExpr for LiteralLong {
         Ok(Arc::from(Field {
             name: self.i.to_string(),
             data_type: ArrowType::Int64Type,
+            nullable: false,
         }))
     }
 }
@@ -114,6 +119,7 @@ impl LogicalExpr for LiteralFloat {
         Ok(Arc::from(Field {
             name: self.i.to_string(),
             data_type: ArrowType::FloatType,
+            nullable: false,
         }))
     }
 }
@@ -143,6 +149,37 @@ impl LogicalExpr for LiteralDouble {
         Ok(Arc::from(Field {
             name: self.i.to_string(),
             data_type: ArrowType::DoubleType,
+            nullable: false,
+        }))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+/// Logical expression representing a literal boolean value.
+pub struct LiteralBoolean {
+    pub b: bool,
+}
+
+/// Convenience method to create a LiteralBoolean
+pub fn lit_bool(value: bool) -> Arc<Expr> {
+    Arc::from(Expr::Literal(LiteralExpr::LiteralBoolean(LiteralBoolean {
+        b: value,
+    })))
+}
+
+impl Display for LiteralBoolean {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.b)
+    }
+}
+
+impl LogicalExpr for LiteralBoolean {
+    fn to_field(&self, _input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        Ok(Arc::from(Field {
+            name: self.b.to_string(),
+            data_type: ArrowType::BooleanType,
+            nullable: false,
         }))
     }
 }
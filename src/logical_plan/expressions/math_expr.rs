@@ -3,6 +3,7 @@ use crate::logical_plan::expressions::binary_expr::{Base, BinaryExprBase};
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::LogicalPlan;
+use anyhow::bail;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
@@ -85,10 +86,24 @@ impl MathExprExt for Expr {
 }
 
 impl LogicalExpr for BinaryExprBase {
+    /// Fails for operands the physical expression can't evaluate, which are numbers of
+    /// different types or no numbers
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let l = self.l.to_field(input.clone())?.data_type.clone();
+        let r = self.r.to_field(input)?.data_type.clone();
+        if l != r || !l.is_numeric() {
+            bail!(
+                "operator {} is not supported for {:?} and {:?}: {}",
+                self.op,
+                l,
+                r,
+                self
+            );
+        }
         Ok(Arc::from(Field {
             name: self.name.clone(),
-            data_type: self.l.to_field(input)?.data_type.clone(),
+            data_type: l,
+            nullable: true,
         }))
     }
 }
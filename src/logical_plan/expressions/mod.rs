@@ -15,7 +15,7 @@ use crate::logical_plan::expressions::math_expr::MathExpr;
 use crate::logical_plan::expressions::unary_expr::UnaryExpr;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::LogicalPlan;
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use std::fmt::{Display, Formatter, Pointer};
 use std::sync::Arc;
 
@@ -101,6 +101,11 @@ pub struct ColumnIndex {
     pub i: usize,
 }
 
+/// Convenience method to create a ColumnIndex reference
+pub fn col_index(i: usize) -> Arc<Expr> {
+    Arc::from(Expr::ColumnIndex(ColumnIndex { i }))
+}
+
 impl Display for ColumnIndex {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.i)
@@ -149,9 +154,14 @@ impl Display for CastExpr {
 
 impl LogicalExpr for CastExpr {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let field = self.expr.to_field(input)?;
+        if self.data_type == ArrowType::BooleanType {
+            bail!("cast to {:?} is not supported: {}", self.data_type, self);
+        }
         Ok(Arc::from(Field {
-            name: self.expr.to_field(input)?.name.clone(),
+            name: field.name.clone(),
             data_type: self.data_type.clone(),
+            nullable: field.nullable,
         }))
     }
 }
@@ -186,9 +196,11 @@ impl Display for Alias {
 
 impl LogicalExpr for Alias {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let field = self.expr.to_field(input)?;
         Ok(Arc::from(Field {
             name: self.alias.clone(),
-            data_type: self.expr.to_field(input)?.data_type.clone(),
+            data_type: field.data_type.clone(),
+            nullable: field.nullable,
         }))
     }
 }
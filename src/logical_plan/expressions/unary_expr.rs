@@ -3,18 +3,40 @@ use crate::datatypes::schema::Field;
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::LogicalPlan;
+use anyhow::bail;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 pub enum UnaryExpr {
     Not(Not),
+    Negate(Negate),
+    IsNull(IsNull),
+    IsNotNull(IsNotNull),
+}
+
+impl UnaryExpr {
+    pub fn get_expr(&self) -> Arc<Expr> {
+        self.get_base().expr.clone()
+    }
+
+    pub fn get_base(&self) -> &UnaryExprBase {
+        match self {
+            UnaryExpr::Not(u) => &u.base,
+            UnaryExpr::Negate(u) => &u.base,
+            UnaryExpr::IsNull(u) => &u.base,
+            UnaryExpr::IsNotNull(u) => &u.base,
+        }
+    }
 }
 
 impl Display for UnaryExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             UnaryExpr::Not(u) => u.fmt(f),
+            UnaryExpr::Negate(u) => u.fmt(f),
+            UnaryExpr::IsNull(u) => u.fmt(f),
+            UnaryExpr::IsNotNull(u) => u.fmt(f),
         }
     }
 }
@@ -23,14 +45,17 @@ impl LogicalExpr for UnaryExpr {
     fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
         match self {
             UnaryExpr::Not(u) => u.to_field(input),
+            UnaryExpr::Negate(u) => u.to_field(input),
+            UnaryExpr::IsNull(u) => u.to_field(input),
+            UnaryExpr::IsNotNull(u) => u.to_field(input),
         }
     }
 }
 
 pub struct UnaryExprBase {
-    name: String,
-    op: String,
-    expr: Arc<Expr>,
+    pub name: String,
+    pub op: String,
+    pub expr: Arc<Expr>,
 }
 
 impl UnaryExprBase {
@@ -41,7 +66,7 @@ impl UnaryExprBase {
 
 impl Display for UnaryExprBase {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.op, self.expr.to_string())
+        write!(f, "{} {}", self.op, self.expr)
     }
 }
 
@@ -51,6 +76,11 @@ pub struct Not {
     base: UnaryExprBase,
 }
 
+/// Convenience method to create a Not
+pub fn not(expr: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Unary(UnaryExpr::Not(Not::new(expr))))
+}
+
 impl Not {
     fn new(expr: Arc<Expr>) -> Self {
         Self {
@@ -60,10 +90,19 @@ impl Not {
 }
 
 impl LogicalExpr for Not {
-    fn to_field(&self, _input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+    fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let data_type = self.base.expr.to_field(input)?.data_type.clone();
+        if data_type != ArrowType::BooleanType {
+            bail!(
+                "NOT requires a boolean operand, got {:?}: {}",
+                data_type,
+                self
+            );
+        }
         Ok(Arc::from(Field {
             name: "NOT".to_string(),
             data_type: ArrowType::BooleanType,
+            nullable: true,
         }))
     }
 }
@@ -73,3 +112,125 @@ impl Display for Not {
         write!(f, "{}", self.base)
     }
 }
+
+////////////////////////////////////////////////////////////////////////////
+
+/// Arithmetic negation of a numeric expression e.g. `-x`
+pub struct Negate {
+    base: UnaryExprBase,
+}
+
+/// Convenience method to create a Negate
+pub fn negate(expr: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Unary(UnaryExpr::Negate(Negate::new(expr))))
+}
+
+impl Negate {
+    fn new(expr: Arc<Expr>) -> Self {
+        Self {
+            base: UnaryExprBase::new("negate".to_string(), "-".to_string(), expr),
+        }
+    }
+}
+
+impl LogicalExpr for Negate {
+    fn to_field(&self, input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        let field = self.base.expr.to_field(input)?;
+        if !matches!(
+            field.data_type,
+            ArrowType::Int8Type
+                | ArrowType::Int16Type
+                | ArrowType::Int32Type
+                | ArrowType::Int64Type
+                | ArrowType::FloatType
+                | ArrowType::DoubleType
+        ) {
+            bail!(
+                "cannot negate values of type {:?}: {}",
+                field.data_type,
+                self
+            );
+        }
+        Ok(Arc::from(Field {
+            name: self.base.name.clone(),
+            data_type: field.data_type.clone(),
+            nullable: field.nullable,
+        }))
+    }
+}
+
+impl Display for Negate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.base.op, self.base.expr)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+pub struct IsNull {
+    base: UnaryExprBase,
+}
+
+/// Convenience method to create an IsNull
+pub fn is_null(expr: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Unary(UnaryExpr::IsNull(IsNull::new(expr))))
+}
+
+impl IsNull {
+    fn new(expr: Arc<Expr>) -> Self {
+        Self {
+            base: UnaryExprBase::new("is_null".to_string(), "IS NULL".to_string(), expr),
+        }
+    }
+}
+
+impl LogicalExpr for IsNull {
+    fn to_field(&self, _input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        Ok(Arc::from(Field {
+            name: self.base.name.clone(),
+            data_type: ArrowType::BooleanType,
+            nullable: false,
+        }))
+    }
+}
+
+impl Display for IsNull {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.base.expr, self.base.op)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+pub struct IsNotNull {
+    base: UnaryExprBase,
+}
+
+/// Convenience method to create an IsNotNull
+pub fn is_not_null(expr: Arc<Expr>) -> Arc<Expr> {
+    Arc::new(Expr::Unary(UnaryExpr::IsNotNull(IsNotNull::new(expr))))
+}
+
+impl IsNotNull {
+    fn new(expr: Arc<Expr>) -> Self {
+        Self {
+            base: UnaryExprBase::new("is_not_null".to_string(), "IS NOT NULL".to_string(), expr),
+        }
+    }
+}
+
+impl LogicalExpr for IsNotNull {
+    fn to_field(&self, _input: Arc<dyn LogicalPlan>) -> anyhow::Result<Arc<Field>> {
+        Ok(Arc::from(Field {
+            name: self.base.name.clone(),
+            data_type: ArrowType::BooleanType,
+            nullable: false,
+        }))
+    }
+}
+
+impl Display for IsNotNull {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.base.expr, self.base.op)
+    }
+}
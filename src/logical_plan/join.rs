@@ -0,0 +1,237 @@
+use crate::datatypes::schema::{Field, Schema};
+use crate::logical_plan::scan::Scan;
+use crate::logical_plan::LogicalPlan;
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    /// Left rows with at least one match, the right columns are not part of the output
+    Semi,
+    /// Left rows without any match, the right columns are not part of the output
+    Anti,
+}
+
+impl JoinType {
+    /// Whether the columns of the right input are part of the output
+    pub fn includes_right(&self) -> bool {
+        !matches!(self, JoinType::Semi | JoinType::Anti)
+    }
+}
+
+/// Logical plan representing an equi-join of two inputs. `on` holds pairs of column names of
+/// the left and right input which must be equal. Columns present on both sides are renamed to
+/// `<qualifier>.<name>` in the output.
+pub struct Join {
+    pub left: Arc<dyn LogicalPlan>,
+    pub right: Arc<dyn LogicalPlan>,
+    pub join_type: JoinType,
+    pub on: Vec<(String, String)>,
+    pub left_qualifier: String,
+    pub right_qualifier: String,
+    schema: Arc<Schema>,
+}
+
+impl Join {
+    pub fn new(
+        left: Arc<dyn LogicalPlan>,
+        right: Arc<dyn LogicalPlan>,
+        join_type: JoinType,
+        on: Vec<(String, String)>,
+        left_qualifier: impl Into<String>,
+        right_qualifier: impl Into<String>,
+    ) -> Arc<Self> {
+        let left_qualifier = left_qualifier.into();
+        let right_qualifier = right_qualifier.into();
+        let schema = Self::derive_schema(
+            &left.schema(),
+            &right.schema(),
+            join_type,
+            &left_qualifier,
+            &right_qualifier,
+        );
+        Arc::new(Join {
+            left,
+            right,
+            join_type,
+            on,
+            left_qualifier,
+            right_qualifier,
+            schema,
+        })
+    }
+
+    /// Same join on top of different inputs, used by optimizer rules which rewrite the inputs
+    pub fn with_inputs(
+        &self,
+        left: Arc<dyn LogicalPlan>,
+        right: Arc<dyn LogicalPlan>,
+    ) -> Arc<Self> {
+        Self::new(
+            left,
+            right,
+            self.join_type,
+            self.on.clone(),
+            self.left_qualifier.clone(),
+            self.right_qualifier.clone(),
+        )
+    }
+
+    /// Name of the table the plan reads from, i.e. the file name without extension of the
+    /// leftmost scan, used to qualify duplicate columns
+    pub fn qualifier(plan: &dyn LogicalPlan) -> String {
+        match plan.as_any().downcast_ref::<Scan>() {
+            Some(scan) => Path::new(&scan.path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| scan.path.clone()),
+            None => plan
+                .children()
+                .first()
+                .map(|child| Self::qualifier(child.as_ref()))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn derive_schema(
+        left: &Schema,
+        right: &Schema,
+        join_type: JoinType,
+        left_qualifier: &str,
+        right_qualifier: &str,
+    ) -> Arc<Schema> {
+        if !join_type.includes_right() {
+            return Arc::new(left.clone());
+        }
+        let left_names: HashSet<&str> = left.fields.iter().map(|f| f.name.as_str()).collect();
+        let right_names: HashSet<&str> = right.fields.iter().map(|f| f.name.as_str()).collect();
+        // the side which is padded with nulls for rows without a match becomes nullable
+        let qualify = |f: &Arc<Field>, qualifier: &str, other: &HashSet<&str>, padded: bool| {
+            let name = if other.contains(f.name.as_str()) {
+                format!("{}.{}", qualifier, f.name)
+            } else {
+                f.name.clone()
+            };
+            Arc::new(Field {
+                name,
+                data_type: f.data_type.clone(),
+                nullable: f.nullable || padded,
+            })
+        };
+        let left_padded = matches!(join_type, JoinType::Right | JoinType::Full);
+        let right_padded = matches!(join_type, JoinType::Left | JoinType::Full);
+        let fields = left
+            .fields
+            .iter()
+            .map(|f| qualify(f, left_qualifier, &right_names, left_padded))
+            .chain(
+                right
+                    .fields
+                    .iter()
+                    .map(|f| qualify(f, right_qualifier, &left_names, right_padded)),
+            )
+            .collect();
+        Arc::new(Schema { fields })
+    }
+}
+
+impl Display for Join {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Join: type={:?}, on={}",
+            self.join_type,
+            self.on
+                .iter()
+                .map(|(l, r)| format!(
+                    "{}.{} = {}.{}",
+                    self.left_qualifier, l, self.right_qualifier, r
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl LogicalPlan for Join {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::Source;
+    use crate::logical_plan::format;
+    use crate::logical_plan::join::{Join, JoinType};
+    use crate::logical_plan::scan::Scan;
+    use crate::logical_plan::LogicalPlan;
+
+    #[test]
+    fn test_logical_join() {
+        let employee = Scan::new(
+            "testdata/employee.csv",
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap(),
+            vec![],
+        );
+        let state = Scan::new(
+            "testdata/state.csv",
+            Source::from_csv("testdata/state.csv", None, CsvOptions::default(), 1024).unwrap(),
+            vec![],
+        );
+        let join = Join::new(
+            employee.clone(),
+            state.clone(),
+            JoinType::Inner,
+            vec![("state".to_string(), "state".to_string())],
+            Join::qualifier(employee.as_ref()),
+            Join::qualifier(state.as_ref()),
+        );
+
+        assert_eq!(
+            "Join: type=Inner, on=employee.state = state.state\n\
+            \tScan: testdata/employee.csv; projection=None\n\
+            \tScan: testdata/state.csv; projection=None\n",
+            format(join.clone(), 0)
+        );
+        let names: Vec<String> = join
+            .schema()
+            .fields
+            .iter()
+            .map(|f| f.name.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                "id",
+                "first_name",
+                "last_name",
+                "employee.state",
+                "job_title",
+                "salary",
+                "state.state",
+                "name"
+            ],
+            names
+        );
+
+        let semi = Join::new(employee, state, JoinType::Semi, vec![], "e", "s");
+        assert_eq!(6, semi.schema().fields.len());
+        assert_eq!("state", semi.schema().fields[3].name);
+    }
+}
@@ -1,24 +1,38 @@
+use crate::data_source::statistics::{ColumnStatistics, Statistics};
 use crate::datatypes::schema::Schema;
 use crate::logical_plan::LogicalPlan;
 use std::any::Any;
 use std::fmt::Display;
 use std::sync::Arc;
 
-/// Logical plan representing a limit
+/// Logical plan representing a limit, skipping the first `offset` rows of the input
+/// and returning at most `limit` rows afterwards
 pub struct Limit {
-    input: Arc<dyn LogicalPlan>,
-    limit: usize,
+    pub input: Arc<dyn LogicalPlan>,
+    pub offset: usize,
+    pub limit: Option<usize>,
 }
 
 impl Limit {
-    pub fn new(input: Arc<dyn LogicalPlan>, limit: usize) -> Self {
-        Limit { input, limit }
+    pub fn new(input: Arc<dyn LogicalPlan>, offset: usize, limit: Option<usize>) -> Arc<Self> {
+        Arc::new(Limit {
+            input,
+            offset,
+            limit,
+        })
     }
 }
 
 impl Display for Limit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Limit: {}", self.limit)
+        match self.limit {
+            Some(limit) => write!(f, "Limit: {}", limit)?,
+            None => write!(f, "Limit: None")?,
+        }
+        if self.offset > 0 {
+            write!(f, "; offset={}", self.offset)?;
+        }
+        Ok(())
     }
 }
 
@@ -27,6 +41,37 @@ impl LogicalPlan for Limit {
         self.input.schema()
     }
 
+    fn statistics(&self) -> Statistics {
+        let input = self.input.statistics();
+        let Some(input_rows) = input.row_count else {
+            return Statistics {
+                row_count: self.limit,
+                ..Default::default()
+            };
+        };
+        let rows = input_rows.saturating_sub(self.offset);
+        let rows = self.limit.map_or(rows, |limit| rows.min(limit));
+        if rows == input_rows {
+            return input;
+        }
+        // the bounds of the columns still hold, the nulls may have been skipped
+        let columns = input
+            .columns
+            .into_iter()
+            .map(|column| ColumnStatistics {
+                null_count: None,
+                distinct_count: column.distinct_count.map(|n| n.min(rows)),
+                ..column
+            })
+            .collect();
+        Statistics {
+            row_count: Some(rows),
+            byte_size: None,
+            columns,
+            is_exact: input.is_exact,
+        }
+    }
+
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
         vec![self.input.clone()]
     }
@@ -35,3 +80,26 @@ impl LogicalPlan for Limit {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::Source;
+    use crate::logical_plan::format;
+    use crate::logical_plan::limit::Limit;
+    use crate::logical_plan::scan::Scan;
+
+    #[test]
+    fn test_logical_limit() {
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+        let scan = Scan::new("employee".to_string(), csv, vec![]);
+
+        let limit = Limit::new(Limit::new(scan, 0, Some(3)), 1, None);
+        let plan_string = format(limit, 0);
+        assert_eq!(
+            "Limit: None; offset=1\n\tLimit: 3\n\t\tScan: employee; projection=None\n",
+            plan_string
+        );
+    }
+}
@@ -1,13 +1,17 @@
 pub mod aggregate;
 pub mod data_frame;
 pub mod expressions;
+pub mod join;
 pub mod limit;
 pub mod logical_expr;
 pub mod projection;
 pub mod scan;
 pub mod selection;
+pub mod sort;
 
+use crate::data_source::statistics::Statistics;
 use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::Expr;
 use std::any::Any;
 use std::sync::Arc;
 
@@ -15,9 +19,24 @@ pub trait LogicalPlan: ToString + Any {
     fn schema(&self) -> Arc<Schema>;
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>>;
 
+    /// Statistics of the output, e.g. for optimizer rules, unknown by default
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
+/// Position of the column an expression reads in the schema, following aliases
+pub(crate) fn column_index(expr: &Expr, schema: &Schema) -> Option<usize> {
+    match expr {
+        Expr::Column(c) => schema.fields.iter().position(|f| f.name == c.name),
+        Expr::ColumnIndex(c) => Some(c.i).filter(|i| *i < schema.fields.len()),
+        Expr::Alias(a) => column_index(&a.expr, schema),
+        _ => None,
+    }
+}
+
 /// trait to pretty print LogicalPlan
 /// outside logical plan because otherwise it does not work with ?Sized
 pub trait LogicalPlanPrinter {
@@ -46,19 +65,27 @@ fn format(plan: Arc<dyn LogicalPlan>, indent: usize) -> String {
 
 #[cfg(test)]
 mod test {
-    use crate::data_source::Source;
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::statistics::StatisticsValue;
+    use crate::data_source::{DataSource, Source};
+    use crate::datatypes::record_batch::RecordBatch;
+    use crate::logical_plan::aggregate::Aggregate;
+    use crate::logical_plan::expressions::aggr_expr::count;
     use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
     use crate::logical_plan::expressions::col;
     use crate::logical_plan::expressions::literal_expr::lit_str;
     use crate::logical_plan::format;
+    use crate::logical_plan::limit::Limit;
     use crate::logical_plan::projection::Projection;
     use crate::logical_plan::scan::Scan;
     use crate::logical_plan::selection::Selection;
+    use crate::logical_plan::LogicalPlan;
 
     #[test]
     fn test_build_logical_plan() {
         // create a plan to represent the data source
-        let csv = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         // create a plan to represent the scan of the data source (FROM)
         let scan = Scan::new("employee".to_string(), csv, vec![]);
         // create a plan to represent the selection (WHERE)
@@ -84,7 +111,8 @@ mod test {
             Selection::new(
                 Scan::new(
                     "employee".to_string(),
-                    Source::from_csv("testdata/employee.csv", None, true, 1024),
+                    Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024)
+                        .unwrap(),
                     vec![],
                 ),
                 col("state").eq(lit_str("CO")),
@@ -99,4 +127,36 @@ mod test {
             plan_string
         );
     }
+
+    #[test]
+    fn test_statistics_of_plan() {
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+        let batches: Vec<RecordBatch> = csv.scan(vec![]).collect();
+        let source = Source::from_in_memory(csv.schema(), batches);
+        let scan = Scan::new("employee", source, vec![]);
+
+        let statistics = scan.statistics();
+        assert_eq!(Some(4), statistics.row_count);
+        assert!(statistics.is_exact);
+
+        let projection = Projection::new(scan.clone(), vec![col("salary")]);
+        let limit = Limit::new(projection, 1, Some(2));
+        let statistics = limit.statistics();
+        assert_eq!(Some(2), statistics.row_count);
+        assert!(statistics.is_exact);
+        assert_eq!(None, statistics.byte_size);
+        assert_eq!(
+            Some(StatisticsValue::Number(10000.0)),
+            statistics.columns[0].min
+        );
+
+        let aggregate = Aggregate::new(scan.clone(), vec![col("state")], vec![count(col("id"))]);
+        assert_eq!(Some(3), aggregate.statistics().row_count);
+
+        let selection = Selection::new(scan, col("state").eq(lit_str("CO")));
+        let statistics = selection.statistics();
+        assert_eq!(Some(4), statistics.row_count);
+        assert!(!statistics.is_exact);
+    }
 }
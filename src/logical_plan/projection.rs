@@ -1,7 +1,8 @@
+use crate::data_source::statistics::Statistics;
 use crate::datatypes::schema::Schema;
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::logical_expr::LogicalExpr;
-use crate::logical_plan::LogicalPlan;
+use crate::logical_plan::{column_index, LogicalPlan};
 use std::any::Any;
 use std::fmt::Display;
 use std::sync::Arc;
@@ -39,6 +40,19 @@ impl LogicalPlan for Projection {
             .collect();
         Arc::from(Schema { fields })
     }
+
+    /// Statistics of the projected columns, computed columns are unknown
+    fn statistics(&self) -> Statistics {
+        let input_schema = self.input.schema();
+        let indices: Vec<Option<usize>> = self
+            .expr
+            .iter()
+            .map(|e| column_index(e, &input_schema))
+            .collect();
+        self.input
+            .statistics()
+            .project(&indices, input_schema.fields.len())
+    }
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
         vec![self.input.clone()]
     }
@@ -50,6 +64,7 @@ impl LogicalPlan for Projection {
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::data_source::Source;
     use crate::logical_plan::expressions::col;
     use crate::logical_plan::format;
@@ -58,7 +73,8 @@ mod test {
 
     #[test]
     fn test_logical_projection() {
-        let csv = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let scan = Scan::new("employee".to_string(), csv, vec![]);
 
         let projection = Projection::new(scan, vec![col("id")]);
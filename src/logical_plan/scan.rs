@@ -1,22 +1,31 @@
-use crate::data_source::{DataSource, Source};
+use crate::data_source::statistics::{AnalyzedStatistics, Statistics};
+use crate::data_source::{DataSource, LimitHint};
 use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::LogicalPlan;
 use std::any::Any;
 use std::fmt::Display;
 use std::sync::Arc;
 
 /// Represents a scan of a data source
+#[derive(Clone)]
 pub struct Scan {
     pub path: String,
-    pub datasource: Arc<Source>,
+    pub datasource: Arc<dyn DataSource>,
     pub projection: Vec<String>,
+    /// predicates pushed down into the data source, referring to columns by name
+    pub filters: Vec<Arc<Expr>>,
+    /// rows needed by the consumer, pushed down from a limit
+    pub limit: Option<LimitHint>,
+    /// statistics collected by scanning the data source, used instead of its own statistics
+    pub analyzed: Option<AnalyzedStatistics>,
     schema: Arc<Schema>,
 }
 
 impl Scan {
     pub fn new(
         path: impl Into<String>,
-        datasource: Arc<Source>,
+        datasource: Arc<dyn DataSource>,
         projection: Vec<String>,
     ) -> Arc<Self> {
         let schema = Self::derive_schema(datasource.clone(), projection.clone());
@@ -24,11 +33,48 @@ impl Scan {
             path: path.into(),
             datasource,
             projection,
+            filters: vec![],
+            limit: None,
+            analyzed: None,
             schema,
         })
     }
 
-    fn derive_schema(datasource: Arc<Source>, projection: Vec<String>) -> Arc<Schema> {
+    /// Copy of the scan reading the given columns
+    pub fn with_projection(&self, projection: Vec<String>) -> Arc<Self> {
+        let schema = Self::derive_schema(self.datasource.clone(), projection.clone());
+        Arc::new(Scan {
+            projection,
+            schema,
+            ..self.clone()
+        })
+    }
+
+    /// Copy of the scan passing the filters to the data source
+    pub fn with_filters(&self, filters: Vec<Arc<Expr>>) -> Arc<Self> {
+        Arc::new(Scan {
+            filters,
+            ..self.clone()
+        })
+    }
+
+    /// Copy of the scan with the number of rows needed by its consumer
+    pub fn with_limit(&self, limit: Option<LimitHint>) -> Arc<Self> {
+        Arc::new(Scan {
+            limit,
+            ..self.clone()
+        })
+    }
+
+    /// Copy of the scan with statistics collected by scanning the data source
+    pub fn with_statistics(&self, analyzed: AnalyzedStatistics) -> Arc<Self> {
+        Arc::new(Scan {
+            analyzed: Some(analyzed),
+            ..self.clone()
+        })
+    }
+
+    fn derive_schema(datasource: Arc<dyn DataSource>, projection: Vec<String>) -> Arc<Schema> {
         let schema = datasource.schema();
         if projection.is_empty() {
             return schema;
@@ -45,10 +91,18 @@ impl Scan {
 impl Display for Scan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.projection.is_empty() {
-            write!(f, "Scan: {}; projection=None", self.path)
+            write!(f, "Scan: {}; projection=None", self.path)?;
         } else {
-            write!(f, "Scan: {}; projection={:?}", self.path, self.projection)
+            write!(f, "Scan: {}; projection={:?}", self.path, self.projection)?;
         }
+        if !self.filters.is_empty() {
+            let filters: Vec<String> = self.filters.iter().map(|e| e.to_string()).collect();
+            write!(f, "; filters=[{}]", filters.join(", "))?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, "; limit={}", limit)?;
+        }
+        Ok(())
     }
 }
 
@@ -57,6 +111,27 @@ impl LogicalPlan for Scan {
         self.schema.clone()
     }
 
+    /// Statistics of the data source, the filters make its counts upper bounds. Analyzed
+    /// statistics are used as long as the data has not been modified since.
+    fn statistics(&self) -> Statistics {
+        let source_schema = self.datasource.schema();
+        let indices: Vec<Option<usize>> = self
+            .schema
+            .fields
+            .iter()
+            .map(|f| source_schema.fields.iter().position(|s| s.name == f.name))
+            .collect();
+        let statistics = match &self.analyzed {
+            Some(analyzed) if analyzed.is_current() => analyzed.statistics.clone(),
+            _ => self.datasource.statistics(),
+        };
+        let statistics = statistics.project(&indices, source_schema.fields.len());
+        match self.filters.is_empty() {
+            true => statistics,
+            false => statistics.inexact(),
+        }
+    }
+
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
         vec![]
     }
@@ -68,13 +143,15 @@ impl LogicalPlan for Scan {
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::data_source::Source;
     use crate::logical_plan::format;
     use crate::logical_plan::scan::Scan;
 
     #[test]
     fn test_logical_scan() {
-        let csv = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let scan = Scan::new("employee".to_string(), csv, vec![]);
         let plan_string = format(scan, 0);
         assert_eq!("Scan: employee; projection=None\n", plan_string);
@@ -1,3 +1,4 @@
+use crate::data_source::statistics::Statistics;
 use crate::datatypes::schema::Schema;
 use crate::logical_plan::expressions::Expr;
 use crate::logical_plan::LogicalPlan;
@@ -27,6 +28,11 @@ impl LogicalPlan for Selection {
         self.input.schema()
     }
 
+    /// The counts of the input are upper bounds of the selected rows
+    fn statistics(&self) -> Statistics {
+        self.input.statistics().inexact()
+    }
+
     fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
         vec![self.input.clone()]
     }
@@ -45,6 +51,7 @@ macro_rules! selection {
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::data_source::Source;
     use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
     use crate::logical_plan::expressions::col;
@@ -55,7 +62,8 @@ mod test {
 
     #[test]
     fn test_logical_selection() {
-        let csv = Source::from_csv("testdata/employee.csv", None, true, 1024);
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
         let scan = Scan::new("employee".to_string(), csv, vec![]);
 
         let filter_expr = col("state").eq(lit_str("CO"));
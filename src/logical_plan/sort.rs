@@ -0,0 +1,126 @@
+use crate::data_source::statistics::Statistics;
+use crate::datatypes::schema::Schema;
+use crate::logical_plan::expressions::Expr;
+use crate::logical_plan::LogicalPlan;
+use std::any::Any;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Sort key of a Sort plan
+#[derive(Clone)]
+pub struct SortExpr {
+    pub expr: Arc<Expr>,
+    pub asc: bool,
+    pub nulls_first: bool,
+}
+
+/// Convenience method to create an ascending sort key, nulls are sorted last
+pub fn asc(expr: Arc<Expr>) -> SortExpr {
+    SortExpr {
+        expr,
+        asc: true,
+        nulls_first: false,
+    }
+}
+
+/// Convenience method to create a descending sort key, nulls are sorted first
+pub fn desc(expr: Arc<Expr>) -> SortExpr {
+    SortExpr {
+        expr,
+        asc: false,
+        nulls_first: true,
+    }
+}
+
+impl SortExpr {
+    pub fn with_nulls_first(mut self, nulls_first: bool) -> Self {
+        self.nulls_first = nulls_first;
+        self
+    }
+}
+
+impl Display for SortExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.expr,
+            if self.asc { "ASC" } else { "DESC" },
+            if self.nulls_first {
+                "NULLS FIRST"
+            } else {
+                "NULLS LAST"
+            }
+        )
+    }
+}
+
+/// Logical plan representing the ordering of all rows of the input by one or more sort keys
+pub struct Sort {
+    pub input: Arc<dyn LogicalPlan>,
+    pub sort_expr: Vec<SortExpr>,
+}
+
+impl Sort {
+    pub fn new(input: Arc<dyn LogicalPlan>, sort_expr: Vec<SortExpr>) -> Arc<Self> {
+        Arc::new(Sort { input, sort_expr })
+    }
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sort: {}",
+            self.sort_expr
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl LogicalPlan for Sort {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn LogicalPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::Source;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::format;
+    use crate::logical_plan::scan::Scan;
+    use crate::logical_plan::sort::{asc, desc, Sort};
+
+    #[test]
+    fn test_logical_sort() {
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+        let scan = Scan::new("employee".to_string(), csv, vec![]);
+        let sort = Sort::new(
+            scan,
+            vec![asc(col("state")).with_nulls_first(true), desc(col("id"))],
+        );
+
+        assert_eq!(
+            "Sort: state ASC NULLS FIRST, id DESC NULLS FIRST\n\tScan: employee; projection=None\n",
+            format(sort, 0)
+        );
+    }
+}
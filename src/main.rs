@@ -1,7 +1,586 @@
-fn main() {
-    println!("{}", say_hello());
+use anyhow::{anyhow, bail};
+use config::{Config, File, Value, ValueKind};
+use rs_query::data_source::compression::Compression;
+use rs_query::data_source::csv_data_source::{CsvOptions, MalformedRowPolicy};
+use rs_query::data_source::listing_data_source::ListingFormat;
+use rs_query::execution::ExecutionContext;
+use rs_query::logical_plan::LogicalPlanPrinter;
+use rs_query::physical_plan::PhysicalPlanPrinter;
+use rs_query::query_planner::QueryPlanner;
+use sqlparser::ast::{Expr, FileFormat, Statement, Value as SqlValue};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::ops::Deref;
+
+const HELP: &str = "\
+SQL statements are terminated with ';' and may span multiple lines.
+
+CREATE EXTERNAL TABLE <name> [STORED AS PARQUET|TEXTFILE|JSONFILE] LOCATION '<file>'
+    [TBLPROPERTIES ('has_header' = 'false', 'delimiter' = '\\t', ...)]
+                                  register a CSV (TEXTFILE), NDJSON or Parquet file as table,
+                                  CSV properties: has_header, delimiter, quote, escape,
+                                  comment, skip_rows, trim, column_names ('a,b,c'),
+                                  on_malformed_row (fail, skip or null)
+ANALYZE TABLE <name>              scan a table to collect row count, min/max, null and
+                                  distinct counts of its columns for the planner
+\\register <name> <file> [noheader] register a CSV, TSV, NDJSON, Parquet or
+                                  Arrow IPC file by its extension, also .gz or .zst
+                                  compressed, directories and globs like
+                                  'sales/*/*.csv' register all their files
+\\describe <name>                  print the columns of a table and their (inferred) types
+\\explain <query>                  print the logical, optimized and physical plan
+\\help                             print this help
+\\quit                             exit the shell";
+
+fn main() -> anyhow::Result<()> {
+    let mut shell = Shell::new(ExecutionContext::new(load_settings()?));
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut buffer = String::new();
+
+    println!("rquery SQL shell, type \\help for help");
+    loop {
+        print!(
+            "{}",
+            if buffer.is_empty() {
+                "rquery> "
+            } else {
+                "     -> "
+            }
+        );
+        std::io::stdout().flush()?;
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let trimmed = line.trim();
+
+        // commands are single line and only accepted outside of a SQL statement
+        if buffer.is_empty() && trimmed.starts_with('\\') {
+            if matches!(trimmed, "\\q" | "\\quit") {
+                break;
+            }
+            print_result(shell.run_command(trimmed));
+            continue;
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if trimmed.ends_with(';') {
+            print_result(shell.run_sql(&buffer));
+            buffer.clear();
+        }
+    }
+    Ok(())
+}
+
+fn print_result(result: anyhow::Result<String>) {
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+/// Reads `configuration/base.yaml` and the file for the `APP_ENVIRONMENT` (default: `local`)
+/// and flattens the nested keys into settings like `csv.batch_size`
+fn load_settings() -> anyhow::Result<HashMap<String, String>> {
+    let environment = std::env::var("APP_ENVIRONMENT").unwrap_or_else(|_| "local".to_string());
+    let config = Config::builder()
+        .add_source(File::with_name("configuration/base").required(false))
+        .add_source(File::with_name(&format!("configuration/{}", environment)).required(false))
+        .build()?;
+
+    let mut settings = HashMap::new();
+    for (key, value) in config.try_deserialize::<HashMap<String, Value>>()? {
+        flatten_setting(key, value, &mut settings)?;
+    }
+    Ok(settings)
+}
+
+fn flatten_setting(
+    key: String,
+    value: Value,
+    settings: &mut HashMap<String, String>,
+) -> anyhow::Result<()> {
+    match value.kind {
+        ValueKind::Table(table) => {
+            for (k, v) in table {
+                flatten_setting(format!("{}.{}", key, k), v, settings)?;
+            }
+        }
+        _ => {
+            settings.insert(key, value.into_string()?);
+        }
+    }
+    Ok(())
+}
+
+/// Default dialect for the file, tab separated for `.tsv` files
+fn csv_options(file_name: &str) -> CsvOptions {
+    if Compression::strip_extension(file_name).ends_with(".tsv") {
+        CsvOptions::tsv()
+    } else {
+        CsvOptions::default()
+    }
+}
+
+/// Applies a table property of CREATE EXTERNAL TABLE to the CSV options
+fn with_csv_property(options: CsvOptions, name: &str, value: &str) -> anyhow::Result<CsvOptions> {
+    let byte = |value: &str| match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => bail!("expected a single character for {}, got '{}'", name, value),
+    };
+    let flag = |value: &str| {
+        value
+            .parse::<bool>()
+            .map_err(|_| anyhow!("expected true or false for {}, got '{}'", name, value))
+    };
+    Ok(match name.to_lowercase().as_str() {
+        "has_header" => options.with_has_headers(flag(value)?),
+        "delimiter" => options.with_delimiter(byte(value)?),
+        "quote" => options.with_quote(byte(value)?),
+        "escape" => options.with_escape(byte(value)?),
+        "comment" => options.with_comment(byte(value)?),
+        "skip_rows" => options.with_skip_rows(value.parse()?),
+        "trim" => options.with_trim(flag(value)?),
+        "on_malformed_row" => options.with_on_malformed_row(match value {
+            "fail" => MalformedRowPolicy::Fail,
+            "skip" => MalformedRowPolicy::Skip,
+            "null" => MalformedRowPolicy::Null,
+            _ => bail!("expected fail, skip or null for {}, got '{}'", name, value),
+        }),
+        "column_names" => options.with_column_names(value.split(',').map(str::trim).collect()),
+        other => bail!("unknown table property: {}", other),
+    })
+}
+
+/// Interactive SQL shell on top of an ExecutionContext
+struct Shell {
+    ctx: ExecutionContext,
+}
+
+impl Shell {
+    fn new(ctx: ExecutionContext) -> Self {
+        Shell { ctx }
+    }
+
+    /// Runs a backslash command and returns its output
+    fn run_command(&mut self, command: &str) -> anyhow::Result<String> {
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        match name {
+            "\\help" | "\\?" => Ok(HELP.to_string()),
+            "\\describe" | "\\d" => self.describe(args.trim()),
+            "\\explain" => self.explain(args),
+            "\\register" => {
+                let args: Vec<&str> = args.split_whitespace().collect();
+                match args.as_slice() {
+                    [table_name, file_name] => {
+                        self.register(table_name, file_name, None, csv_options(file_name))
+                    }
+                    [table_name, file_name, "noheader"] => self.register(
+                        table_name,
+                        file_name,
+                        None,
+                        csv_options(file_name).with_has_headers(false),
+                    ),
+                    _ => bail!("usage: \\register <name> <file> [noheader]"),
+                }
+            }
+            other => bail!("unknown command: {}, type \\help for help", other),
+        }
+    }
+
+    /// Runs a single SQL statement and returns the rendered result
+    fn run_sql(&mut self, sql: &str) -> anyhow::Result<String> {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql)?;
+        if let [Statement::CreateTable {
+            external: true,
+            name,
+            file_format,
+            location,
+            table_properties,
+            ..
+        }] = statements.as_slice()
+        {
+            let location = location
+                .as_ref()
+                .ok_or(anyhow!("external table requires a LOCATION"))?;
+            let properties: HashMap<String, String> = table_properties
+                .iter()
+                .map(|property| {
+                    let value = match &property.value {
+                        Expr::Value(SqlValue::SingleQuotedString(s)) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (property.name.value.clone(), value)
+                })
+                .collect();
+            // STORED AS only accepts built-in formats, custom ones are given as a property
+            if let Some(format) = properties.get("format") {
+                self.ctx
+                    .register_table(name.to_string(), format, location, &properties)?;
+                return Ok(format!("registered table {}", name));
+            }
+            let mut options = csv_options(location);
+            for property in table_properties {
+                let value = &properties[&property.name.value];
+                options = with_csv_property(options, &property.name.value, value)?;
+            }
+            return self.register(&name.to_string(), location, *file_format, options);
+        }
+        if let [Statement::Analyze { table_name, .. }] = statements.as_slice() {
+            return self.analyze(&table_name.to_string());
+        }
+
+        let df = self.ctx.sql(sql)?;
+        let mut output = String::new();
+        let mut row_count = 0;
+        for batch in self.ctx.execute(df.clone(), true)? {
+            row_count += batch.row_count();
+            output.push_str(&batch.show().unwrap_or_default());
+        }
+        output.push_str(&format!("{} row(s)", row_count));
+        for rejected in self.ctx.take_rejected_rows(df) {
+            output.push_str(&format!("\n{}", rejected));
+        }
+        Ok(output)
+    }
+
+    fn register(
+        &mut self,
+        table_name: &str,
+        file_name: &str,
+        file_format: Option<FileFormat>,
+        options: CsvOptions,
+    ) -> anyhow::Result<String> {
+        let format = match file_format {
+            Some(FileFormat::PARQUET) => ListingFormat::Parquet,
+            Some(FileFormat::JSONFILE) => ListingFormat::Json,
+            Some(FileFormat::TEXTFILE) => ListingFormat::Csv(options),
+            Some(other) => bail!("file format not supported: {}", other),
+            // sqlparser has no file format for arrow, so ipc files are only detected by extension
+            None => ListingFormat::from_extension(file_name, options),
+        };
+        if std::path::Path::new(file_name).is_dir() || file_name.contains(['*', '?']) {
+            self.ctx.register_listing(table_name, file_name, format)?;
+            return Ok(format!("registered table {}", table_name));
+        }
+        if !std::path::Path::new(file_name).is_file() {
+            bail!("file not found: {}", file_name);
+        }
+        match format {
+            ListingFormat::Csv(options) => self.ctx.register_csv(table_name, file_name, options)?,
+            ListingFormat::Json => self.ctx.register_json(table_name, file_name)?,
+            ListingFormat::Parquet => self.ctx.register_parquet(table_name, file_name)?,
+            ListingFormat::Ipc => self.ctx.register_ipc(table_name, file_name)?,
+        }
+        Ok(format!("registered table {}", table_name))
+    }
+
+    fn analyze(&mut self, table_name: &str) -> anyhow::Result<String> {
+        let statistics = self.ctx.analyze(table_name)?;
+        let df = self.ctx.table(table_name).unwrap();
+        let mut lines = vec![format!(
+            "{}: {} row(s)",
+            table_name,
+            statistics.row_count.unwrap_or_default()
+        )];
+        for (field, column) in df.schema().fields.iter().zip(&statistics.columns) {
+            let show = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            lines.push(format!(
+                "{}: min={}, max={}, nulls={}, distinct={}",
+                field.name,
+                show(column.min.as_ref().map(|v| v.to_string())),
+                show(column.max.as_ref().map(|v| v.to_string())),
+                show(column.null_count.map(|n| n.to_string())),
+                show(column.distinct_count.map(|n| n.to_string())),
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn describe(&self, table_name: &str) -> anyhow::Result<String> {
+        let df = self
+            .ctx
+            .table(table_name)
+            .ok_or(anyhow!("table not found: {}", table_name))?;
+        Ok(df
+            .schema()
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "{}: {:?}{}",
+                    f.name,
+                    f.data_type,
+                    if f.nullable { " (nullable)" } else { "" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn explain(&self, sql: &str) -> anyhow::Result<String> {
+        let plan = self.ctx.sql(sql)?.logical_plan();
+        let optimized = self.ctx.optimizer().run(plan.clone());
+        let physical_plan = QueryPlanner::create_physical_plan(optimized.plan.deref());
+        Ok(format!(
+            "logical plan:\n{}\noptimized plan:\n{}\napplied rules: {}\n\nphysical plan:\n{}",
+            plan.pretty(),
+            optimized.plan.pretty(),
+            optimized.trace.join(", "),
+            physical_plan.pretty()
+        ))
+    }
 }
 
-fn say_hello() -> String {
-    "Hello, world!".to_string()
+#[cfg(test)]
+mod test {
+    use crate::{load_settings, Shell};
+    use rs_query::data_source::csv_data_source::CsvOptions;
+    use rs_query::data_source::{DataSource, Source, TableProvider};
+    use rs_query::execution::ExecutionContext;
+    use rs_query::optimizer::Optimizer;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    struct CsvTableProvider;
+
+    impl TableProvider for CsvTableProvider {
+        fn create(
+            &self,
+            location: &str,
+            _options: &HashMap<String, String>,
+        ) -> anyhow::Result<Arc<dyn DataSource>> {
+            Ok(Source::from_csv(
+                location,
+                None,
+                CsvOptions::default(),
+                1024,
+            )?)
+        }
+    }
+
+    #[test]
+    fn test_create_external_table_and_query() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+
+        shell
+            .run_sql("CREATE EXTERNAL TABLE employee STORED AS TEXTFILE LOCATION 'testdata/employee.csv';")
+            .unwrap();
+        let output = shell
+            .run_sql("SELECT id FROM employee WHERE state = 'CO';")
+            .unwrap();
+
+        assert_eq!("|id|\n|--|\n|2 |\n|3 |\n2 row(s)", output);
+        assert_eq!(
+            "id: Int64Type\n\
+            first_name: StringType\n\
+            last_name: StringType\n\
+            state: StringType (nullable)\n\
+            job_title: StringType\n\
+            salary: Int64Type",
+            shell.run_command("\\describe employee").unwrap()
+        );
+
+        shell
+            .run_sql(
+                "CREATE EXTERNAL TABLE dialect STORED AS TEXTFILE LOCATION 'testdata/dialect.csv' \
+                TBLPROPERTIES ('delimiter' = ';', 'quote' = '''', 'escape' = '\\', \
+                'comment' = '#', 'skip_rows' = '1', 'trim' = 'false');",
+            )
+            .unwrap();
+        let output = shell.run_sql("SELECT note FROM dialect;").unwrap();
+        assert_eq!("|note|\n|----|\n|it's|\n|a;b |\n2 row(s)", output);
+        shell
+            .run_sql(
+                "CREATE EXTERNAL TABLE malformed STORED AS TEXTFILE LOCATION 'testdata/malformed.csv' \
+                TBLPROPERTIES ('on_malformed_row' = 'skip');",
+            )
+            .unwrap();
+        let output = shell.run_sql("SELECT id FROM malformed;").unwrap();
+        assert!(output.ends_with(
+            "3 row(s)\nmalformed row at testdata/malformed.csv:3: expected 3 fields, got 2"
+        ));
+        assert!(shell
+            .run_sql("CREATE EXTERNAL TABLE t LOCATION 'testdata/employee.csv' TBLPROPERTIES ('delimiter' = 'ab');")
+            .is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+
+        let output = shell
+            .run_command("\\register events testdata/events.ndjson")
+            .unwrap();
+
+        assert_eq!("registered table events", output);
+        assert!(shell
+            .run_command("\\describe events")
+            .unwrap()
+            .contains("user.name: StringType\n"));
+        shell
+            .run_command("\\register sales testdata/sales/*/*/*.csv")
+            .unwrap();
+        assert!(shell
+            .run_command("\\describe sales")
+            .unwrap()
+            .ends_with("year: Int64Type\nregion: StringType"));
+        shell
+            .run_command("\\register archived testdata/events.ndjson.zst")
+            .unwrap();
+        assert!(shell
+            .run_command("\\describe archived")
+            .unwrap()
+            .contains("user.name: StringType\n"));
+        assert!(shell.run_command("\\register t missing.csv").is_err());
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+        shell
+            .run_command("\\register employee testdata/employee.tsv")
+            .unwrap();
+        shell
+            .run_command("\\register alltypes testdata/alltypes_utf8.parquet")
+            .unwrap();
+
+        assert!(shell
+            .run_command("\\describe employee")
+            .unwrap()
+            .ends_with("salary: Int64Type"));
+        assert!(shell
+            .run_command("\\describe alltypes")
+            .unwrap()
+            .starts_with("id: Int32Type (nullable)\n"));
+        assert!(shell.run_command("\\describe unknown").is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+        shell
+            .run_command("\\register alltypes testdata/alltypes_utf8.parquet")
+            .unwrap();
+
+        let output = shell
+            .run_command("\\explain SELECT id FROM alltypes")
+            .unwrap();
+
+        assert!(output.contains("Projection: id"));
+        assert!(output.contains("ScanExec"));
+        assert!(shell
+            .run_command("\\explain SELECT nosuch FROM alltypes")
+            .is_err());
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+
+        assert!(shell.run_command("\\unknown").is_err());
+    }
+
+    #[test]
+    fn test_invalid_query_keeps_shell_running() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+        shell
+            .run_sql("CREATE EXTERNAL TABLE employee LOCATION 'testdata/employee.csv';")
+            .unwrap();
+
+        let error = shell
+            .run_sql("SELECT id FROM employee WHERE state = 1;")
+            .unwrap_err();
+
+        assert_eq!(
+            "cannot compare values of different type StringType and Int64Type: state = 1",
+            error.to_string()
+        );
+        let output = shell
+            .run_sql("SELECT id FROM employee WHERE state = 'CA';")
+            .unwrap();
+        assert_eq!("|id|\n|--|\n|1 |\n1 row(s)", output);
+    }
+
+    #[test]
+    fn test_create_external_table_from_provider() {
+        let mut ctx = ExecutionContext::new(HashMap::default());
+        ctx.register_table_provider("custom", Arc::new(CsvTableProvider));
+        let mut shell = Shell::new(ctx);
+
+        shell
+            .run_sql(
+                "CREATE EXTERNAL TABLE employee LOCATION 'testdata/employee.csv' \
+                TBLPROPERTIES ('format' = 'custom');",
+            )
+            .unwrap();
+        let output = shell
+            .run_sql("SELECT id FROM employee WHERE state = 'CO';")
+            .unwrap();
+        assert_eq!("|id|\n|--|\n|2 |\n|3 |\n2 row(s)", output);
+        assert!(shell
+            .run_sql(
+                "CREATE EXTERNAL TABLE t LOCATION 'testdata/employee.csv' \
+                TBLPROPERTIES ('format' = 'unknown');"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_analyze_table() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+        shell
+            .run_sql("CREATE EXTERNAL TABLE employee LOCATION 'testdata/employee.csv';")
+            .unwrap();
+
+        let output = shell.run_sql("ANALYZE TABLE employee;").unwrap();
+
+        assert!(output.starts_with("employee: 4 row(s)\nid: min=1, max=4, nulls=0, distinct=4\n"));
+        assert!(output.ends_with("salary: min=10000, max=12000, nulls=0, distinct=3"));
+        assert!(shell.run_sql("ANALYZE TABLE unknown;").is_err());
+    }
+
+    #[test]
+    fn test_malformed_row_fails_query() {
+        let mut shell = Shell::new(ExecutionContext::new(HashMap::default()));
+        shell
+            .run_sql(
+                "CREATE EXTERNAL TABLE malformed LOCATION 'testdata/malformed.csv' \
+                TBLPROPERTIES ('on_malformed_row' = 'fail');",
+            )
+            .unwrap();
+
+        let error = shell.run_sql("SELECT id FROM malformed;").unwrap_err();
+
+        assert_eq!(
+            "malformed row at testdata/malformed.csv:3: expected 3 fields, got 2",
+            error.to_string()
+        );
+        // the shell keeps working after the failed query
+        let output = shell.run_sql("SELECT id FROM malformed LIMIT 1;").unwrap();
+        assert_eq!("|id|\n|--|\n|1 |\n1 row(s)", output);
+    }
+
+    #[test]
+    fn test_base_settings_toggle_every_optimizer_rule() {
+        let settings = load_settings().unwrap();
+
+        for rule in Optimizer::default_rules() {
+            let key = format!("optimizer.{}.enabled", rule.name());
+            assert_eq!(
+                Some("true"),
+                settings.get(&key).map(String::as_str),
+                "{}",
+                key
+            );
+        }
+    }
 }
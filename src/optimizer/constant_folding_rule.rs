@@ -0,0 +1,308 @@
+use crate::datatypes::arrow_types::ArrowType;
+use crate::logical_plan::aggregate::Aggregate;
+use crate::logical_plan::expressions::binary_expr::{Base, BinaryExpr};
+use crate::logical_plan::expressions::literal_expr::{
+    lit_bool, lit_double, lit_float, lit_long, lit_str, LiteralExpr,
+};
+use crate::logical_plan::expressions::math_expr::MathExpr;
+use crate::logical_plan::expressions::unary_expr::UnaryExpr;
+use crate::logical_plan::expressions::{alias, Expr};
+use crate::logical_plan::join::Join;
+use crate::logical_plan::limit::Limit;
+use crate::logical_plan::logical_expr::LogicalExpr;
+use crate::logical_plan::projection::Projection;
+use crate::logical_plan::scan::Scan;
+use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::{Sort, SortExpr};
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::{transform_up, OptimizerRule};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Evaluates literal-only subexpressions once at planning time instead of per row, simplifies
+/// boolean identities like `x AND true` or `NOT NOT x` and removes filters that are always true
+pub struct ConstantFoldingRule;
+
+impl OptimizerRule for ConstantFoldingRule {
+    fn name(&self) -> &str {
+        "constant_folding"
+    }
+
+    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+        if let Some(selection) = plan.as_any().downcast_ref::<Selection>() {
+            let input = self.optimize(selection.input.clone());
+            let expr = simplify(selection.expr.clone());
+            match as_bool(&expr) {
+                Some(true) => input,
+                _ => Selection::new(input, expr),
+            }
+        } else if let Some(projection) = plan.as_any().downcast_ref::<Projection>() {
+            let input = self.optimize(projection.input.clone());
+            let expr = Self::simplify_keeping_names(&projection.expr, projection.input.clone());
+            Projection::new(input, expr)
+        } else if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
+            let input = self.optimize(aggregate.input.clone());
+            let group_expr =
+                Self::simplify_keeping_names(&aggregate.group_expr, aggregate.input.clone());
+            Aggregate::new(input, group_expr, aggregate.aggregate_expr.clone())
+        } else if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            let input = self.optimize(limit.input.clone());
+            Limit::new(input, limit.offset, limit.limit)
+        } else if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            let input = self.optimize(sort.input.clone());
+            let sort_expr = sort
+                .sort_expr
+                .iter()
+                .map(|s| SortExpr {
+                    expr: simplify(s.expr.clone()),
+                    ..s.clone()
+                })
+                .collect();
+            Sort::new(input, sort_expr)
+        } else if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            join.with_inputs(
+                self.optimize(join.left.clone()),
+                self.optimize(join.right.clone()),
+            )
+        } else if plan.as_any().downcast_ref::<Scan>().is_some() {
+            plan
+        } else {
+            panic!(
+                "ConstantFoldingRule does not support plan: {}",
+                plan.to_string()
+            )
+        }
+    }
+}
+
+impl ConstantFoldingRule {
+    /// Simplifies output expressions, aliasing them to their original name if it changed
+    fn simplify_keeping_names(expr: &[Arc<Expr>], input: Arc<dyn LogicalPlan>) -> Vec<Arc<Expr>> {
+        expr.iter()
+            .map(|e| {
+                let simplified = simplify(e.clone());
+                match (
+                    e.to_field(input.clone()),
+                    simplified.to_field(input.clone()),
+                ) {
+                    (Ok(before), Ok(after)) if before.name != after.name => {
+                        alias(simplified, before.name.clone())
+                    }
+                    _ => simplified,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Simplifies the expression bottom up
+fn simplify(expr: Arc<Expr>) -> Arc<Expr> {
+    transform_up(expr, &simplify_node)
+}
+
+fn simplify_node(expr: Arc<Expr>) -> Arc<Expr> {
+    match &*expr {
+        Expr::Cast(c) => fold_cast(&c.expr, &c.data_type),
+        Expr::Math(math) => fold_math(math, &math.get_left(), &math.get_right()),
+        Expr::Binary(bin) => fold_binary(bin, bin.get_left(), bin.get_right()),
+        Expr::Unary(u) => fold_unary(u, u.get_expr()),
+        _ => None,
+    }
+    .unwrap_or(expr)
+}
+
+fn as_bool(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Literal(LiteralExpr::LiteralBoolean(b)) => Some(b.b),
+        _ => None,
+    }
+}
+
+fn fold_cast(expr: &Expr, data_type: &ArrowType) -> Option<Arc<Expr>> {
+    let Expr::Literal(lit) = expr else {
+        return None;
+    };
+    match (lit, data_type) {
+        (LiteralExpr::LiteralString(s), ArrowType::StringType) => Some(lit_str(&s.str)),
+        (LiteralExpr::LiteralString(s), ArrowType::Int64Type) => s.str.parse().ok().map(lit_long),
+        (LiteralExpr::LiteralString(s), ArrowType::FloatType) => s.str.parse().ok().map(lit_float),
+        (LiteralExpr::LiteralString(s), ArrowType::DoubleType) => {
+            s.str.parse().ok().map(lit_double)
+        }
+        (LiteralExpr::LiteralString(s), ArrowType::BooleanType) => s.str.parse().ok().map(lit_bool),
+        (LiteralExpr::LiteralLong(l), ArrowType::Int64Type) => Some(lit_long(l.i)),
+        (LiteralExpr::LiteralLong(l), ArrowType::FloatType) => Some(lit_float(l.i as f32)),
+        (LiteralExpr::LiteralLong(l), ArrowType::DoubleType) => Some(lit_double(l.i as f64)),
+        (LiteralExpr::LiteralLong(l), ArrowType::StringType) => Some(lit_str(&l.i.to_string())),
+        (LiteralExpr::LiteralFloat(f), ArrowType::FloatType) => Some(lit_float(f.i)),
+        (LiteralExpr::LiteralFloat(f), ArrowType::DoubleType) => Some(lit_double(f.i as f64)),
+        (LiteralExpr::LiteralDouble(d), ArrowType::DoubleType) => Some(lit_double(d.i)),
+        (LiteralExpr::LiteralBoolean(b), ArrowType::BooleanType) => Some(lit_bool(b.b)),
+        _ => None,
+    }
+}
+
+fn fold_math(math: &MathExpr, l: &Expr, r: &Expr) -> Option<Arc<Expr>> {
+    let (Expr::Literal(l), Expr::Literal(r)) = (l, r) else {
+        return None;
+    };
+    match (l, r) {
+        // overflow and division by zero are left to the execution
+        (LiteralExpr::LiteralLong(l), LiteralExpr::LiteralLong(r)) => match math {
+            MathExpr::Add(_) => l.i.checked_add(r.i),
+            MathExpr::Subtract(_) => l.i.checked_sub(r.i),
+            MathExpr::Multiply(_) => l.i.checked_mul(r.i),
+            MathExpr::Divide(_) => l.i.checked_div(r.i),
+            MathExpr::Modulus(_) => l.i.checked_rem(r.i),
+        }
+        .map(lit_long),
+        (LiteralExpr::LiteralDouble(l), LiteralExpr::LiteralDouble(r)) => {
+            Some(lit_double(apply_math(math, l.i, r.i)))
+        }
+        (LiteralExpr::LiteralFloat(l), LiteralExpr::LiteralFloat(r)) => {
+            Some(lit_float(apply_math(math, l.i, r.i)))
+        }
+        _ => None,
+    }
+}
+
+fn apply_math<T>(math: &MathExpr, l: T, r: T) -> T
+where
+    T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T>
+        + std::ops::Rem<Output = T>,
+{
+    match math {
+        MathExpr::Add(_) => l + r,
+        MathExpr::Subtract(_) => l - r,
+        MathExpr::Multiply(_) => l * r,
+        MathExpr::Divide(_) => l / r,
+        MathExpr::Modulus(_) => l % r,
+    }
+}
+
+fn fold_binary(bin: &BinaryExpr, l: Arc<Expr>, r: Arc<Expr>) -> Option<Arc<Expr>> {
+    match bin {
+        // `false AND null` is false and `true OR null` is true, so these hold for nulls as well
+        BinaryExpr::And(_) => match (as_bool(&l), as_bool(&r)) {
+            (Some(true), _) => Some(r),
+            (_, Some(true)) => Some(l),
+            (Some(false), _) | (_, Some(false)) => Some(lit_bool(false)),
+            _ => None,
+        },
+        BinaryExpr::Or(_) => match (as_bool(&l), as_bool(&r)) {
+            (Some(false), _) => Some(r),
+            (_, Some(false)) => Some(l),
+            (Some(true), _) | (_, Some(true)) => Some(lit_bool(true)),
+            _ => None,
+        },
+        _ => {
+            let ordering = compare_literals(&l, &r)?;
+            let result = match bin {
+                BinaryExpr::Eq(_) => ordering == Ordering::Equal,
+                BinaryExpr::Neq(_) => ordering != Ordering::Equal,
+                BinaryExpr::Gt(_) => ordering == Ordering::Greater,
+                BinaryExpr::GtEq(_) => ordering != Ordering::Less,
+                BinaryExpr::Lt(_) => ordering == Ordering::Less,
+                BinaryExpr::LtEq(_) => ordering != Ordering::Greater,
+                BinaryExpr::And(_) | BinaryExpr::Or(_) => unreachable!(),
+            };
+            Some(lit_bool(result))
+        }
+    }
+}
+
+/// Compares two literals of the same type
+fn compare_literals(l: &Expr, r: &Expr) -> Option<Ordering> {
+    let (Expr::Literal(l), Expr::Literal(r)) = (l, r) else {
+        return None;
+    };
+    match (l, r) {
+        (LiteralExpr::LiteralString(l), LiteralExpr::LiteralString(r)) => Some(l.str.cmp(&r.str)),
+        (LiteralExpr::LiteralLong(l), LiteralExpr::LiteralLong(r)) => Some(l.i.cmp(&r.i)),
+        (LiteralExpr::LiteralFloat(l), LiteralExpr::LiteralFloat(r)) => l.i.partial_cmp(&r.i),
+        (LiteralExpr::LiteralDouble(l), LiteralExpr::LiteralDouble(r)) => l.i.partial_cmp(&r.i),
+        (LiteralExpr::LiteralBoolean(l), LiteralExpr::LiteralBoolean(r)) => Some(l.b.cmp(&r.b)),
+        _ => None,
+    }
+}
+
+fn fold_unary(u: &UnaryExpr, e: Arc<Expr>) -> Option<Arc<Expr>> {
+    match (u, &*e) {
+        (UnaryExpr::Not(_), Expr::Unary(inner @ UnaryExpr::Not(_))) => Some(inner.get_expr()),
+        (UnaryExpr::Not(_), _) => as_bool(&e).map(|b| lit_bool(!b)),
+        (UnaryExpr::Negate(_), Expr::Literal(lit)) => match lit {
+            LiteralExpr::LiteralLong(l) => l.i.checked_neg().map(lit_long),
+            LiteralExpr::LiteralFloat(f) => Some(lit_float(-f.i)),
+            LiteralExpr::LiteralDouble(d) => Some(lit_double(-d.i)),
+            _ => None,
+        },
+        // literals are never null
+        (UnaryExpr::IsNull(_), Expr::Literal(_)) => Some(lit_bool(false)),
+        (UnaryExpr::IsNotNull(_), Expr::Literal(_)) => Some(lit_bool(true)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::expressions::binary_expr::{and, or, BooleanBinaryExprExt};
+    use crate::logical_plan::expressions::literal_expr::{lit_bool, lit_long, lit_str};
+    use crate::logical_plan::expressions::math_expr::MathExprExt;
+    use crate::logical_plan::expressions::unary_expr::not;
+    use crate::logical_plan::expressions::{cast, col};
+    use crate::logical_plan::LogicalPlanPrinter;
+    use crate::optimizer::constant_folding_rule::{simplify, ConstantFoldingRule};
+    use crate::optimizer::OptimizerRule;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_simplify_expressions() {
+        assert_eq!("15", simplify(lit_long(5).add(lit_long(10))).to_string());
+        assert_eq!(
+            "true",
+            simplify(cast(lit_str("12"), ArrowType::Int64Type).gt(lit_long(3).mult(lit_long(2))))
+                .to_string()
+        );
+        assert_eq!(
+            "state = CO",
+            simplify(and(col("state").eq(lit_str("CO")), lit_bool(true))).to_string()
+        );
+        assert_eq!(
+            "false",
+            simplify(and(lit_bool(false), col("state").eq(lit_str("CO")))).to_string()
+        );
+        assert_eq!(
+            "state",
+            simplify(or(lit_bool(false), col("state"))).to_string()
+        );
+        assert_eq!("state", simplify(not(not(col("state")))).to_string());
+        // division by zero is not folded
+        assert_eq!("1 / 0", simplify(lit_long(1).div(lit_long(0))).to_string());
+    }
+
+    #[test]
+    fn test_fold_plan() {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let df = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .filter(lit_long(1).eq(lit_long(1)))
+            .filter(and(col("state").eq(lit_str("CO")), lit_bool(true)))
+            .project(vec![col("id"), lit_long(2).add(lit_long(3))]);
+
+        let plan = ConstantFoldingRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Projection: id, 5 AS add\n\
+            \tSelection: state = CO\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            plan.pretty()
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::data_source::FilterPushDown;
+use crate::logical_plan::aggregate::Aggregate;
+use crate::logical_plan::expressions::binary_expr::and;
+use crate::logical_plan::expressions::{col, Expr};
+use crate::logical_plan::join::Join;
+use crate::logical_plan::limit::Limit;
+use crate::logical_plan::projection::Projection;
+use crate::logical_plan::scan::Scan;
+use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::Sort;
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::predicate_push_down_rule::split_conjunction;
+use crate::optimizer::{replace_columns, OptimizerRule};
+use std::sync::Arc;
+
+/// Hands the conjuncts of a selection directly on top of a scan to the data source. Filters the
+/// source applies exactly are removed from the selection, inexact ones are applied by both.
+/// Runs after the predicate push down, which moves the selections onto the scans.
+pub struct FilterPushDownRule;
+
+impl OptimizerRule for FilterPushDownRule {
+    fn name(&self) -> &str {
+        "filter_push_down"
+    }
+
+    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+        Self::push_down(plan)
+    }
+}
+
+impl FilterPushDownRule {
+    fn push_down(plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+        if let Some(selection) = plan.as_any().downcast_ref::<Selection>() {
+            if let Some(scan) = selection.input.as_any().downcast_ref::<Scan>() {
+                return Self::push_into_scan(selection, scan);
+            }
+            let input = Self::push_down(selection.input.clone());
+            Selection::new(input, selection.expr.clone())
+        } else if let Some(projection) = plan.as_any().downcast_ref::<Projection>() {
+            let input = Self::push_down(projection.input.clone());
+            Projection::new(input, projection.expr.clone())
+        } else if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
+            let input = Self::push_down(aggregate.input.clone());
+            Aggregate::new(
+                input,
+                aggregate.group_expr.clone(),
+                aggregate.aggregate_expr.clone(),
+            )
+        } else if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            let input = Self::push_down(sort.input.clone());
+            Sort::new(input, sort.sort_expr.clone())
+        } else if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            let input = Self::push_down(limit.input.clone());
+            Limit::new(input, limit.offset, limit.limit)
+        } else if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            let left = Self::push_down(join.left.clone());
+            let right = Self::push_down(join.right.clone());
+            join.with_inputs(left, right)
+        } else if plan.as_any().downcast_ref::<Scan>().is_some() {
+            plan
+        } else {
+            panic!(
+                "FilterPushDownRule does not support plan: {}",
+                plan.to_string()
+            )
+        }
+    }
+
+    fn push_into_scan(selection: &Selection, scan: &Scan) -> Arc<dyn LogicalPlan> {
+        // data sources refer to columns by name
+        let schema = scan.schema();
+        let resolve = |e: &Expr| match e {
+            Expr::Column(c) => Some(col(c.name.clone())),
+            Expr::ColumnIndex(c) => Some(col(schema.fields.get(c.i)?.name.clone())),
+            _ => None,
+        };
+
+        let mut predicates = vec![];
+        split_conjunction(selection.expr.clone(), &mut predicates);
+        let mut filters = scan.filters.clone();
+        let mut kept = vec![];
+        for predicate in predicates {
+            let Some(filter) = replace_columns(predicate.clone(), &resolve) else {
+                kept.push(predicate);
+                continue;
+            };
+            let support = scan.datasource.supports_filter(&filter);
+            // inexact filters stay in the selection, so they are only added once
+            let is_new = !filters.iter().any(|f| f.to_string() == filter.to_string());
+            if support != FilterPushDown::Unsupported && is_new {
+                filters.push(filter);
+            }
+            if support != FilterPushDown::Exact {
+                kept.push(predicate);
+            }
+        }
+
+        let input = scan.with_filters(filters);
+        match kept.into_iter().reduce(and) {
+            Some(expr) => Selection::new(input, expr),
+            None => input,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::expressions::binary_expr::{and, BooleanBinaryExprExt};
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::{lit_long, lit_str};
+    use crate::logical_plan::LogicalPlanPrinter;
+    use crate::optimizer::filter_push_down_rule::FilterPushDownRule;
+    use crate::optimizer::OptimizerRule;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_exact_filters_replace_selection() {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let df = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .filter(and(
+                col("state").eq(lit_str("CO")),
+                col("id").gt(lit_long(2)),
+            ))
+            .project(vec![col("id")]);
+
+        let plan = FilterPushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Projection: id\n\
+            \tScan: testdata/employee.csv; projection=None; filters=[state = CO, id > 2]\n",
+            plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_inexact_filters_are_kept() {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let df = ctx
+            .parquet("testdata/alltypes_utf8.parquet")
+            .unwrap()
+            .filter(and(col("id").gt(lit_long(3)), col("id").neq(lit_long(5))));
+
+        let plan = FilterPushDownRule.optimize(df.logical_plan());
+        // applying the rule again does not add the inexact filter twice
+        let plan = FilterPushDownRule.optimize(plan);
+
+        assert_eq!(
+            "Selection: id > 3 AND id != 5\n\
+            \tScan: testdata/alltypes_utf8.parquet; projection=None; filters=[id > 3]\n",
+            plan.pretty()
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::data_source::LimitHint;
+use crate::logical_plan::aggregate::Aggregate;
+use crate::logical_plan::join::Join;
+use crate::logical_plan::limit::Limit;
+use crate::logical_plan::projection::Projection;
+use crate::logical_plan::scan::Scan;
+use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::Sort;
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::OptimizerRule;
+use std::sync::Arc;
+
+/// Hands the number of rows needed by a limit to the scan below it, through projections. Below
+/// a selection the rows are filtered after the scan, so the scan only gets a soft hint. The limit
+/// itself stays in the plan.
+pub struct LimitPushDownRule;
+
+impl OptimizerRule for LimitPushDownRule {
+    fn name(&self) -> &str {
+        "limit_push_down"
+    }
+
+    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+        Self::push_down(plan, None)
+    }
+}
+
+impl LimitPushDownRule {
+    fn push_down(plan: Arc<dyn LogicalPlan>, hint: Option<LimitHint>) -> Arc<dyn LogicalPlan> {
+        if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            // the skipped rows have to be read as well
+            let hint = match (limit.limit, hint) {
+                (Some(rows), Some(LimitHint::Exact(needed))) => {
+                    Some(LimitHint::Exact(limit.offset + rows.min(needed)))
+                }
+                (Some(rows), _) => Some(LimitHint::Exact(limit.offset + rows)),
+                (None, Some(LimitHint::Exact(needed))) => {
+                    Some(LimitHint::Exact(limit.offset + needed))
+                }
+                (None, Some(LimitHint::Soft(needed))) => {
+                    Some(LimitHint::Soft(limit.offset + needed))
+                }
+                (None, None) => None,
+            };
+            let input = Self::push_down(limit.input.clone(), hint);
+            Limit::new(input, limit.offset, limit.limit)
+        } else if let Some(projection) = plan.as_any().downcast_ref::<Projection>() {
+            let input = Self::push_down(projection.input.clone(), hint);
+            Projection::new(input, projection.expr.clone())
+        } else if let Some(selection) = plan.as_any().downcast_ref::<Selection>() {
+            let hint = hint.map(|hint| LimitHint::Soft(hint.rows()));
+            let input = Self::push_down(selection.input.clone(), hint);
+            Selection::new(input, selection.expr.clone())
+        } else if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
+            let input = Self::push_down(aggregate.input.clone(), None);
+            Aggregate::new(
+                input,
+                aggregate.group_expr.clone(),
+                aggregate.aggregate_expr.clone(),
+            )
+        } else if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            let input = Self::push_down(sort.input.clone(), None);
+            Sort::new(input, sort.sort_expr.clone())
+        } else if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            let left = Self::push_down(join.left.clone(), None);
+            let right = Self::push_down(join.right.clone(), None);
+            join.with_inputs(left, right)
+        } else if let Some(scan) = plan.as_any().downcast_ref::<Scan>() {
+            scan.with_limit(hint)
+        } else {
+            panic!(
+                "LimitPushDownRule does not support plan: {}",
+                plan.to_string()
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::lit_long;
+    use crate::logical_plan::LogicalPlanPrinter;
+    use crate::optimizer::limit_push_down_rule::LimitPushDownRule;
+    use crate::optimizer::OptimizerRule;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_push_limit_into_scan() {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let df = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .project(vec![col("id")])
+            .offset(1)
+            .limit(2);
+
+        let plan = LimitPushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Limit: 2\n\
+            \tLimit: None; offset=1\n\
+            \t\tProjection: id\n\
+            \t\t\tScan: testdata/employee.csv; projection=None; limit=3\n",
+            plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_soft_hint_below_selection() {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let df = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .filter(col("id").gt(lit_long(2)))
+            .limit(1);
+
+        let plan = LimitPushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Limit: 1\n\
+            \tSelection: id > 2\n\
+            \t\tScan: testdata/employee.csv; projection=None; limit=1 (soft)\n",
+            plan.pretty()
+        );
+    }
+}
@@ -1,25 +1,139 @@
-mod projection_push_down_rule;
+pub mod constant_folding_rule;
+pub mod filter_push_down_rule;
+pub mod limit_push_down_rule;
+pub mod predicate_push_down_rule;
+pub mod projection_push_down_rule;
 
 use crate::logical_plan::expressions::aggr_expr::AggrExpr;
-use crate::logical_plan::expressions::binary_expr::Base;
-use crate::logical_plan::expressions::Expr;
-use crate::logical_plan::LogicalPlan;
+use crate::logical_plan::expressions::binary_expr::{
+    and, or, Base, BinaryExpr, BooleanBinaryExprExt,
+};
+use crate::logical_plan::expressions::math_expr::{MathExpr, MathExprExt};
+use crate::logical_plan::expressions::unary_expr::{is_not_null, is_null, negate, not, UnaryExpr};
+use crate::logical_plan::expressions::{alias, cast, Expr};
+use crate::logical_plan::{LogicalPlan, LogicalPlanPrinter};
+use crate::optimizer::constant_folding_rule::ConstantFoldingRule;
+use crate::optimizer::filter_push_down_rule::FilterPushDownRule;
+use crate::optimizer::limit_push_down_rule::LimitPushDownRule;
+use crate::optimizer::predicate_push_down_rule::PredicatePushDownRule;
 use crate::optimizer::projection_push_down_rule::ProjectionPushDownRule;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tracing::info;
 
-pub struct Optimizer;
+const DEFAULT_MAX_PASSES: usize = 10;
+
+/// Rewrites a logical plan into an equivalent, cheaper to execute plan
+pub trait OptimizerRule {
+    /// Name of the rule, used for the `optimizer.<name>.enabled` setting and in the trace
+    fn name(&self) -> &str;
+
+    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan>;
+}
+
+/// Applies its rules in order, repeating the whole pipeline until the plan stops changing
+/// or the maximum number of passes is reached
+pub struct Optimizer {
+    rules: Vec<Arc<dyn OptimizerRule>>,
+    max_passes: usize,
+}
+
+/// Optimized plan together with the rules that changed it, e.g. `pass 1: predicate_push_down`
+pub struct OptimizerResult {
+    pub plan: Arc<dyn LogicalPlan>,
+    pub trace: Vec<String>,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new(Self::default_rules())
+    }
+}
 
 impl Optimizer {
+    /// Optimizes the plan with the default rules
     pub fn optimize(plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
-        // use here a list of rules when new rules are implemented
-        let rule = ProjectionPushDownRule;
-        rule.optimize(plan)
+        Self::default().run(plan).plan
     }
-}
 
-trait OptimizerRule {
-    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan>;
+    pub fn new(rules: Vec<Arc<dyn OptimizerRule>>) -> Self {
+        Optimizer {
+            rules,
+            max_passes: DEFAULT_MAX_PASSES,
+        }
+    }
+
+    /// Creates an optimizer with the default rules followed by `custom_rules`, configured by:
+    /// - `optimizer.max_passes`: maximum number of passes through all rules
+    /// - `optimizer.<rule name>.enabled`: set to `false` to skip a rule
+    pub fn from_settings(
+        settings: &HashMap<String, String>,
+        custom_rules: Vec<Arc<dyn OptimizerRule>>,
+    ) -> Self {
+        let rules = Self::default_rules()
+            .into_iter()
+            .chain(custom_rules)
+            .filter(|rule| {
+                let key = format!("optimizer.{}.enabled", rule.name());
+                settings.get(&key).map(String::as_str) != Some("false")
+            })
+            .collect();
+        let max_passes = settings
+            .get("optimizer.max_passes")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_PASSES);
+        Optimizer { rules, max_passes }
+    }
+
+    pub fn default_rules() -> Vec<Arc<dyn OptimizerRule>> {
+        vec![
+            Arc::new(ConstantFoldingRule),
+            Arc::new(PredicatePushDownRule),
+            Arc::new(FilterPushDownRule),
+            Arc::new(ProjectionPushDownRule),
+            Arc::new(LimitPushDownRule),
+        ]
+    }
+
+    /// Appends a rule to the end of the pipeline
+    pub fn with_rule(mut self, rule: Arc<dyn OptimizerRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_max_passes(mut self, max_passes: usize) -> Self {
+        self.max_passes = max_passes;
+        self
+    }
+
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    pub fn run(&self, plan: Arc<dyn LogicalPlan>) -> OptimizerResult {
+        let mut plan = plan;
+        let mut trace = vec![];
+        // plans do not implement equality, so changes are detected on the printed plan
+        let mut plan_string = plan.pretty();
+        for pass in 1..=self.max_passes {
+            let mut changed = false;
+            for rule in &self.rules {
+                let optimized_plan = rule.optimize(plan.clone());
+                let optimized_string = optimized_plan.pretty();
+                if optimized_string != plan_string {
+                    info!("optimizer pass {}: {} changed the plan", pass, rule.name());
+                    trace.push(format!("pass {}: {}", pass, rule.name()));
+                    plan_string = optimized_string;
+                    changed = true;
+                }
+                plan = optimized_plan;
+            }
+            if !changed {
+                break;
+            }
+        }
+        OptimizerResult { plan, trace }
+    }
 }
 
 fn extract_all_columns(
@@ -31,7 +145,11 @@ fn extract_all_columns(
         .for_each(|e| extract_columns(e.clone(), input.clone(), accum))
 }
 
-fn extract_columns(expr: Arc<Expr>, input: Arc<dyn LogicalPlan>, accum: &mut HashSet<String>) {
+pub(crate) fn extract_columns(
+    expr: Arc<Expr>,
+    input: Arc<dyn LogicalPlan>,
+    accum: &mut HashSet<String>,
+) {
     match &*expr {
         Expr::Column(col) => {
             accum.insert(col.name.clone());
@@ -57,7 +175,219 @@ fn extract_columns(expr: Arc<Expr>, input: Arc<dyn LogicalPlan>, accum: &mut Has
                 extract_columns(count_d.base.expr.clone(), input.clone(), accum)
             }
         },
+        Expr::Unary(u) => extract_columns(u.get_expr(), input.clone(), accum),
         Expr::Alias(a) => extract_columns(a.expr.clone(), input.clone(), accum),
         _ => panic!("extract_columns does not support expression: {}", expr),
     }
 }
+
+/// Rebuilds the expression with all column references replaced by the result of `f`.
+/// Returns None if `f` cannot resolve a column or the expression contains an aggregate
+fn replace_columns(expr: Arc<Expr>, f: &dyn Fn(&Expr) -> Option<Arc<Expr>>) -> Option<Arc<Expr>> {
+    match &*expr {
+        Expr::Column(_) | Expr::ColumnIndex(_) => f(&expr),
+        Expr::Literal(_) => Some(expr.clone()),
+        Expr::Cast(c) => Some(cast(
+            replace_columns(c.expr.clone(), f)?,
+            c.data_type.clone(),
+        )),
+        Expr::Binary(bin) => {
+            let l = replace_columns(bin.get_left(), f)?;
+            let r = replace_columns(bin.get_right(), f)?;
+            Some(match bin {
+                BinaryExpr::And(_) => and(l, r),
+                BinaryExpr::Or(_) => or(l, r),
+                BinaryExpr::Eq(_) => l.eq(r),
+                BinaryExpr::Neq(_) => l.neq(r),
+                BinaryExpr::Gt(_) => l.gt(r),
+                BinaryExpr::GtEq(_) => l.gteq(r),
+                BinaryExpr::Lt(_) => l.lt(r),
+                BinaryExpr::LtEq(_) => l.lteq(r),
+            })
+        }
+        Expr::Unary(u) => {
+            let e = replace_columns(u.get_expr(), f)?;
+            Some(match u {
+                UnaryExpr::Not(_) => not(e),
+                UnaryExpr::Negate(_) => negate(e),
+                UnaryExpr::IsNull(_) => is_null(e),
+                UnaryExpr::IsNotNull(_) => is_not_null(e),
+            })
+        }
+        Expr::Math(math) => {
+            let l = replace_columns(math.get_left(), f)?;
+            let r = replace_columns(math.get_right(), f)?;
+            Some(match math {
+                MathExpr::Add(_) => l.add(r),
+                MathExpr::Subtract(_) => l.subtract(r),
+                MathExpr::Multiply(_) => l.mult(r),
+                MathExpr::Divide(_) => l.div(r),
+                MathExpr::Modulus(_) => l.modulus(r),
+            })
+        }
+        Expr::Aggr(_) => None,
+        Expr::Alias(a) => Some(alias(replace_columns(a.expr.clone(), f)?, a.alias.clone())),
+    }
+}
+
+/// Rebuilds the expression bottom up, applying `f` to each node after its children.
+/// Aggregates are passed to `f` as they are
+fn transform_up(expr: Arc<Expr>, f: &dyn Fn(Arc<Expr>) -> Arc<Expr>) -> Arc<Expr> {
+    f(map_children(expr, &|child| transform_up(child, f)))
+}
+
+/// Rebuilds the expression top down, replacing each node for which `f` returns a new
+/// expression as a whole, e.g. an aggregate together with its input
+pub(crate) fn transform_down(
+    expr: Arc<Expr>,
+    f: &dyn Fn(&Arc<Expr>) -> Option<Arc<Expr>>,
+) -> Arc<Expr> {
+    match f(&expr) {
+        Some(replaced) => replaced,
+        None => map_children(expr, &|child| transform_down(child, f)),
+    }
+}
+
+/// Rebuilds the expression with `f` applied to its direct children, aggregates are kept
+fn map_children(expr: Arc<Expr>, f: &dyn Fn(Arc<Expr>) -> Arc<Expr>) -> Arc<Expr> {
+    match &*expr {
+        Expr::Column(_) | Expr::ColumnIndex(_) | Expr::Literal(_) | Expr::Aggr(_) => expr.clone(),
+        Expr::Cast(c) => cast(f(c.expr.clone()), c.data_type.clone()),
+        Expr::Binary(bin) => {
+            let l = f(bin.get_left());
+            let r = f(bin.get_right());
+            match bin {
+                BinaryExpr::And(_) => and(l, r),
+                BinaryExpr::Or(_) => or(l, r),
+                BinaryExpr::Eq(_) => l.eq(r),
+                BinaryExpr::Neq(_) => l.neq(r),
+                BinaryExpr::Gt(_) => l.gt(r),
+                BinaryExpr::GtEq(_) => l.gteq(r),
+                BinaryExpr::Lt(_) => l.lt(r),
+                BinaryExpr::LtEq(_) => l.lteq(r),
+            }
+        }
+        Expr::Unary(u) => {
+            let e = f(u.get_expr());
+            match u {
+                UnaryExpr::Not(_) => not(e),
+                UnaryExpr::Negate(_) => negate(e),
+                UnaryExpr::IsNull(_) => is_null(e),
+                UnaryExpr::IsNotNull(_) => is_not_null(e),
+            }
+        }
+        Expr::Math(math) => {
+            let l = f(math.get_left());
+            let r = f(math.get_right());
+            match math {
+                MathExpr::Add(_) => l.add(r),
+                MathExpr::Subtract(_) => l.subtract(r),
+                MathExpr::Multiply(_) => l.mult(r),
+                MathExpr::Divide(_) => l.div(r),
+                MathExpr::Modulus(_) => l.modulus(r),
+            }
+        }
+        Expr::Alias(a) => alias(f(a.expr.clone()), a.alias.clone()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::expressions::binary_expr::BooleanBinaryExprExt;
+    use crate::logical_plan::expressions::col;
+    use crate::logical_plan::expressions::literal_expr::lit_str;
+    use crate::logical_plan::limit::Limit;
+    use crate::logical_plan::{LogicalPlan, LogicalPlanPrinter};
+    use crate::optimizer::{Optimizer, OptimizerRule};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    /// Wraps the plan into a limit unless it already is one
+    struct LimitRule;
+
+    impl OptimizerRule for LimitRule {
+        fn name(&self) -> &str {
+            "limit"
+        }
+
+        fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+            if plan.as_any().downcast_ref::<Limit>().is_some() {
+                plan
+            } else {
+                Limit::new(plan, 0, Some(10))
+            }
+        }
+    }
+
+    fn test_plan() -> Arc<dyn LogicalPlan> {
+        let ctx = ExecutionContext::new(HashMap::default());
+        ctx.csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .project(vec![col("id"), col("state")])
+            .filter(col("state").eq(lit_str("CO")))
+            .logical_plan()
+    }
+
+    #[test]
+    fn test_trace_and_fixed_point() {
+        let result = Optimizer::default()
+            .with_rule(Arc::new(LimitRule))
+            .run(test_plan());
+
+        assert_eq!(
+            vec![
+                "pass 1: predicate_push_down",
+                "pass 1: filter_push_down",
+                "pass 1: projection_push_down",
+                "pass 1: limit",
+                "pass 2: limit_push_down"
+            ],
+            result.trace
+        );
+        assert_eq!(
+            "Limit: 10\n\
+            \tProjection: id, state\n\
+            \t\tScan: testdata/employee.csv; projection=[\"id\", \"state\"]; filters=[state = CO]; \
+            limit=10\n",
+            result.plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_rules_from_settings() {
+        let mut settings = HashMap::new();
+        settings.insert(
+            "optimizer.projection_push_down.enabled".to_string(),
+            "false".to_string(),
+        );
+        let optimizer = Optimizer::from_settings(&settings, vec![Arc::new(LimitRule)]);
+
+        assert_eq!(
+            vec![
+                "constant_folding",
+                "predicate_push_down",
+                "filter_push_down",
+                "limit_push_down",
+                "limit"
+            ],
+            optimizer.rule_names()
+        );
+
+        let result = optimizer.with_max_passes(0).run(test_plan());
+        assert!(result.trace.is_empty());
+
+        let settings: HashMap<String, String> =
+            ["constant_folding", "filter_push_down", "limit_push_down"]
+                .iter()
+                .map(|name| (format!("optimizer.{}.enabled", name), "false".to_string()))
+                .collect();
+        let optimizer = Optimizer::from_settings(&settings, vec![]);
+
+        assert_eq!(
+            vec!["predicate_push_down", "projection_push_down"],
+            optimizer.rule_names()
+        );
+    }
+}
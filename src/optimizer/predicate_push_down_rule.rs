@@ -0,0 +1,290 @@
+use crate::logical_plan::aggregate::Aggregate;
+use crate::logical_plan::expressions::binary_expr::{and, Base, BinaryExpr};
+use crate::logical_plan::expressions::{col, Expr};
+use crate::logical_plan::join::{Join, JoinType};
+use crate::logical_plan::limit::Limit;
+use crate::logical_plan::logical_expr::LogicalExpr;
+use crate::logical_plan::projection::Projection;
+use crate::logical_plan::scan::Scan;
+use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::Sort;
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::{replace_columns, OptimizerRule};
+use std::sync::Arc;
+
+/// Moves filters as close to the scan as possible. Conjunctive filters are split, so each part
+/// of an `AND` is pushed down independently, and references to the output of a projection or to
+/// the group columns of an aggregate are rewritten to the expressions producing them.
+pub struct PredicatePushDownRule;
+
+impl OptimizerRule for PredicatePushDownRule {
+    fn name(&self) -> &str {
+        "predicate_push_down"
+    }
+
+    fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
+        Self::push_down(plan, vec![])
+    }
+}
+
+impl PredicatePushDownRule {
+    /// `predicates` refer to the output of `plan` and are applied on top of it if they cannot be
+    /// pushed any further
+    fn push_down(
+        plan: Arc<dyn LogicalPlan>,
+        mut predicates: Vec<Arc<Expr>>,
+    ) -> Arc<dyn LogicalPlan> {
+        if let Some(selection) = plan.as_any().downcast_ref::<Selection>() {
+            split_conjunction(selection.expr.clone(), &mut predicates);
+            Self::push_down(selection.input.clone(), predicates)
+        } else if let Some(projection) = plan.as_any().downcast_ref::<Projection>() {
+            let outputs = Self::outputs(&projection.expr, projection.input.clone());
+            let (pushed, kept) = Self::rewrite(predicates, &outputs, 0);
+            let input = Self::push_down(projection.input.clone(), pushed);
+            Self::apply(Projection::new(input, projection.expr.clone()), kept)
+        } else if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
+            // only filters on the group columns can be evaluated before aggregating
+            let outputs = Self::outputs(&aggregate.group_expr, aggregate.input.clone());
+            let (pushed, kept) = Self::rewrite(predicates, &outputs, 0);
+            let input = Self::push_down(aggregate.input.clone(), pushed);
+            let aggregate = Aggregate::new(
+                input,
+                aggregate.group_expr.clone(),
+                aggregate.aggregate_expr.clone(),
+            );
+            Self::apply(aggregate, kept)
+        } else if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            // filtering does not change the order of the remaining rows
+            let input = Self::push_down(sort.input.clone(), predicates);
+            Sort::new(input, sort.sort_expr.clone())
+        } else if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            // filtering before a limit would change which rows are returned
+            let input = Self::push_down(limit.input.clone(), vec![]);
+            Self::apply(Limit::new(input, limit.offset, limit.limit), predicates)
+        } else if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            // a predicate on a single input is evaluated before joining, unless that input
+            // is filled with nulls by an outer join
+            let (push_left, push_right) = match join.join_type {
+                JoinType::Inner => (true, true),
+                JoinType::Left | JoinType::Semi | JoinType::Anti => (true, false),
+                JoinType::Right => (false, true),
+                JoinType::Full => (false, false),
+            };
+            let left_len = join.left.schema().fields.len();
+            let outputs = Self::join_outputs(join);
+            let (left_predicates, predicates) = match push_left {
+                true => Self::rewrite(predicates, &outputs[..left_len], 0),
+                false => (vec![], predicates),
+            };
+            let (right_predicates, kept) = match push_right {
+                true => Self::rewrite(predicates, &outputs[left_len..], left_len),
+                false => (vec![], predicates),
+            };
+            let left = Self::push_down(join.left.clone(), left_predicates);
+            let right = Self::push_down(join.right.clone(), right_predicates);
+            Self::apply(join.with_inputs(left, right), kept)
+        } else if plan.as_any().downcast_ref::<Scan>().is_some() {
+            Self::apply(plan, predicates)
+        } else {
+            panic!(
+                "PredicatePushDownRule does not support plan: {}",
+                plan.to_string()
+            )
+        }
+    }
+
+    /// Name and producing expression of each output column of the given expressions
+    fn outputs(expr: &[Arc<Expr>], input: Arc<dyn LogicalPlan>) -> Vec<(String, Arc<Expr>)> {
+        expr.iter()
+            .map(|e| {
+                let name = e
+                    .to_field(input.clone())
+                    .map(|f| f.name.clone())
+                    .unwrap_or_default();
+                (name, strip_alias(e.clone()))
+            })
+            .collect()
+    }
+
+    /// Output columns of a join with the input column producing them
+    fn join_outputs(join: &Join) -> Vec<(String, Arc<Expr>)> {
+        let input_fields = join
+            .left
+            .schema()
+            .fields
+            .iter()
+            .chain(join.right.schema().fields.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        join.schema()
+            .fields
+            .iter()
+            .zip(input_fields)
+            .map(|(output, input)| (output.name.clone(), col(input.name.clone())))
+            .collect()
+    }
+
+    /// Splits the predicates into those that could be rewritten in terms of the input and those
+    /// that have to stay on top. `offset` is the position of the first of the `outputs` within
+    /// all output columns, for resolving column indices.
+    fn rewrite(
+        predicates: Vec<Arc<Expr>>,
+        outputs: &[(String, Arc<Expr>)],
+        offset: usize,
+    ) -> (Vec<Arc<Expr>>, Vec<Arc<Expr>>) {
+        let resolve = |e: &Expr| match e {
+            Expr::Column(col) => outputs
+                .iter()
+                .find(|(name, _)| *name == col.name)
+                .map(|(_, expr)| expr.clone()),
+            Expr::ColumnIndex(col_index) => col_index
+                .i
+                .checked_sub(offset)
+                .and_then(|i| outputs.get(i))
+                .map(|(_, expr)| expr.clone()),
+            _ => None,
+        };
+
+        let mut pushed = vec![];
+        let mut kept = vec![];
+        for predicate in predicates {
+            match replace_columns(predicate.clone(), &resolve) {
+                Some(rewritten) => pushed.push(rewritten),
+                None => kept.push(predicate),
+            }
+        }
+        (pushed, kept)
+    }
+
+    fn apply(plan: Arc<dyn LogicalPlan>, predicates: Vec<Arc<Expr>>) -> Arc<dyn LogicalPlan> {
+        match predicates.into_iter().reduce(and) {
+            Some(expr) => Selection::new(plan, expr),
+            None => plan,
+        }
+    }
+}
+
+pub(crate) fn split_conjunction(expr: Arc<Expr>, accum: &mut Vec<Arc<Expr>>) {
+    match &*expr {
+        Expr::Binary(bin @ BinaryExpr::And(_)) => {
+            split_conjunction(bin.get_left(), accum);
+            split_conjunction(bin.get_right(), accum);
+        }
+        _ => accum.push(expr),
+    }
+}
+
+fn strip_alias(expr: Arc<Expr>) -> Arc<Expr> {
+    match &*expr {
+        Expr::Alias(a) => strip_alias(a.expr.clone()),
+        _ => expr,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::Source;
+    use crate::logical_plan::data_frame::{DataFrame, DataFrameImpl};
+    use crate::logical_plan::expressions::aggr_expr::max;
+    use crate::logical_plan::expressions::binary_expr::{and, BooleanBinaryExprExt};
+    use crate::logical_plan::expressions::literal_expr::lit_str;
+    use crate::logical_plan::expressions::{alias, col, col_index};
+    use crate::logical_plan::join::JoinType;
+    use crate::logical_plan::scan::Scan;
+    use crate::logical_plan::LogicalPlanPrinter;
+    use crate::optimizer::predicate_push_down_rule::PredicatePushDownRule;
+    use crate::optimizer::OptimizerRule;
+    use std::sync::Arc;
+
+    fn employee() -> Arc<dyn DataFrame> {
+        let csv =
+            Source::from_csv("testdata/employee.csv", None, CsvOptions::default(), 1024).unwrap();
+        Arc::new(DataFrameImpl {
+            plan: Scan::new("employee".to_string(), csv, vec![]),
+        })
+    }
+
+    fn state() -> Arc<dyn DataFrame> {
+        let csv =
+            Source::from_csv("testdata/state.csv", None, CsvOptions::default(), 1024).unwrap();
+        Arc::new(DataFrameImpl {
+            plan: Scan::new("state".to_string(), csv, vec![]),
+        })
+    }
+
+    #[test]
+    fn test_push_down_through_projection() {
+        let df = employee()
+            .project(vec![col("id"), alias(col("state"), "s")])
+            .filter(and(col("s").eq(lit_str("CO")), col("id").neq(lit_str("2"))));
+
+        let plan = PredicatePushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Projection: id, state AS s\n\
+            \tSelection: state = CO AND id != 2\n\
+            \t\tScan: employee; projection=None\n",
+            plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_push_down_group_columns_only() {
+        let df = employee()
+            .aggregate(vec![col("state")], vec![max(col("salary"))])
+            .filter(and(
+                col_index(0).eq(lit_str("CO")),
+                col_index(1).eq(lit_str("11500")),
+            ));
+
+        let plan = PredicatePushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Selection: 1 = 11500\n\
+            \tAggregate: group_expr=state, aggregate_expr=MAX(salary)\n\
+            \t\tSelection: state = CO\n\
+            \t\t\tScan: employee; projection=None\n",
+            plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_no_push_down_through_limit() {
+        let df = employee()
+            .filter(col("state").eq(lit_str("CO")))
+            .limit(1)
+            .filter(col("id").eq(lit_str("2")));
+
+        let plan = PredicatePushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Selection: id = 2\n\
+            \tLimit: 1\n\
+            \t\tSelection: state = CO\n\
+            \t\t\tScan: employee; projection=None\n",
+            plan.pretty()
+        );
+    }
+
+    #[test]
+    fn test_push_down_into_preserved_join_input() {
+        let df = employee()
+            .join(state(), JoinType::Left, vec![("state", "state")])
+            .filter(and(
+                col("employee.state").eq(lit_str("CO")),
+                col("name").eq(lit_str("Colorado")),
+            ));
+
+        let plan = PredicatePushDownRule.optimize(df.logical_plan());
+
+        assert_eq!(
+            "Selection: name = Colorado\n\
+            \tJoin: type=Left, on=employee.state = state.state\n\
+            \t\tSelection: state = CO\n\
+            \t\t\tScan: employee; projection=None\n\
+            \t\tScan: state; projection=None\n",
+            plan.pretty()
+        );
+    }
+}
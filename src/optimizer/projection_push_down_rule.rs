@@ -1,7 +1,10 @@
 use crate::logical_plan::aggregate::Aggregate;
+use crate::logical_plan::join::Join;
+use crate::logical_plan::limit::Limit;
 use crate::logical_plan::projection::Projection;
 use crate::logical_plan::scan::Scan;
 use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::Sort;
 use crate::logical_plan::LogicalPlan;
 use crate::optimizer::{extract_all_columns, extract_columns, OptimizerRule};
 use std::collections::HashSet;
@@ -10,6 +13,10 @@ use std::sync::Arc;
 pub struct ProjectionPushDownRule;
 
 impl OptimizerRule for ProjectionPushDownRule {
+    fn name(&self) -> &str {
+        "projection_push_down"
+    }
+
     fn optimize(&self, plan: Arc<dyn LogicalPlan>) -> Arc<dyn LogicalPlan> {
         let mut col_set = HashSet::new();
         Self::push_down(plan, &mut col_set)
@@ -37,6 +44,15 @@ impl ProjectionPushDownRule {
             );
             let input = Self::push_down(selection.input.clone(), column_names);
             Selection::new(input, selection.expr.clone())
+        } else if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            for s in &sort.sort_expr {
+                extract_columns(s.expr.clone(), sort.input.clone(), column_names);
+            }
+            let input = Self::push_down(sort.input.clone(), column_names);
+            Sort::new(input, sort.sort_expr.clone())
+        } else if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            let input = Self::push_down(limit.input.clone(), column_names);
+            Limit::new(input, limit.offset, limit.limit)
         } else if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
             extract_all_columns(
                 aggregate.group_expr.clone(),
@@ -54,6 +70,28 @@ impl ProjectionPushDownRule {
                 aggregate.group_expr.clone(),
                 aggregate.aggregate_expr.clone(),
             )
+        } else if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            // qualified output columns are read by their name in the input
+            let input_fields: Vec<_> = join
+                .left
+                .schema()
+                .fields
+                .iter()
+                .chain(join.right.schema().fields.iter())
+                .cloned()
+                .collect();
+            for (output, input) in join.schema().fields.iter().zip(input_fields) {
+                if column_names.contains(&output.name) {
+                    column_names.insert(input.name.clone());
+                }
+            }
+            for (l, r) in &join.on {
+                column_names.insert(l.clone());
+                column_names.insert(r.clone());
+            }
+            let left = Self::push_down(join.left.clone(), column_names);
+            let right = Self::push_down(join.right.clone(), column_names);
+            join.with_inputs(left, right)
         } else if let Some(scan) = plan.as_any().downcast_ref::<Scan>() {
             let valid_field_names: HashSet<String> = scan
                 .datasource
@@ -72,7 +110,7 @@ impl ProjectionPushDownRule {
             let mut push_down_vec: Vec<String> = push_down_set.into_iter().collect();
             push_down_vec.sort();
 
-            Scan::new(&scan.path, scan.datasource.clone(), push_down_vec)
+            scan.with_projection(push_down_vec)
         } else {
             panic!(
                 "ProjectionPushDownRule does not support plan: {}",
@@ -84,6 +122,7 @@ impl ProjectionPushDownRule {
 
 #[cfg(test)]
 mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
     use crate::datatypes::arrow_types::ArrowType;
     use crate::execution::ExecutionContext;
     use crate::logical_plan::expressions::aggr_expr::{min, sum};
@@ -99,7 +138,8 @@ mod test {
         let ctx = ExecutionContext::new(HashMap::default());
 
         let df = ctx
-            .csv("testdata/employee.csv", true)
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
             .filter(col("state").eq(lit_str("CO")))
             .aggregate(
                 vec![col("state")],
@@ -114,8 +154,7 @@ mod test {
         let optimized_plan = Optimizer::optimize(plan);
 
         let expected_plan = "Aggregate: group_expr=state, aggregate_expr=SUM(CAST(salary AS DoubleType)), MIN(CAST(salary AS Int64Type))\n\
-        \tSelection: state = CO\n\
-        \t\tScan: testdata/employee.csv; projection=[\"salary\", \"state\"]\n";
+        \tScan: testdata/employee.csv; projection=[\"salary\", \"state\"]; filters=[state = CO]\n";
 
         assert_eq!(expected_plan, optimized_plan.pretty())
     }
@@ -0,0 +1,99 @@
+use crate::datatypes::arrow_types::ArrowType;
+use crate::physical_plan::expressions::aggregate_expression::AggregateExpression;
+use crate::physical_plan::expressions::{Accumulator, Expression};
+use std::any::Any;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+pub struct AvgExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for AvgExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AVG({})", self.expr.to_string())
+    }
+}
+
+impl AggregateExpression for AvgExpression {
+    fn input_expression(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self, arrow_type: ArrowType) -> Arc<Mutex<dyn Accumulator>> {
+        let acc = AvgAccumulator::new(arrow_type);
+        Arc::new(Mutex::new(acc))
+    }
+}
+
+/// Sums up all non null values as f64 and divides the sum by their count in the end
+pub struct AvgAccumulator {
+    sum: f64,
+    count: i64,
+    arrow_type: ArrowType,
+}
+
+impl AvgAccumulator {
+    fn new(arrow_type: ArrowType) -> Self {
+        AvgAccumulator {
+            sum: 0.0,
+            count: 0,
+            arrow_type,
+        }
+    }
+}
+
+impl Accumulator for AvgAccumulator {
+    fn accumulate(&mut self, value: Option<Arc<dyn Any>>) {
+        let Some(value) = value else { return };
+        let val = match self.arrow_type {
+            ArrowType::Int8Type => value.downcast_ref::<i8>().map(|v| *v as f64),
+            ArrowType::Int16Type => value.downcast_ref::<i16>().map(|v| *v as f64),
+            ArrowType::Int32Type => value.downcast_ref::<i32>().map(|v| *v as f64),
+            ArrowType::Int64Type => value.downcast_ref::<i64>().map(|v| *v as f64),
+            ArrowType::UInt8Type => value.downcast_ref::<u8>().map(|v| *v as f64),
+            ArrowType::UInt16Type => value.downcast_ref::<u16>().map(|v| *v as f64),
+            ArrowType::UInt32Type => value.downcast_ref::<u32>().map(|v| *v as f64),
+            ArrowType::UInt64Type => value.downcast_ref::<u64>().map(|v| *v as f64),
+            ArrowType::FloatType => value.downcast_ref::<f32>().map(|v| *v as f64),
+            ArrowType::DoubleType => value.downcast_ref::<f64>().copied(),
+            _ => panic!(
+                "Unsupported data type in avg expression: {:?}",
+                self.arrow_type
+            ),
+        };
+        if let Some(val) = val {
+            self.sum += val;
+            self.count += 1;
+        }
+    }
+
+    fn final_value(&self) -> Option<Arc<dyn Any>> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(Arc::new(self.sum / self.count as f64))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::physical_plan::expressions::avg_expression::AvgAccumulator;
+    use crate::physical_plan::expressions::Accumulator;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_avg_accumulator() {
+        let mut acc = AvgAccumulator::new(ArrowType::Int64Type);
+        assert!(acc.final_value().is_none());
+
+        acc.accumulate(Some(Arc::new(1_i64)));
+        acc.accumulate(None);
+        acc.accumulate(Some(Arc::new(2_i64)));
+
+        let binding = acc.final_value().unwrap();
+        assert_eq!(*binding.downcast_ref::<f64>().unwrap(), 1.5);
+    }
+}
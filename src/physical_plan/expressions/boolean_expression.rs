@@ -32,12 +32,16 @@ pub trait BooleanExpression: Expression {
         let array = ArrowArrayFactory::create(ArrowType::BooleanType.to_datatype(), vec_size);
         let mut boolean_vector = ArrowVectorBuilder::new(array);
         for i in 0..vec_size {
-            let value = self.evaluate_bool(
-                l.get_value(i).unwrap().deref(),
-                r.get_value(i).unwrap().deref(),
-                l.get_type(),
-            );
-            boolean_vector.append(Some(Arc::new(value)))
+            // comparing against null yields null
+            let value = match (l.get_value(i), r.get_value(i)) {
+                (Some(l_value), Some(r_value)) => Some(Arc::new(self.evaluate_bool(
+                    l_value.deref(),
+                    r_value.deref(),
+                    l.get_type(),
+                )) as Arc<dyn Any>),
+                _ => None,
+            };
+            boolean_vector.append(value)
         }
         boolean_vector.build()
     }
@@ -71,6 +75,11 @@ impl BooleanExpression for AndExpression {
         self.r.clone()
     }
 
+    /// Three-valued AND: false if either side is false, null if either side is null
+    fn compare(&self, l: Arc<dyn ColumnVector>, r: Arc<dyn ColumnVector>) -> Arc<dyn ColumnVector> {
+        compare_kleene(l, r, false)
+    }
+
     fn evaluate_bool(&self, l: &dyn Any, r: &dyn Any, arrow_type: ArrowType) -> bool {
         to_bool(l.deref()) && to_bool(r.deref())
     }
@@ -104,6 +113,11 @@ impl BooleanExpression for OrExpression {
         self.r.clone()
     }
 
+    /// Three-valued OR: true if either side is true, null if either side is null
+    fn compare(&self, l: Arc<dyn ColumnVector>, r: Arc<dyn ColumnVector>) -> Arc<dyn ColumnVector> {
+        compare_kleene(l, r, true)
+    }
+
     fn evaluate_bool(&self, l: &dyn Any, r: &dyn Any, arrow_type: ArrowType) -> bool {
         to_bool(l.deref()) || to_bool(r.deref())
     }
@@ -411,3 +425,27 @@ fn to_bool(v: &dyn Any) -> bool {
         None => panic!("Not a bool"),
     }
 }
+
+/// Combines two boolean vectors where `dominant` wins over null and the other value,
+/// i.e. `false` for AND and `true` for OR
+fn compare_kleene(
+    l: Arc<dyn ColumnVector>,
+    r: Arc<dyn ColumnVector>,
+    dominant: bool,
+) -> Arc<dyn ColumnVector> {
+    let vec_size = l.size();
+    let array = ArrowArrayFactory::create(ArrowType::BooleanType.to_datatype(), vec_size);
+    let mut boolean_vector = ArrowVectorBuilder::new(array);
+    for i in 0..vec_size {
+        let l_value = l.get_value(i).map(|v| to_bool(v.deref()));
+        let r_value = r.get_value(i).map(|v| to_bool(v.deref()));
+        let value = match (l_value, r_value) {
+            (Some(l), _) if l == dominant => Some(dominant),
+            (_, Some(r)) if r == dominant => Some(dominant),
+            (Some(_), Some(_)) => Some(!dominant),
+            _ => None,
+        };
+        boolean_vector.append(value.map(|v| Arc::new(v) as Arc<dyn Any>))
+    }
+    boolean_vector.build()
+}
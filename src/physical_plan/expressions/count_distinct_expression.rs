@@ -0,0 +1,78 @@
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::physical_plan::expressions::aggregate_expression::AggregateExpression;
+use crate::physical_plan::expressions::{Accumulator, Expression};
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+pub struct CountDistinctExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for CountDistinctExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "COUNT(DISTINCT {})", self.expr.to_string())
+    }
+}
+
+impl AggregateExpression for CountDistinctExpression {
+    fn input_expression(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self, arrow_type: ArrowType) -> Arc<Mutex<dyn Accumulator>> {
+        let acc = CountDistinctAccumulator::new(arrow_type);
+        Arc::new(Mutex::new(acc))
+    }
+}
+
+/// Counts all distinct non null values.
+/// Like the grouping keys in HashAggregateExec, values are compared by their string representation.
+pub struct CountDistinctAccumulator {
+    values: HashSet<String>,
+    arrow_type: ArrowType,
+}
+
+impl CountDistinctAccumulator {
+    fn new(arrow_type: ArrowType) -> Self {
+        CountDistinctAccumulator {
+            values: HashSet::new(),
+            arrow_type,
+        }
+    }
+}
+
+impl Accumulator for CountDistinctAccumulator {
+    fn accumulate(&mut self, value: Option<Arc<dyn Any>>) {
+        if let Some(value) = value.and_then(|v| RecordBatch::value_to_string(v, &self.arrow_type)) {
+            self.values.insert(value);
+        }
+    }
+
+    fn final_value(&self) -> Option<Arc<dyn Any>> {
+        Some(Arc::new(self.values.len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::physical_plan::expressions::count_distinct_expression::CountDistinctAccumulator;
+    use crate::physical_plan::expressions::Accumulator;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_count_distinct_accumulator() {
+        let mut acc = CountDistinctAccumulator::new(ArrowType::Int32Type);
+
+        acc.accumulate(Some(Arc::new(7_i32)));
+        acc.accumulate(None);
+        acc.accumulate(Some(Arc::new(7_i32)));
+        acc.accumulate(Some(Arc::new(8_i32)));
+
+        let binding = acc.final_value().unwrap();
+        assert_eq!(*binding.downcast_ref::<i64>().unwrap(), 2);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::datatypes::arrow_types::ArrowType;
+use crate::physical_plan::expressions::aggregate_expression::AggregateExpression;
+use crate::physical_plan::expressions::{Accumulator, Expression};
+use std::any::Any;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+pub struct CountExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for CountExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "COUNT({})", self.expr.to_string())
+    }
+}
+
+impl AggregateExpression for CountExpression {
+    fn input_expression(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self, _arrow_type: ArrowType) -> Arc<Mutex<dyn Accumulator>> {
+        Arc::new(Mutex::new(CountAccumulator::default()))
+    }
+}
+
+/// Counts all non null values
+#[derive(Default)]
+pub struct CountAccumulator {
+    count: i64,
+}
+
+impl Accumulator for CountAccumulator {
+    fn accumulate(&mut self, value: Option<Arc<dyn Any>>) {
+        if value.is_some() {
+            self.count += 1;
+        }
+    }
+
+    fn final_value(&self) -> Option<Arc<dyn Any>> {
+        Some(Arc::new(self.count))
+    }
+}
@@ -1,12 +1,16 @@
 pub mod aggregate_expression;
+pub mod avg_expression;
 pub mod binary_expression;
 pub mod boolean_expression;
 pub mod cast_expression;
 pub mod column_expression;
+pub mod count_distinct_expression;
+pub mod count_expression;
 pub mod math_expression;
 pub mod max_expression;
 pub mod min_expression;
 pub mod sum_expression;
+pub mod unary_expression;
 
 use crate::datatypes::arrow_types::ArrowType;
 use crate::datatypes::column_vector::ColumnVector;
@@ -104,3 +108,23 @@ impl Expression for LiteralStringExpression {
         })
     }
 }
+
+pub struct LiteralBooleanExpression {
+    pub value: bool,
+}
+
+impl Display for LiteralBooleanExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Expression for LiteralBooleanExpression {
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        Arc::new(LiteralValueVector {
+            arrow_type: ArrowType::BooleanType,
+            value: Some(Arc::new(self.value)),
+            size: input.row_count(),
+        })
+    }
+}
@@ -0,0 +1,337 @@
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+use crate::datatypes::column_vector::ColumnVector;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::physical_plan::expressions::Expression;
+use std::any::Any;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Common framework for evaluating unary expressions.
+/// The input expression is evaluated first and the specific unary operator is then applied
+/// to each of the resulting values, where a missing value represents null.
+pub trait UnaryExpression: Expression {
+    fn expr(&self) -> Arc<dyn Expression>;
+
+    /// Returns the type of the output vector for the given input type
+    fn result_type(&self, input_type: ArrowType) -> ArrowType;
+
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        let value = self.expr().evaluate(input);
+        let input_type = value.get_type();
+        let array = ArrowArrayFactory::create(
+            self.result_type(input_type.clone()).to_datatype(),
+            value.size(),
+        );
+        let mut vector = ArrowVectorBuilder::new(array);
+        for i in 0..value.size() {
+            let v = value.get_value(i);
+            vector.append(self.evaluate_unary(v.as_deref(), &input_type));
+        }
+        vector.build()
+    }
+
+    fn evaluate_unary(
+        &self,
+        value: Option<&dyn Any>,
+        arrow_type: &ArrowType,
+    ) -> Option<Arc<dyn Any>>;
+}
+
+pub struct NotExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for NotExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NOT {}", self.expr.to_string())
+    }
+}
+
+impl Expression for NotExpression {
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        UnaryExpression::evaluate(self, input)
+    }
+}
+
+impl UnaryExpression for NotExpression {
+    fn expr(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn result_type(&self, _input_type: ArrowType) -> ArrowType {
+        ArrowType::BooleanType
+    }
+
+    /// NOT null is null
+    fn evaluate_unary(
+        &self,
+        value: Option<&dyn Any>,
+        _arrow_type: &ArrowType,
+    ) -> Option<Arc<dyn Any>> {
+        value.map(|v| match v.downcast_ref::<bool>() {
+            Some(b) => Arc::new(!*b) as Arc<dyn Any>,
+            None => panic!("Not a bool"),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+pub struct NegateExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for NegateExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "-{}", self.expr.to_string())
+    }
+}
+
+impl Expression for NegateExpression {
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        UnaryExpression::evaluate(self, input)
+    }
+}
+
+impl UnaryExpression for NegateExpression {
+    fn expr(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn result_type(&self, input_type: ArrowType) -> ArrowType {
+        input_type
+    }
+
+    fn evaluate_unary(
+        &self,
+        value: Option<&dyn Any>,
+        arrow_type: &ArrowType,
+    ) -> Option<Arc<dyn Any>> {
+        let v = value?;
+        match arrow_type {
+            ArrowType::Int8Type => v.downcast_ref::<i8>().map(|v| Arc::new(-v) as Arc<dyn Any>),
+            ArrowType::Int16Type => v
+                .downcast_ref::<i16>()
+                .map(|v| Arc::new(-v) as Arc<dyn Any>),
+            ArrowType::Int32Type => v
+                .downcast_ref::<i32>()
+                .map(|v| Arc::new(-v) as Arc<dyn Any>),
+            ArrowType::Int64Type => v
+                .downcast_ref::<i64>()
+                .map(|v| Arc::new(-v) as Arc<dyn Any>),
+            ArrowType::FloatType => v
+                .downcast_ref::<f32>()
+                .map(|v| Arc::new(-v) as Arc<dyn Any>),
+            ArrowType::DoubleType => v
+                .downcast_ref::<f64>()
+                .map(|v| Arc::new(-v) as Arc<dyn Any>),
+            _ => panic!(
+                "Unsupported data type in negate expression: {:?}",
+                arrow_type
+            ),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+pub struct IsNullExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for IsNullExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} IS NULL", self.expr.to_string())
+    }
+}
+
+impl Expression for IsNullExpression {
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        UnaryExpression::evaluate(self, input)
+    }
+}
+
+impl UnaryExpression for IsNullExpression {
+    fn expr(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn result_type(&self, _input_type: ArrowType) -> ArrowType {
+        ArrowType::BooleanType
+    }
+
+    fn evaluate_unary(
+        &self,
+        value: Option<&dyn Any>,
+        _arrow_type: &ArrowType,
+    ) -> Option<Arc<dyn Any>> {
+        Some(Arc::new(value.is_none()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////
+
+pub struct IsNotNullExpression {
+    pub expr: Arc<dyn Expression>,
+}
+
+impl Display for IsNotNullExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} IS NOT NULL", self.expr.to_string())
+    }
+}
+
+impl Expression for IsNotNullExpression {
+    fn evaluate(&self, input: &RecordBatch) -> Arc<dyn ColumnVector> {
+        UnaryExpression::evaluate(self, input)
+    }
+}
+
+impl UnaryExpression for IsNotNullExpression {
+    fn expr(&self) -> Arc<dyn Expression> {
+        self.expr.clone()
+    }
+
+    fn result_type(&self, _input_type: ArrowType) -> ArrowType {
+        ArrowType::BooleanType
+    }
+
+    fn evaluate_unary(
+        &self,
+        value: Option<&dyn Any>,
+        _arrow_type: &ArrowType,
+    ) -> Option<Arc<dyn Any>> {
+        Some(Arc::new(value.is_some()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+    use crate::datatypes::record_batch::RecordBatch;
+    use crate::datatypes::schema::{Field, Schema};
+    use crate::physical_plan::expressions::boolean_expression::{AndExpression, OrExpression};
+    use crate::physical_plan::expressions::column_expression::ColumnExpression;
+    use crate::physical_plan::expressions::unary_expression::{
+        IsNotNullExpression, IsNullExpression, NegateExpression, NotExpression,
+    };
+    use crate::physical_plan::expressions::Expression;
+    use std::any::Any;
+    use std::sync::Arc;
+
+    /// batch with a boolean column `b` = [true, false, null] and an i64 column `i` = [1, null, 3]
+    fn test_batch() -> RecordBatch {
+        let mut b = ArrowVectorBuilder::new(ArrowArrayFactory::create(
+            ArrowType::BooleanType.to_datatype(),
+            3,
+        ));
+        b.append(Some(Arc::new(true)));
+        b.append(Some(Arc::new(false)));
+        b.append(None);
+        let mut i = ArrowVectorBuilder::new(ArrowArrayFactory::create(
+            ArrowType::Int64Type.to_datatype(),
+            3,
+        ));
+        i.append(Some(Arc::new(1_i64)));
+        i.append(None);
+        i.append(Some(Arc::new(3_i64)));
+
+        RecordBatch {
+            schema: Arc::new(Schema {
+                fields: vec![
+                    Arc::new(Field {
+                        name: "b".to_string(),
+                        data_type: ArrowType::BooleanType,
+                        nullable: true,
+                    }),
+                    Arc::new(Field {
+                        name: "i".to_string(),
+                        data_type: ArrowType::Int64Type,
+                        nullable: true,
+                    }),
+                ],
+            }),
+            fields: vec![b.build(), i.build()],
+        }
+    }
+
+    fn evaluate_bools(expr: &dyn Expression) -> Vec<Option<bool>> {
+        let result = expr.evaluate(&test_batch());
+        (0..result.size())
+            .map(|i| {
+                result
+                    .get_value(i)
+                    .map(|v| *v.downcast_ref::<bool>().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_not() {
+        let not = NotExpression {
+            expr: Arc::new(ColumnExpression { i: 0 }),
+        };
+
+        assert_eq!(vec![Some(false), Some(true), None], evaluate_bools(&not));
+    }
+
+    #[test]
+    fn test_is_null_and_is_not_null() {
+        let is_null = IsNullExpression {
+            expr: Arc::new(ColumnExpression { i: 1 }),
+        };
+        let is_not_null = IsNotNullExpression {
+            expr: Arc::new(ColumnExpression { i: 1 }),
+        };
+
+        assert_eq!(
+            vec![Some(false), Some(true), Some(false)],
+            evaluate_bools(&is_null)
+        );
+        assert_eq!(
+            vec![Some(true), Some(false), Some(true)],
+            evaluate_bools(&is_not_null)
+        );
+    }
+
+    #[test]
+    fn test_negate() {
+        let negate = NegateExpression {
+            expr: Arc::new(ColumnExpression { i: 1 }),
+        };
+
+        let result = negate.evaluate(&test_batch());
+        let values: Vec<Option<i64>> = (0..result.size())
+            .map(|i| {
+                result
+                    .get_value(i)
+                    .map(|v: Arc<dyn Any>| *v.downcast_ref::<i64>().unwrap())
+            })
+            .collect();
+        assert_eq!(vec![Some(-1), None, Some(-3)], values);
+    }
+
+    #[test]
+    fn test_three_valued_and_or() {
+        // b AND NOT b = [false, false, null], b OR NOT b = [true, true, null]
+        let and = AndExpression {
+            l: Arc::new(ColumnExpression { i: 0 }),
+            r: Arc::new(NotExpression {
+                expr: Arc::new(ColumnExpression { i: 0 }),
+            }),
+        };
+        let or = OrExpression {
+            l: Arc::new(ColumnExpression { i: 0 }),
+            r: Arc::new(NotExpression {
+                expr: Arc::new(ColumnExpression { i: 0 }),
+            }),
+        };
+
+        assert_eq!(vec![Some(false), Some(false), None], evaluate_bools(&and));
+        assert_eq!(vec![Some(true), Some(true), None], evaluate_bools(&or));
+    }
+}
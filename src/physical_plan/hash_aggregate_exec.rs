@@ -88,6 +88,17 @@ impl PhysicalPlan for HashAggregateExec {
             }
         });
 
+        // without grouping the aggregate has a single row, even for an empty input
+        if self.group_expr.is_empty() && map.is_empty() {
+            let accumulators = self
+                .aggregate_expr
+                .iter()
+                .zip(self.schema.fields.iter())
+                .map(|(a, f)| a.create_accumulator(f.data_type.clone()))
+                .collect();
+            map.insert(vec![], accumulators);
+        }
+
         let mut builders = Vec::new();
 
         self.schema.fields.iter().for_each(|f| {
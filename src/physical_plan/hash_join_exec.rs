@@ -0,0 +1,231 @@
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+use crate::datatypes::column_vector::ColumnVector;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::Schema;
+use crate::logical_plan::join::JoinType;
+use crate::physical_plan::expressions::Expression;
+use crate::physical_plan::PhysicalPlan;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Row of the join output as index of the left row and (batch, row) of the right row,
+/// where a missing side is filled with nulls
+type JoinedRow = (Option<usize>, Option<(usize, usize)>);
+
+/// Equi-join which builds a hash table on the right input and streams the left input through it
+pub struct HashJoinExec {
+    pub left: Arc<dyn PhysicalPlan>,
+    pub right: Arc<dyn PhysicalPlan>,
+    pub join_type: JoinType,
+    pub left_keys: Vec<Arc<dyn Expression>>,
+    pub right_keys: Vec<Arc<dyn Expression>>,
+    pub schema: Arc<Schema>,
+}
+
+impl Display for HashJoinExec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "HashJoinExec: type={:?}, on={}",
+            self.join_type,
+            self.left_keys
+                .iter()
+                .zip(self.right_keys.iter())
+                .map(|(l, r)| format!("{} = {}", l.to_string(), r.to_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl PhysicalPlan for HashJoinExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        // todo use impl Hash or Eq instead of just string, like in the HashAggregateExec
+        let right_batches: Vec<RecordBatch> = self.right.execute().collect();
+        let mut table: HashMap<Vec<String>, Vec<(usize, usize)>> = HashMap::new();
+        for (batch_index, batch) in right_batches.iter().enumerate() {
+            let keys = evaluate_keys(&self.right_keys, batch);
+            for row_index in 0..batch.row_count() {
+                if let Some(key) = row_key(&keys, row_index) {
+                    table.entry(key).or_default().push((batch_index, row_index));
+                }
+            }
+        }
+        let matched = right_batches
+            .iter()
+            .map(|b| vec![false; b.row_count()])
+            .collect();
+
+        Box::new(HashJoinIterator {
+            exec: self,
+            left: self.left.execute(),
+            right_batches,
+            table,
+            matched,
+            finished: false,
+        })
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+}
+
+fn evaluate_keys(keys: &[Arc<dyn Expression>], batch: &RecordBatch) -> Vec<Arc<dyn ColumnVector>> {
+    keys.iter().map(|k| k.evaluate(batch)).collect()
+}
+
+/// Key of the row, None if any of its values is null since null never equals anything
+fn row_key(keys: &[Arc<dyn ColumnVector>], row_index: usize) -> Option<Vec<String>> {
+    keys.iter()
+        .map(|k| RecordBatch::value_to_string(k.get_value(row_index)?, &k.get_type()))
+        .collect()
+}
+
+struct HashJoinIterator<'a> {
+    exec: &'a HashJoinExec,
+    left: Box<dyn Iterator<Item = RecordBatch> + 'a>,
+    right_batches: Vec<RecordBatch>,
+    table: HashMap<Vec<String>, Vec<(usize, usize)>>,
+    /// right rows which found a match, to emit the remaining ones of right and full joins
+    matched: Vec<Vec<bool>>,
+    finished: bool,
+}
+
+impl HashJoinIterator<'_> {
+    fn probe(&mut self, batch: &RecordBatch) -> RecordBatch {
+        let join_type = self.exec.join_type;
+        let keys = evaluate_keys(&self.exec.left_keys, batch);
+        let mut rows: Vec<JoinedRow> = vec![];
+        for row_index in 0..batch.row_count() {
+            let matches = row_key(&keys, row_index).and_then(|k| self.table.get(&k));
+            match (join_type, matches) {
+                (JoinType::Semi, Some(_)) | (JoinType::Anti, None) => {
+                    rows.push((Some(row_index), None))
+                }
+                (JoinType::Semi | JoinType::Anti, _) => {}
+                (_, Some(matches)) => {
+                    for &(b, r) in matches {
+                        self.matched[b][r] = true;
+                        rows.push((Some(row_index), Some((b, r))));
+                    }
+                }
+                (JoinType::Left | JoinType::Full, None) => rows.push((Some(row_index), None)),
+                (_, None) => {}
+            }
+        }
+        self.build(Some(batch), &rows)
+    }
+
+    fn unmatched_right(&self) -> Vec<JoinedRow> {
+        self.matched
+            .iter()
+            .enumerate()
+            .flat_map(|(b, rows)| {
+                rows.iter()
+                    .enumerate()
+                    .filter(|(_, matched)| !**matched)
+                    .map(move |(r, _)| (None, Some((b, r))))
+            })
+            .collect()
+    }
+
+    fn build(&self, left: Option<&RecordBatch>, rows: &[JoinedRow]) -> RecordBatch {
+        let schema = self.exec.schema.clone();
+        let left_count = self.exec.left.schema().fields.len();
+        let fields = schema
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(c, f)| {
+                let array = ArrowArrayFactory::create(f.data_type.to_datatype(), rows.len());
+                let mut vector = ArrowVectorBuilder::new(array);
+                let left_column = left.filter(|_| c < left_count).map(|b| b.field(c));
+                for (l, r) in rows {
+                    let value = if c < left_count {
+                        left_column
+                            .as_ref()
+                            .zip(*l)
+                            .and_then(|(v, i)| v.get_value(i))
+                    } else {
+                        r.and_then(|(b, i)| {
+                            self.right_batches[b].fields[c - left_count].get_value(i)
+                        })
+                    };
+                    vector.append(value);
+                }
+                vector.build()
+            })
+            .collect();
+        RecordBatch { schema, fields }
+    }
+}
+
+impl Iterator for HashJoinIterator<'_> {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(batch) = self.left.next() {
+            return Some(self.probe(&batch));
+        }
+        if self.finished || !matches!(self.exec.join_type, JoinType::Right | JoinType::Full) {
+            return None;
+        }
+        self.finished = true;
+        let rows = self.unmatched_right();
+        if rows.is_empty() {
+            None
+        } else {
+            Some(self.build(None, &rows))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::join::JoinType;
+    use std::collections::HashMap;
+
+    fn join(join_type: JoinType) -> String {
+        let ctx = ExecutionContext::new(HashMap::default());
+        let employee = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap();
+        let state = ctx
+            .csv("testdata/state.csv", CsvOptions::default())
+            .unwrap();
+        let df = employee.join(state, join_type, vec![("state", "state")]);
+
+        ctx.execute(df, false)
+            .unwrap()
+            .map(|batch| batch.to_csv().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_inner_and_outer_joins() {
+        assert_eq!(
+            "1,Bill,Hopkins,CA,Manager,12000,CA,California\n\
+            2,Gregg,Langford,CO,Driver,10000,CO,Colorado\n\
+            3,John,Travis,CO,Manager, Software,11500,CO,Colorado\n",
+            join(JoinType::Inner)
+        );
+        assert!(join(JoinType::Left).ends_with("4,Von,Mill,,Defensive End,11500,,\n"));
+        assert!(join(JoinType::Right).ends_with(",,,,,,TX,Texas\n"));
+        assert_eq!(5, join(JoinType::Full).lines().count());
+    }
+
+    #[test]
+    fn test_semi_and_anti_joins() {
+        assert_eq!(3, join(JoinType::Semi).lines().count());
+        assert_eq!("4,Von,Mill,,Defensive End,11500\n", join(JoinType::Anti));
+    }
+}
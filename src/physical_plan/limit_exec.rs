@@ -0,0 +1,143 @@
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::Schema;
+use crate::physical_plan::PhysicalPlan;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Skips the first `offset` rows of the input and returns at most `limit` rows afterwards.
+/// Batches are pulled lazily from the input, so the input is not read any further once
+/// the limit is reached.
+pub struct LimitExec {
+    pub input: Arc<dyn PhysicalPlan>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl Display for LimitExec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LimitExec: limit={:?}, offset={}",
+            self.limit, self.offset
+        )
+    }
+}
+
+impl PhysicalPlan for LimitExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        Box::new(LimitIterator {
+            input: self.input.execute(),
+            skip: self.offset,
+            remaining: self.limit,
+        })
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalPlan>> {
+        vec![self.input.clone()]
+    }
+}
+
+struct LimitIterator<'a> {
+    input: Box<dyn Iterator<Item = RecordBatch> + 'a>,
+    skip: usize,
+    remaining: Option<usize>,
+}
+
+impl Iterator for LimitIterator<'_> {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining != Some(0) {
+            let batch = self.input.next()?;
+            let row_count = batch.row_count();
+
+            if self.skip >= row_count {
+                self.skip -= row_count;
+                continue;
+            }
+
+            let start = self.skip;
+            let length = match self.remaining {
+                Some(remaining) => remaining.min(row_count - start),
+                None => row_count - start,
+            };
+            self.skip = 0;
+            self.remaining = self.remaining.map(|remaining| remaining - length);
+
+            if start == 0 && length == row_count {
+                return Some(batch);
+            }
+            return Some(batch.slice(start, length));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::data_source::Source;
+    use crate::datatypes::record_batch::RecordBatch;
+    use crate::physical_plan::limit_exec::LimitExec;
+    use crate::physical_plan::scan_exec::ScanExec;
+    use crate::physical_plan::PhysicalPlan;
+    use std::sync::Arc;
+
+    fn limit_exec(batch_size: usize, offset: usize, limit: Option<usize>) -> LimitExec {
+        let scan = ScanExec {
+            ds: Source::from_csv(
+                "testdata/employee.csv",
+                None,
+                CsvOptions::default(),
+                batch_size,
+            )
+            .unwrap(),
+            projection: vec!["id".to_string()],
+            filters: vec![],
+            limit: None,
+        };
+        LimitExec {
+            input: Arc::new(scan),
+            offset,
+            limit,
+        }
+    }
+
+    fn ids(batches: Vec<RecordBatch>) -> String {
+        batches.iter().map(|b| b.to_csv().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_limit_slices_last_batch() {
+        let batches: Vec<RecordBatch> = limit_exec(3, 0, Some(2)).execute().collect();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!("1\n2\n", ids(batches));
+    }
+
+    #[test]
+    fn test_limit_with_offset_across_batches() {
+        let batches: Vec<RecordBatch> = limit_exec(1, 1, Some(2)).execute().collect();
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!("2\n3\n", ids(batches));
+    }
+
+    #[test]
+    fn test_offset_without_limit() {
+        let batches: Vec<RecordBatch> = limit_exec(3, 2, None).execute().collect();
+
+        assert_eq!("3\n4\n", ids(batches));
+    }
+
+    #[test]
+    fn test_offset_beyond_input() {
+        let batches: Vec<RecordBatch> = limit_exec(3, 10, Some(1)).execute().collect();
+
+        assert!(batches.is_empty());
+    }
+}
@@ -4,9 +4,12 @@ use std::sync::Arc;
 
 pub mod expressions;
 pub mod hash_aggregate_exec;
+pub mod hash_join_exec;
+pub mod limit_exec;
 pub mod projection_exec;
 pub mod scan_exec;
 pub mod selection_exec;
+pub mod sort_exec;
 
 /// A physical plan represents an executable piece of code that will produce data.
 pub trait PhysicalPlan: ToString {
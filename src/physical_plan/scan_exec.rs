@@ -1,14 +1,17 @@
-use crate::data_source::{DataSource, Source};
+use crate::data_source::{DataSource, LimitHint};
 use crate::datatypes::record_batch::RecordBatch;
 use crate::datatypes::schema::Schema;
 use crate::logical_plan::data_frame::DataFrame;
+use crate::logical_plan::expressions::Expr;
 use crate::physical_plan::PhysicalPlan;
 use std::fmt::Display;
 use std::sync::Arc;
 
 pub struct ScanExec {
-    pub ds: Arc<Source>,
+    pub ds: Arc<dyn DataSource>,
     pub projection: Vec<String>,
+    pub filters: Vec<Arc<Expr>>,
+    pub limit: Option<LimitHint>,
 }
 
 impl Display for ScanExec {
@@ -18,12 +21,24 @@ impl Display for ScanExec {
             "ScanExec: schema={}, projection={:?}",
             self.ds.schema(),
             self.projection
-        )
+        )?;
+        if !self.filters.is_empty() {
+            let filters: Vec<String> = self.filters.iter().map(|e| e.to_string()).collect();
+            write!(f, ", filters=[{}]", filters.join(", "))?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, ", limit={}", limit)?;
+        }
+        Ok(())
     }
 }
 
 impl PhysicalPlan for ScanExec {
     fn schema(&self) -> Arc<Schema> {
+        // an empty projection reads all columns, like in the logical Scan
+        if self.projection.is_empty() {
+            return self.ds.schema();
+        }
         Arc::new(
             self.ds
                 .schema()
@@ -33,8 +48,11 @@ impl PhysicalPlan for ScanExec {
     }
 
     fn execute(&self) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
-        self.ds
-            .scan(self.projection.iter().map(String::as_str).collect()) // todo fix mismatch of String and str and remove conversions
+        let projection = self.projection.iter().map(String::as_str).collect(); // todo fix mismatch of String and str and remove conversions
+        match self.limit {
+            Some(limit) => self.ds.scan_with_limit(projection, &self.filters, limit),
+            None => self.ds.scan_with_filters(projection, &self.filters),
+        }
     }
 
     fn children(&self) -> Vec<Arc<dyn PhysicalPlan>> {
@@ -47,7 +47,10 @@ impl PhysicalPlan for SelectionExec {
         vec![self.input.clone()]
     }
 }
-fn filter(v: Arc<dyn ColumnVector>, selection: Arc<dyn ColumnVector>) -> Arc<dyn ColumnVector> {
+pub(crate) fn filter(
+    v: Arc<dyn ColumnVector>,
+    selection: Arc<dyn ColumnVector>,
+) -> Arc<dyn ColumnVector> {
     let array = ArrowArrayFactory::create(v.get_type().to_datatype().clone(), selection.size());
     let mut filtered_vector = ArrowVectorBuilder::new(array);
 
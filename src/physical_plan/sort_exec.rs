@@ -0,0 +1,202 @@
+use crate::datatypes::arrow_field_vector::ArrowArrayFactory;
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::arrow_vector_builder::ArrowVectorBuilder;
+use crate::datatypes::column_vector::ColumnVector;
+use crate::datatypes::record_batch::RecordBatch;
+use crate::datatypes::schema::Schema;
+use crate::physical_plan::expressions::Expression;
+use crate::physical_plan::PhysicalPlan;
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::Deref;
+use std::sync::Arc;
+
+pub struct PhysicalSortExpr {
+    pub expr: Arc<dyn Expression>,
+    pub asc: bool,
+    pub nulls_first: bool,
+}
+
+impl Display for PhysicalSortExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.expr.to_string(),
+            if self.asc { "ASC" } else { "DESC" },
+            if self.nulls_first {
+                "NULLS FIRST"
+            } else {
+                "NULLS LAST"
+            }
+        )
+    }
+}
+
+/// Sorts all rows of the input, which requires to read all input batches before
+/// the single sorted batch is returned
+pub struct SortExec {
+    pub input: Arc<dyn PhysicalPlan>,
+    pub sort_expr: Vec<PhysicalSortExpr>,
+}
+
+impl Display for SortExec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SortExec: {}",
+            self.sort_expr
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl PhysicalPlan for SortExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        let batches: Vec<RecordBatch> = self.input.execute().collect();
+        let Some(first) = batches.first() else {
+            return Box::new(std::iter::empty());
+        };
+        let schema = first.schema.clone();
+
+        let keys: Vec<Vec<Arc<dyn ColumnVector>>> = batches
+            .iter()
+            .map(|batch| {
+                self.sort_expr
+                    .iter()
+                    .map(|s| s.expr.evaluate(batch))
+                    .collect()
+            })
+            .collect();
+
+        let mut rows: Vec<(usize, usize)> = batches
+            .iter()
+            .enumerate()
+            .flat_map(|(b, batch)| (0..batch.row_count()).map(move |r| (b, r)))
+            .collect();
+        // stable, so rows with equal keys keep the input order
+        rows.sort_by(|&(lb, lr), &(rb, rr)| {
+            self.sort_expr
+                .iter()
+                .enumerate()
+                .map(|(k, s)| {
+                    let l = &keys[lb][k];
+                    let r = &keys[rb][k];
+                    compare(s, l.get_value(lr), r.get_value(rr), &l.get_type())
+                })
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let fields = schema
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(c, f)| {
+                let array = ArrowArrayFactory::create(f.data_type.to_datatype(), rows.len());
+                let mut vector = ArrowVectorBuilder::new(array);
+                for &(b, r) in &rows {
+                    vector.append(batches[b].fields[c].get_value(r));
+                }
+                vector.build()
+            })
+            .collect();
+
+        Box::new(std::iter::once(RecordBatch { schema, fields }))
+    }
+
+    fn children(&self) -> Vec<Arc<dyn PhysicalPlan>> {
+        vec![self.input.clone()]
+    }
+}
+
+fn compare(
+    sort_expr: &PhysicalSortExpr,
+    l: Option<Arc<dyn Any>>,
+    r: Option<Arc<dyn Any>>,
+    arrow_type: &ArrowType,
+) -> Ordering {
+    match (l, r) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) if sort_expr.nulls_first => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) if sort_expr.nulls_first => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(l), Some(r)) => {
+            let ordering = compare_values(l.deref(), r.deref(), arrow_type);
+            if sort_expr.asc {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        }
+    }
+}
+
+fn compare_values(l: &dyn Any, r: &dyn Any, arrow_type: &ArrowType) -> Ordering {
+    fn cmp<T: Ord + 'static>(l: &dyn Any, r: &dyn Any) -> Ordering {
+        l.downcast_ref::<T>().cmp(&r.downcast_ref::<T>())
+    }
+    match arrow_type {
+        ArrowType::BooleanType => cmp::<bool>(l, r),
+        ArrowType::Int8Type => cmp::<i8>(l, r),
+        ArrowType::Int16Type => cmp::<i16>(l, r),
+        ArrowType::Int32Type => cmp::<i32>(l, r),
+        ArrowType::Int64Type => cmp::<i64>(l, r),
+        ArrowType::UInt8Type => cmp::<u8>(l, r),
+        ArrowType::UInt16Type => cmp::<u16>(l, r),
+        ArrowType::UInt32Type => cmp::<u32>(l, r),
+        ArrowType::UInt64Type => cmp::<u64>(l, r),
+        ArrowType::FloatType => l
+            .downcast_ref::<f32>()
+            .zip(r.downcast_ref::<f32>())
+            .map_or(Ordering::Equal, |(l, r)| l.total_cmp(r)),
+        ArrowType::DoubleType => l
+            .downcast_ref::<f64>()
+            .zip(r.downcast_ref::<f64>())
+            .map_or(Ordering::Equal, |(l, r)| l.total_cmp(r)),
+        ArrowType::StringType => cmp::<String>(l, r),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::datatypes::arrow_types::ArrowType;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::expressions::{cast, col};
+    use crate::logical_plan::sort::{asc, desc};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sort_across_batches() {
+        let mut settings = HashMap::new();
+        settings.insert("csv.batch_size".to_string(), "1".to_string());
+        let ctx = ExecutionContext::new(settings);
+
+        let df = ctx
+            .csv("testdata/employee.csv", CsvOptions::default())
+            .unwrap()
+            .sort(vec![
+                asc(col("state")).with_nulls_first(true),
+                desc(cast(col("salary"), ArrowType::Int64Type)),
+            ])
+            .project(vec![col("first_name")]);
+
+        let batches: Vec<String> = ctx
+            .execute(df, false)
+            .unwrap()
+            .map(|b| b.to_csv().unwrap())
+            .collect();
+
+        assert_eq!(vec!["Von\nBill\nJohn\nGregg\n"], batches);
+    }
+}
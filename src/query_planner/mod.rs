@@ -4,33 +4,46 @@ use crate::logical_plan::expressions::aggr_expr::AggrExpr;
 use crate::logical_plan::expressions::binary_expr::{Base, BinaryExpr};
 use crate::logical_plan::expressions::literal_expr::LiteralExpr;
 use crate::logical_plan::expressions::math_expr::MathExpr;
-use crate::logical_plan::expressions::Expr;
+use crate::logical_plan::expressions::unary_expr::UnaryExpr;
+use crate::logical_plan::expressions::{col, Expr};
+use crate::logical_plan::join::Join;
+use crate::logical_plan::limit::Limit;
 use crate::logical_plan::logical_expr::LogicalExpr;
 use crate::logical_plan::projection::Projection;
 use crate::logical_plan::scan::Scan;
 use crate::logical_plan::selection::Selection;
+use crate::logical_plan::sort::Sort;
 use crate::logical_plan::LogicalPlan;
 use crate::physical_plan::expressions::aggregate_expression::AggregateExpression;
+use crate::physical_plan::expressions::avg_expression::AvgExpression;
 use crate::physical_plan::expressions::boolean_expression::{
     AndExpression, EqExpression, GtEqExpression, GtExpression, LtEqExpression, LtExpression,
     NeqExpression, OrExpression,
 };
 use crate::physical_plan::expressions::cast_expression::CastExpression;
 use crate::physical_plan::expressions::column_expression::ColumnExpression;
+use crate::physical_plan::expressions::count_distinct_expression::CountDistinctExpression;
+use crate::physical_plan::expressions::count_expression::CountExpression;
 use crate::physical_plan::expressions::math_expression::{
     AddExpression, DivideExpression, ModulusExpression, MultiplyExpression, SubtractExpression,
 };
 use crate::physical_plan::expressions::max_expression::MaxExpression;
 use crate::physical_plan::expressions::min_expression::MinExpression;
 use crate::physical_plan::expressions::sum_expression::SumExpression;
+use crate::physical_plan::expressions::unary_expression::{
+    IsNotNullExpression, IsNullExpression, NegateExpression, NotExpression,
+};
 use crate::physical_plan::expressions::{
-    Expression, LiteralDoubleExpression, LiteralFloatExpression, LiteralLongExpression,
-    LiteralStringExpression,
+    Expression, LiteralBooleanExpression, LiteralDoubleExpression, LiteralFloatExpression,
+    LiteralLongExpression, LiteralStringExpression,
 };
 use crate::physical_plan::hash_aggregate_exec::HashAggregateExec;
+use crate::physical_plan::hash_join_exec::HashJoinExec;
+use crate::physical_plan::limit_exec::LimitExec;
 use crate::physical_plan::projection_exec::ProjectionExec;
 use crate::physical_plan::scan_exec::ScanExec;
 use crate::physical_plan::selection_exec::SelectionExec;
+use crate::physical_plan::sort_exec::{PhysicalSortExpr, SortExec};
 use crate::physical_plan::PhysicalPlan;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -43,6 +56,8 @@ impl QueryPlanner {
             return Arc::new(ScanExec {
                 ds: scan.datasource.clone(),
                 projection: scan.projection.clone(),
+                filters: scan.filters.clone(),
+                limit: scan.limit,
             });
         }
         if let Some(selection) = plan.as_any().downcast_ref::<Selection>() {
@@ -76,6 +91,49 @@ impl QueryPlanner {
                 expr: projection_expr,
             });
         }
+        if let Some(limit) = plan.as_any().downcast_ref::<Limit>() {
+            let input = QueryPlanner::create_physical_plan(limit.input.deref());
+            return Arc::new(LimitExec {
+                input,
+                offset: limit.offset,
+                limit: limit.limit,
+            });
+        }
+        if let Some(sort) = plan.as_any().downcast_ref::<Sort>() {
+            let input = QueryPlanner::create_physical_plan(sort.input.deref());
+            let sort_expr = sort
+                .sort_expr
+                .iter()
+                .map(|s| PhysicalSortExpr {
+                    expr: Self::create_physical_expr(s.expr.clone(), sort.input.deref()),
+                    asc: s.asc,
+                    nulls_first: s.nulls_first,
+                })
+                .collect();
+            return Arc::new(SortExec { input, sort_expr });
+        }
+        if let Some(join) = plan.as_any().downcast_ref::<Join>() {
+            let left = QueryPlanner::create_physical_plan(join.left.deref());
+            let right = QueryPlanner::create_physical_plan(join.right.deref());
+            let (left_keys, right_keys) = join
+                .on
+                .iter()
+                .map(|(l, r)| {
+                    (
+                        Self::create_physical_expr(col(l), join.left.deref()),
+                        Self::create_physical_expr(col(r), join.right.deref()),
+                    )
+                })
+                .unzip();
+            return Arc::new(HashJoinExec {
+                left,
+                right,
+                join_type: join.join_type,
+                left_keys,
+                right_keys,
+                schema: join.schema(),
+            });
+        }
         if let Some(aggregate) = plan.as_any().downcast_ref::<Aggregate>() {
             let input = QueryPlanner::create_physical_plan(aggregate.input.deref());
             let group_expr: Vec<Arc<dyn Expression>> = aggregate
@@ -111,6 +169,31 @@ impl QueryPlanner {
                     })
                         as Arc<dyn AggregateExpression>,
 
+                    Expr::Aggr(AggrExpr::Avg(avg)) => Arc::new(AvgExpression {
+                        expr: Self::create_physical_expr(
+                            avg.base.expr.clone(),
+                            aggregate.input.deref(),
+                        ),
+                    })
+                        as Arc<dyn AggregateExpression>,
+
+                    Expr::Aggr(AggrExpr::Count(count)) => Arc::new(CountExpression {
+                        expr: Self::create_physical_expr(
+                            count.base.expr.clone(),
+                            aggregate.input.deref(),
+                        ),
+                    })
+                        as Arc<dyn AggregateExpression>,
+
+                    Expr::Aggr(AggrExpr::CountDistinct(count_d)) => {
+                        Arc::new(CountDistinctExpression {
+                            expr: Self::create_physical_expr(
+                                count_d.base.expr.clone(),
+                                aggregate.input.deref(),
+                            ),
+                        }) as Arc<dyn AggregateExpression>
+                    }
+
                     _ => panic!("NOT SUPPORTED AGGREGATE"),
                 })
                 .collect();
@@ -145,6 +228,7 @@ impl QueryPlanner {
                 LiteralExpr::LiteralLong(l) => Arc::new(LiteralLongExpression { value: l.i }),
                 LiteralExpr::LiteralFloat(f) => Arc::new(LiteralFloatExpression { value: f.i }),
                 LiteralExpr::LiteralDouble(d) => Arc::new(LiteralDoubleExpression { value: d.i }),
+                LiteralExpr::LiteralBoolean(b) => Arc::new(LiteralBooleanExpression { value: b.b }),
             },
             Expr::Cast(cast) => {
                 let expr = Self::create_physical_expr(cast.expr.clone(), input);
@@ -178,6 +262,15 @@ impl QueryPlanner {
                     MathExpr::Modulus(_) => Arc::new(ModulusExpression { l, r }),
                 }
             }
+            Expr::Unary(unary) => {
+                let expr = Self::create_physical_expr(unary.get_expr(), input);
+                match unary {
+                    UnaryExpr::Not(_) => Arc::new(NotExpression { expr }),
+                    UnaryExpr::Negate(_) => Arc::new(NegateExpression { expr }),
+                    UnaryExpr::IsNull(_) => Arc::new(IsNullExpression { expr }),
+                    UnaryExpr::IsNotNull(_) => Arc::new(IsNotNullExpression { expr }),
+                }
+            }
             Expr::Alias(alias) => {
                 // note that there is no physical expression for an alias since the alias
                 // only affects the name using in the planning phase and not how the aliased
@@ -211,10 +304,12 @@ mod test {
                 Arc::from(Field {
                     name: "passenger_count".to_string(),
                     data_type: ArrowType::UInt32Type,
+                    nullable: true,
                 }),
                 Arc::from(Field {
                     name: "max_fare".to_string(),
                     data_type: ArrowType::DoubleType,
+                    nullable: true,
                 }),
             ],
         };
@@ -1,22 +1,946 @@
-use crate::logical_plan::data_frame::DataFrame;
-use sqlparser::ast::TableFactor;
+use crate::datatypes::arrow_types::ArrowType;
+use crate::datatypes::schema::{Field, Schema};
+use crate::logical_plan::data_frame::{DataFrame, DataFrameImpl};
+use crate::logical_plan::expressions::aggr_expr::{avg, count, count_distinct, max, min, sum};
+use crate::logical_plan::expressions::binary_expr::{and, or, BooleanBinaryExprExt};
+use crate::logical_plan::expressions::literal_expr::{lit_bool, lit_double, lit_long, lit_str};
+use crate::logical_plan::expressions::math_expr::MathExprExt;
+use crate::logical_plan::expressions::unary_expr::{is_not_null, is_null, negate, not};
+use crate::logical_plan::expressions::{alias, cast, col, col_index, Expr};
+use crate::logical_plan::join::{Join, JoinType};
+use crate::logical_plan::logical_expr::LogicalExpr;
+use crate::logical_plan::sort::SortExpr;
+use crate::optimizer::transform_down;
+use anyhow::{anyhow, bail};
+use sqlparser::ast::{
+    BinaryOperator, DataType, Distinct, DuplicateTreatment, Expr as SqlExpr, Function, FunctionArg,
+    FunctionArgExpr, FunctionArguments, GroupByExpr, Ident, JoinConstraint, JoinOperator,
+    OrderByExpr, Query, Select, SelectItem, SetExpr, TableFactor, UnaryOperator, Value,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Translates a parsed SQL query into a DataFrame on top of the registered tables
 pub struct SqlPlanner;
 
 impl SqlPlanner {
+    /// Create a DataFrame for the given SQL query
     pub fn create_data_frame(
-        select: sqlparser::ast::Select,
-        tables: HashMap<String, Arc<dyn DataFrame>>,
-    ) {
-        if let Some(table_with_joins) = select.from.first() {
-            match &table_with_joins.relation {
-                TableFactor::Table { name, .. } => {
-                    let table = tables.get(&name.to_string()).unwrap();
+        query: &Query,
+        tables: &HashMap<String, Arc<dyn DataFrame>>,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let mut df = match query.body.as_ref() {
+            SetExpr::Select(select) => Self::plan_select(select, &query.order_by, tables)?,
+            other => bail!("query not supported: {}", other),
+        };
+        if let Some(offset) = &query.offset {
+            df = df.offset(Self::create_row_count(&offset.value)?);
+        }
+        if let Some(limit) = &query.limit {
+            df = df.limit(Self::create_row_count(limit)?);
+        }
+        Ok(df)
+    }
+
+    fn plan_select(
+        select: &Select,
+        order_by: &[OrderByExpr],
+        tables: &HashMap<String, Arc<dyn DataFrame>>,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let table = Self::plan_from(select, tables)?;
+        let schema = table.clone().schema();
+
+        let projection_expr = select
+            .projection
+            .iter()
+            .map(|item| Self::create_select_expr(item, &schema))
+            .collect::<anyhow::Result<Vec<Vec<Arc<Expr>>>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let group_expr = match &select.group_by {
+            GroupByExpr::Expressions(expr) => expr
+                .iter()
+                .map(|e| Self::create_logical_expr(e, &schema))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            GroupByExpr::All => bail!("GROUP BY ALL is not supported"),
+        };
+
+        let selection_expr = match &select.selection {
+            Some(selection) => Some(Self::create_logical_expr(selection, &schema)?),
+            None => None,
+        };
+        // HAVING may refer to the aliases of the select list besides the columns of the table
+        let having_expr = match &select.having {
+            Some(having) => Some(Self::create_logical_expr(
+                having,
+                &Self::with_aliases(&table, &projection_expr)?,
+            )?),
+            None => None,
+        };
+        let distinct = match &select.distinct {
+            None => false,
+            Some(Distinct::Distinct) => true,
+            Some(Distinct::On(_)) => bail!("DISTINCT ON is not supported"),
+        };
+        Self::check_expr(
+            &table,
+            projection_expr
+                .iter()
+                .chain(group_expr.iter())
+                .chain(selection_expr.iter()),
+        )?;
+
+        // the selection is applied to the table before projecting or aggregating it,
+        // hence it can only reference columns of the table and not aliases of the select list
+        let plan = match selection_expr {
+            Some(selection) => table.filter(selection),
+            None => table,
+        };
+
+        let aggregate_count = projection_expr
+            .iter()
+            .filter(|e| is_aggregate_expr(e))
+            .count();
+
+        if aggregate_count == 0 && group_expr.is_empty() && having_expr.is_none() && !distinct {
+            // the projection keeps the order of the rows, so sorting before it allows to
+            // order by columns which are not selected
+            let sort_expr = order_by
+                .iter()
+                .map(|o| {
+                    let expr = match Self::select_position(&o.expr, &projection_expr, &schema)? {
+                        Some(position) => strip_alias(projection_expr[position].clone()),
+                        None => Self::create_logical_expr(&o.expr, &schema)?,
+                    };
+                    Ok(Self::create_sort_expr(o, expr))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Self::check_expr(&plan, sort_expr.iter().map(|s| &s.expr))?;
+            let plan = if sort_expr.is_empty() {
+                plan
+            } else {
+                plan.sort(sort_expr)
+            };
+            Ok(plan.project(projection_expr))
+        } else {
+            // DISTINCT without aggregates groups by the select list, otherwise the aggregated
+            // rows are grouped by all their columns
+            let distinct_rows = distinct && (aggregate_count > 0 || !group_expr.is_empty());
+            let group_expr = if distinct && !distinct_rows {
+                projection_expr.iter().cloned().map(strip_alias).collect()
+            } else {
+                group_expr
+            };
+            // the output of an aggregate can only be ordered by the expressions of the select list
+            let sort_expr = order_by
+                .iter()
+                .map(|o| {
+                    match Self::select_position(&o.expr, &projection_expr, &schema)? {
+                        Some(position) => Ok(Self::create_sort_expr(o, col_index(position))),
+                        None => bail!("ORDER BY expression {} must appear in the select list of an aggregate query", o.expr),
+                    }
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let mut plan = Self::plan_aggregate(plan, projection_expr, group_expr, having_expr)?;
+            if distinct_rows {
+                let columns = plan.clone().schema().fields.len();
+                plan = plan.aggregate((0..columns).map(col_index).collect(), vec![]);
+            }
+            if sort_expr.is_empty() {
+                Ok(plan)
+            } else {
+                Ok(plan.sort(sort_expr))
+            }
+        }
+    }
+
+    /// Resolves the type of the expressions against the input, so that operands the
+    /// execution can't evaluate are reported while planning
+    fn check_expr<'a>(
+        input: &Arc<dyn DataFrame>,
+        expr: impl IntoIterator<Item = &'a Arc<Expr>>,
+    ) -> anyhow::Result<()> {
+        let plan = input.clone().logical_plan();
+        expr.into_iter()
+            .try_for_each(|e| e.to_field(plan.clone()).map(|_| ()))
+    }
+
+    /// Schema of the table extended by the aliases of the select list
+    fn with_aliases(
+        table: &Arc<dyn DataFrame>,
+        projection_expr: &[Arc<Expr>],
+    ) -> anyhow::Result<Schema> {
+        let plan = table.clone().logical_plan();
+        let mut fields: Vec<Arc<Field>> = table.clone().schema().fields.clone();
+        for expr in projection_expr {
+            if matches!(expr.as_ref(), Expr::Alias(_)) {
+                fields.push(expr.to_field(plan.clone())?);
+            }
+        }
+        Ok(Schema { fields })
+    }
+
+    /// Position in the select list an ORDER BY expression refers to, either by a 1-based
+    /// position, by an alias or by the same expression
+    fn select_position(
+        expr: &SqlExpr,
+        projection_expr: &[Arc<Expr>],
+        schema: &Schema,
+    ) -> anyhow::Result<Option<usize>> {
+        if let SqlExpr::Value(Value::Number(n, _)) = expr {
+            return match n.parse::<usize>() {
+                Ok(position) if (1..=projection_expr.len()).contains(&position) => {
+                    Ok(Some(position - 1))
+                }
+                _ => bail!("ORDER BY position {} is not in the select list", n),
+            };
+        }
+        if let SqlExpr::Identifier(ident) = expr {
+            let position = projection_expr
+                .iter()
+                .position(|e| matches!(e.as_ref(), Expr::Alias(a) if a.alias == ident.value));
+            if position.is_some() {
+                return Ok(position);
+            }
+        }
+        let name = Self::create_logical_expr(expr, schema)?.to_string();
+        Ok(projection_expr
+            .iter()
+            .position(|e| strip_alias(e.clone()).to_string() == name))
+    }
+
+    /// ASC is the default order, nulls are sorted as if they were larger than any value
+    fn create_sort_expr(order_by: &OrderByExpr, expr: Arc<Expr>) -> SortExpr {
+        let asc = order_by.asc.unwrap_or(true);
+        SortExpr {
+            expr,
+            asc,
+            nulls_first: order_by.nulls_first.unwrap_or(!asc),
+        }
+    }
+
+    /// Plans an aggregate followed by a projection which restores the order and the
+    /// aliases of the select list. The projection refers to the output of the aggregate
+    /// by index, since the group expressions come first and the aggregates second.
+    /// HAVING is a selection in between, aggregates which are only used by it are computed
+    /// by the aggregate as well and dropped by the projection.
+    fn plan_aggregate(
+        plan: Arc<dyn DataFrame>,
+        projection_expr: Vec<Arc<Expr>>,
+        group_expr: Vec<Arc<Expr>>,
+        having_expr: Option<Arc<Expr>>,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        let mut aggregate_expr = vec![];
+        let mut output_expr = vec![];
+        let mut aliases = vec![];
+        let mut is_identity = projection_expr.len() >= group_expr.len();
+
+        for (position, expr) in projection_expr.into_iter().enumerate() {
+            let (inner, alias_name) = match expr.as_ref() {
+                Expr::Alias(a) => (a.expr.clone(), Some(a.alias.clone())),
+                _ => (expr.clone(), None),
+            };
+
+            let index = if matches!(inner.as_ref(), Expr::Aggr(_)) {
+                aggregate_expr.push(inner);
+                group_expr.len() + aggregate_expr.len() - 1
+            } else {
+                let name = inner.to_string();
+                group_expr
+                    .iter()
+                    .position(|g| g.to_string() == name)
+                    .ok_or(anyhow!(
+                        "expression {} must appear in the GROUP BY clause or be used in an aggregate function",
+                        name
+                    ))?
+            };
+
+            is_identity &= index == position && alias_name.is_none();
+            output_expr.push(match alias_name {
+                Some(name) => {
+                    aliases.push((name.clone(), index));
+                    alias(col_index(index), name)
+                }
+                None => col_index(index),
+            });
+        }
+
+        let having_expr = having_expr.map(|expr| {
+            let aggregate_expr = RefCell::new(&mut aggregate_expr);
+            transform_down(expr, &|e| {
+                let name = e.to_string();
+                if let Some(index) = group_expr.iter().position(|g| g.to_string() == name) {
+                    return Some(col_index(index));
+                }
+                match e.as_ref() {
+                    Expr::Aggr(_) => {
+                        let mut aggregate_expr = aggregate_expr.borrow_mut();
+                        let index = match aggregate_expr.iter().position(|a| a.to_string() == name)
+                        {
+                            Some(index) => index,
+                            None => {
+                                aggregate_expr.push(e.clone());
+                                aggregate_expr.len() - 1
+                            }
+                        };
+                        Some(col_index(group_expr.len() + index))
+                    }
+                    Expr::Column(c) => aliases
+                        .iter()
+                        .find(|(alias, _)| *alias == c.name)
+                        .map(|(_, index)| col_index(*index)),
+                    _ => None,
+                }
+            })
+        });
+        is_identity &= output_expr.len() == group_expr.len() + aggregate_expr.len();
+
+        let mut aggregate = plan.aggregate(group_expr, aggregate_expr);
+        if let Some(expr) = having_expr {
+            Self::check_expr(&aggregate, std::iter::once(&expr))?;
+            aggregate = aggregate.filter(expr);
+        }
+        if is_identity {
+            Ok(aggregate)
+        } else {
+            Ok(aggregate.project(output_expr))
+        }
+    }
+
+    /// Plans the FROM clause, joining the tables from left to right
+    fn plan_from(
+        select: &Select,
+        tables: &HashMap<String, Arc<dyn DataFrame>>,
+    ) -> anyhow::Result<Arc<dyn DataFrame>> {
+        if select.from.len() != 1 {
+            bail!("exactly one table must be selected");
+        }
+        let from = &select.from[0];
+        let (mut plan, qualifier) = Self::get_table(&from.relation, tables)?;
+
+        for join in &from.joins {
+            let (right, right_qualifier) = Self::get_table(&join.relation, tables)?;
+            let (join_type, constraint) = match &join.join_operator {
+                JoinOperator::Inner(c) => (JoinType::Inner, c),
+                JoinOperator::LeftOuter(c) => (JoinType::Left, c),
+                JoinOperator::RightOuter(c) => (JoinType::Right, c),
+                JoinOperator::FullOuter(c) => (JoinType::Full, c),
+                JoinOperator::LeftSemi(c) => (JoinType::Semi, c),
+                JoinOperator::LeftAnti(c) => (JoinType::Anti, c),
+                _ => bail!("join not supported: {}", join),
+            };
+            let on = Self::create_join_keys(
+                constraint,
+                &plan.clone().schema(),
+                &right.clone().schema(),
+                &right_qualifier,
+            )?;
+            // duplicate columns of the left input are qualified with the first table
+            plan = Arc::new(DataFrameImpl {
+                plan: Join::new(
+                    plan.logical_plan(),
+                    right.logical_plan(),
+                    join_type,
+                    on,
+                    qualifier.clone(),
+                    right_qualifier,
+                ),
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Returns the registered table together with its alias or name as qualifier
+    fn get_table(
+        relation: &TableFactor,
+        tables: &HashMap<String, Arc<dyn DataFrame>>,
+    ) -> anyhow::Result<(Arc<dyn DataFrame>, String)> {
+        match relation {
+            TableFactor::Table { name, alias, .. } => {
+                let table_name = name.to_string();
+                let table = tables
+                    .get(&table_name)
+                    .cloned()
+                    .ok_or(anyhow!("no table registered with name {}", table_name))?;
+                let qualifier = alias
+                    .as_ref()
+                    .map(|a| a.name.value.clone())
+                    .unwrap_or(table_name);
+                Ok((table, qualifier))
+            }
+            other => bail!("relation not supported: {}", other),
+        }
+    }
+
+    /// Translates the join condition into pairs of left and right column names, the condition
+    /// must be a conjunction of equalities between a left and a right column
+    fn create_join_keys(
+        constraint: &JoinConstraint,
+        left: &Schema,
+        right: &Schema,
+        right_qualifier: &str,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        match constraint {
+            JoinConstraint::On(expr) => {
+                let mut equalities = vec![];
+                split_conjunction(expr, &mut equalities);
+                equalities
+                    .into_iter()
+                    .map(|e| match e {
+                        SqlExpr::BinaryOp {
+                            left: l,
+                            op: BinaryOperator::Eq,
+                            right: r,
+                        } => {
+                            let l = Self::resolve_join_key(l, left, right, right_qualifier)?;
+                            let r = Self::resolve_join_key(r, left, right, right_qualifier)?;
+                            match (l, r) {
+                                ((false, l), (true, r)) | ((true, r), (false, l)) => Ok((l, r)),
+                                _ => bail!(
+                                    "join condition must compare a left and a right column: {}",
+                                    e
+                                ),
+                            }
+                        }
+                        other => bail!("join condition must be an equality: {}", other),
+                    })
+                    .collect()
+            }
+            JoinConstraint::Using(idents) => Ok(idents
+                .iter()
+                .map(|i| (i.value.clone(), i.value.clone()))
+                .collect()),
+            _ => bail!("join requires an ON or USING condition"),
+        }
+    }
+
+    /// Resolves a column of a join condition to the side it belongs to (true for right)
+    /// and its name in that input
+    fn resolve_join_key(
+        expr: &SqlExpr,
+        left: &Schema,
+        right: &Schema,
+        right_qualifier: &str,
+    ) -> anyhow::Result<(bool, String)> {
+        let has = |schema: &Schema, name: &str| schema.fields.iter().any(|f| f.name == name);
+        match expr {
+            SqlExpr::CompoundIdentifier(idents) => match idents.as_slice() {
+                [q, n] if q.value == right_qualifier && has(right, &n.value) => {
+                    Ok((true, n.value.clone()))
+                }
+                [_, _] => match resolve_column(idents, left)? {
+                    Some(name) => Ok((false, name)),
+                    None => bail!("column not found: {}", expr),
+                },
+                _ => bail!("column not supported in join condition: {}", expr),
+            },
+            SqlExpr::Identifier(ident) => {
+                match (has(left, &ident.value), has(right, &ident.value)) {
+                    (true, false) => Ok((false, ident.value.clone())),
+                    (false, true) => Ok((true, ident.value.clone())),
+                    (true, true) => bail!("column reference {} is ambiguous", ident),
+                    (false, false) => bail!("column not found: {}", ident),
+                }
+            }
+            other => bail!("join condition must compare columns: {}", other),
+        }
+    }
+
+    fn create_select_expr(item: &SelectItem, schema: &Schema) -> anyhow::Result<Vec<Arc<Expr>>> {
+        match item {
+            SelectItem::UnnamedExpr(expr) => Ok(vec![Self::create_logical_expr(expr, schema)?]),
+            SelectItem::ExprWithAlias { expr, alias: name } => Ok(vec![alias(
+                Self::create_logical_expr(expr, schema)?,
+                name.value.clone(),
+            )]),
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(_, _) => {
+                Ok(schema.fields.iter().map(|f| col(f.name.clone())).collect())
+            }
+        }
+    }
+
+    /// Translates a SQL expression into a logical expression, resolving the column references
+    /// against the schema of the input
+    pub fn create_logical_expr(expr: &SqlExpr, schema: &Schema) -> anyhow::Result<Arc<Expr>> {
+        match expr {
+            SqlExpr::Identifier(ident) => {
+                match resolve_column(std::slice::from_ref(ident), schema)? {
+                    Some(name) => Ok(col(name)),
+                    None => bail!("unknown column: {}", ident),
+                }
+            }
+            SqlExpr::CompoundIdentifier(idents) => match resolve_column(idents, schema)? {
+                Some(name) => Ok(col(name)),
+                None => bail!("unknown column: {}", expr),
+            },
+            SqlExpr::Value(value) => Self::create_literal(value, false),
+            SqlExpr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => match expr.as_ref() {
+                SqlExpr::Value(value) => Self::create_literal(value, true),
+                other => Ok(negate(Self::create_logical_expr(other, schema)?)),
+            },
+            SqlExpr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => Ok(not(Self::create_logical_expr(expr, schema)?)),
+            SqlExpr::IsNull(expr) => Ok(is_null(Self::create_logical_expr(expr, schema)?)),
+            SqlExpr::IsNotNull(expr) => Ok(is_not_null(Self::create_logical_expr(expr, schema)?)),
+            SqlExpr::Nested(expr) => Self::create_logical_expr(expr, schema),
+            SqlExpr::Cast {
+                expr, data_type, ..
+            } => Ok(cast(
+                Self::create_logical_expr(expr, schema)?,
+                Self::create_arrow_type(data_type)?,
+            )),
+            SqlExpr::BinaryOp { left, op, right } => {
+                let l = Self::create_logical_expr(left, schema)?;
+                let r = Self::create_logical_expr(right, schema)?;
+                match op {
+                    BinaryOperator::Eq => Ok(l.eq(r)),
+                    BinaryOperator::NotEq => Ok(l.neq(r)),
+                    BinaryOperator::Gt => Ok(l.gt(r)),
+                    BinaryOperator::GtEq => Ok(l.gteq(r)),
+                    BinaryOperator::Lt => Ok(l.lt(r)),
+                    BinaryOperator::LtEq => Ok(l.lteq(r)),
+                    BinaryOperator::And => Ok(and(l, r)),
+                    BinaryOperator::Or => Ok(or(l, r)),
+                    BinaryOperator::Plus => Ok(l.add(r)),
+                    BinaryOperator::Minus => Ok(l.subtract(r)),
+                    BinaryOperator::Multiply => Ok(l.mult(r)),
+                    BinaryOperator::Divide => Ok(l.div(r)),
+                    BinaryOperator::Modulo => Ok(l.modulus(r)),
+                    other => bail!("binary operator not supported: {}", other),
+                }
+            }
+            SqlExpr::Function(function) => Self::create_aggregate_expr(function, schema),
+            other => bail!("expression not supported: {}", other),
+        }
+    }
+
+    /// Evaluates the row count of a LIMIT or OFFSET clause
+    fn create_row_count(expr: &SqlExpr) -> anyhow::Result<usize> {
+        match expr {
+            SqlExpr::Value(Value::Number(n, _)) => n
+                .parse::<usize>()
+                .map_err(|_| anyhow!("row count must be a non-negative integer: {}", n)),
+            other => bail!("row count must be a literal: {}", other),
+        }
+    }
+
+    fn create_literal(value: &Value, negate: bool) -> anyhow::Result<Arc<Expr>> {
+        match value {
+            Value::Number(n, _) => {
+                let n = if negate { format!("-{n}") } else { n.clone() };
+                if let Ok(i) = n.parse::<i64>() {
+                    Ok(lit_long(i))
+                } else {
+                    Ok(lit_double(n.parse::<f64>()?))
                 }
-                _ => panic!("not supported"),
             }
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) if !negate => {
+                Ok(lit_str(s))
+            }
+            Value::Boolean(b) if !negate => Ok(lit_bool(*b)),
+            other => bail!("literal not supported: {}", other),
+        }
+    }
+
+    fn create_aggregate_expr(function: &Function, schema: &Schema) -> anyhow::Result<Arc<Expr>> {
+        let name = function.name.to_string().to_uppercase();
+        let FunctionArguments::List(arg_list) = &function.args else {
+            bail!("function {} requires an argument list", name)
+        };
+        let [arg] = arg_list.args.as_slice() else {
+            bail!("function {} requires exactly one argument", name)
+        };
+        let input = match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => Self::create_logical_expr(e, schema)?,
+            // COUNT(*) counts the rows, any literal does the job as input
+            FunctionArg::Unnamed(FunctionArgExpr::Wildcard) if name == "COUNT" => lit_long(1),
+            other => bail!("function argument not supported: {}", other),
+        };
+        let distinct = matches!(
+            arg_list.duplicate_treatment,
+            Some(DuplicateTreatment::Distinct)
+        );
+
+        match (name.as_str(), distinct) {
+            ("MIN", _) => Ok(min(input)),
+            ("MAX", _) => Ok(max(input)),
+            ("SUM", false) => Ok(sum(input)),
+            ("AVG", false) => Ok(avg(input)),
+            ("COUNT", false) => Ok(count(input)),
+            ("COUNT", true) => Ok(count_distinct(input)),
+            _ => bail!("function not supported: {}", function),
         }
     }
+
+    fn create_arrow_type(data_type: &DataType) -> anyhow::Result<ArrowType> {
+        match data_type {
+            DataType::Bool | DataType::Boolean => Ok(ArrowType::BooleanType),
+            DataType::TinyInt(_) => Ok(ArrowType::Int8Type),
+            DataType::SmallInt(_) | DataType::Int2(_) => Ok(ArrowType::Int16Type),
+            DataType::Int(_) | DataType::Integer(_) | DataType::Int4(_) => Ok(ArrowType::Int32Type),
+            DataType::BigInt(_) | DataType::Int8(_) | DataType::Int64 => Ok(ArrowType::Int64Type),
+            DataType::UnsignedTinyInt(_) => Ok(ArrowType::UInt8Type),
+            DataType::UnsignedSmallInt(_) => Ok(ArrowType::UInt16Type),
+            DataType::UnsignedInt(_) | DataType::UnsignedInteger(_) => Ok(ArrowType::UInt32Type),
+            DataType::UnsignedBigInt(_) => Ok(ArrowType::UInt64Type),
+            DataType::Float(_) | DataType::Float4 | DataType::Real => Ok(ArrowType::FloatType),
+            DataType::Double | DataType::DoublePrecision | DataType::Float8 | DataType::Float64 => {
+                Ok(ArrowType::DoubleType)
+            }
+            DataType::Varchar(_) | DataType::Text | DataType::String(_) => {
+                Ok(ArrowType::StringType)
+            }
+            other => bail!("data type not supported: {}", other),
+        }
+    }
+}
+
+/// Finds the column referenced by a possibly qualified identifier, qualified columns of a join
+/// can also be referenced by their plain name if that is unique
+fn resolve_column(idents: &[Ident], schema: &Schema) -> anyhow::Result<Option<String>> {
+    let name = idents
+        .iter()
+        .map(|i| i.value.as_str())
+        .collect::<Vec<_>>()
+        .join(".");
+    if schema.fields.iter().any(|f| f.name == name) {
+        return Ok(Some(name));
+    }
+    let Some(last) = idents.last() else {
+        return Ok(None);
+    };
+    if idents.len() > 1 && schema.fields.iter().any(|f| f.name == last.value) {
+        return Ok(Some(last.value.clone()));
+    }
+    let suffix = format!(".{}", last.value);
+    let qualified: Vec<&str> = schema
+        .fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .filter(|n| n.ends_with(&suffix))
+        .collect();
+    match qualified.as_slice() {
+        [] => Ok(None),
+        [name] if idents.len() == 1 => Ok(Some(name.to_string())),
+        _ if idents.len() == 1 => bail!("column reference {} is ambiguous", last),
+        _ => Ok(None),
+    }
+}
+
+fn strip_alias(expr: Arc<Expr>) -> Arc<Expr> {
+    match expr.as_ref() {
+        Expr::Alias(a) => a.expr.clone(),
+        _ => expr,
+    }
+}
+
+fn split_conjunction<'a>(expr: &'a SqlExpr, accum: &mut Vec<&'a SqlExpr>) {
+    match expr {
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjunction(left, accum);
+            split_conjunction(right, accum);
+        }
+        SqlExpr::Nested(e) => split_conjunction(e, accum),
+        _ => accum.push(expr),
+    }
+}
+
+fn is_aggregate_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Aggr(_) => true,
+        Expr::Alias(a) => is_aggregate_expr(&a.expr),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_source::csv_data_source::CsvOptions;
+    use crate::execution::ExecutionContext;
+    use crate::logical_plan::LogicalPlanPrinter;
+    use std::collections::HashMap;
+
+    fn test_context() -> ExecutionContext {
+        let mut ctx = ExecutionContext::new(HashMap::default());
+        ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+            .unwrap();
+        ctx.register_csv("state", "testdata/state.csv", CsvOptions::default())
+            .unwrap();
+        ctx
+    }
+
+    #[test]
+    fn test_plan_projection_with_selection() {
+        let df = test_context()
+            .sql("SELECT id, first_name AS name FROM employee WHERE state = 'CO'")
+            .unwrap();
+
+        assert_eq!(
+            "Projection: id, first_name AS name\n\
+            \tSelection: state = CO\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_wildcard() {
+        let df = test_context().sql("SELECT * FROM employee").unwrap();
+
+        assert_eq!(
+            "Projection: id, first_name, last_name, state, job_title, salary\n\
+            \tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_aggregate() {
+        let df = test_context()
+            .sql("SELECT MAX(CAST(salary AS BIGINT)) AS max_salary, state FROM employee GROUP BY state")
+            .unwrap();
+
+        assert_eq!(
+            "Projection: 1 AS max_salary, 0\n\
+            \tAggregate: group_expr=state, aggregate_expr=MAX(CAST(salary AS Int64Type))\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_aggregate_without_reordering() {
+        let df = test_context()
+            .sql("SELECT state, MIN(salary), COUNT(DISTINCT id) FROM employee GROUP BY state")
+            .unwrap();
+
+        assert_eq!(
+            "Aggregate: group_expr=state, aggregate_expr=MIN(salary), COUNT(DISTINCT id)\n\
+            \tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_limit_and_offset() {
+        let df = test_context()
+            .sql("SELECT id FROM employee LIMIT 2 OFFSET 1")
+            .unwrap();
+
+        assert_eq!(
+            "Limit: 2\n\
+            \tLimit: None; offset=1\n\
+            \t\tProjection: id\n\
+            \t\t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_unary_operators() {
+        let df = test_context()
+            .sql("SELECT -salary FROM employee WHERE NOT state IS NULL")
+            .unwrap();
+
+        assert_eq!(
+            "Projection: -salary\n\
+            \tSelection: NOT state IS NULL\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_join() {
+        let df = test_context()
+            .sql(
+                "SELECT e.first_name, s.name, s.state FROM employee e \
+                LEFT JOIN state s ON s.state = e.state WHERE id > 1",
+            )
+            .unwrap();
+
+        assert_eq!(
+            "Projection: first_name, name, s.state\n\
+            \tSelection: id > 1\n\
+            \t\tJoin: type=Left, on=e.state = s.state\n\
+            \t\t\tScan: testdata/employee.csv; projection=None\n\
+            \t\t\tScan: testdata/state.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_order_by() {
+        let ctx = test_context();
+
+        let df = ctx
+            .sql("SELECT first_name AS name FROM employee ORDER BY name DESC, id NULLS FIRST")
+            .unwrap();
+        assert_eq!(
+            "Projection: first_name AS name\n\
+            \tSort: first_name DESC NULLS FIRST, id ASC NULLS FIRST\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+
+        let df = ctx
+            .sql("SELECT state, COUNT(id) FROM employee GROUP BY state ORDER BY 2 DESC, state")
+            .unwrap();
+        assert_eq!(
+            "Sort: 1 DESC NULLS FIRST, 0 ASC NULLS LAST\n\
+            \tAggregate: group_expr=state, aggregate_expr=COUNT(id)\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_having() {
+        let df = test_context()
+            .sql(
+                "SELECT state, COUNT(id) AS cnt FROM employee GROUP BY state \
+                HAVING cnt > 1 AND MAX(salary) > 10000",
+            )
+            .unwrap();
+
+        // MAX(salary) is only computed for the selection and dropped by the projection
+        assert_eq!(
+            "Projection: 0, 1 AS cnt\n\
+            \tSelection: 1 > 1 AND 2 > 10000\n\
+            \t\tAggregate: group_expr=state, aggregate_expr=COUNT(id), MAX(salary)\n\
+            \t\t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_distinct() {
+        let ctx = test_context();
+
+        let df = ctx
+            .sql("SELECT DISTINCT state, id AS i FROM employee")
+            .unwrap();
+        assert_eq!(
+            "Projection: 0, 1 AS i\n\
+            \tAggregate: group_expr=state, id, aggregate_expr=\n\
+            \t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+
+        let df = ctx
+            .sql("SELECT DISTINCT COUNT(id) FROM employee GROUP BY state")
+            .unwrap();
+        assert_eq!(
+            "Aggregate: group_expr=0, aggregate_expr=\n\
+            \tProjection: 1\n\
+            \t\tAggregate: group_expr=state, aggregate_expr=COUNT(id)\n\
+            \t\t\tScan: testdata/employee.csv; projection=None\n",
+            df.logical_plan().pretty()
+        );
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        let ctx = test_context();
+
+        assert!(ctx.sql("SELECT id FROM unknown").is_err());
+        assert!(ctx.sql("SELECT id, MAX(salary) FROM employee").is_err());
+        assert!(ctx.sql("SELECT id FROM employee GROUP BY state").is_err());
+        assert!(ctx.sql("SELECT UPPER(id) FROM employee").is_err());
+        assert!(ctx.sql("DROP TABLE employee").is_err());
+        assert!(ctx.sql("SELECT id FROM employee LIMIT -1").is_err());
+        assert!(ctx
+            .sql("SELECT state FROM employee JOIN state USING (state)")
+            .is_err());
+        assert!(ctx
+            .sql("SELECT id FROM employee JOIN state ON id > name")
+            .is_err());
+        assert!(ctx.sql("SELECT id FROM employee ORDER BY 2").is_err());
+        assert!(ctx
+            .sql("SELECT state FROM employee GROUP BY state ORDER BY id")
+            .is_err());
+        assert!(ctx.sql("SELECT DISTINCT ON (id) id FROM employee").is_err());
+        assert!(ctx
+            .sql("SELECT state FROM employee GROUP BY state HAVING id > 1")
+            .is_err());
+        assert!(ctx.sql("SELECT id FROM employee HAVING id > 1").is_err());
+    }
+
+    #[test]
+    fn test_plan_type_errors() {
+        let ctx = test_context();
+
+        let error = |sql| ctx.sql(sql).err().unwrap().to_string();
+        assert_eq!(
+            "cannot compare values of different type StringType and Int64Type: state = 1",
+            error("SELECT id FROM employee WHERE state = 1")
+        );
+        assert_eq!(
+            "AND requires boolean operands, got Int64Type and BooleanType: id AND id = 1",
+            error("SELECT id FROM employee WHERE id AND id = 1")
+        );
+        assert_eq!(
+            "operator + is not supported for StringType and StringType: first_name + last_name",
+            error("SELECT first_name + last_name FROM employee")
+        );
+        assert_eq!(
+            "cannot compare values of different type StringType and Int64Type: state > 1",
+            error("SELECT state FROM employee GROUP BY state > 1")
+        );
+        assert_eq!(
+            "operator * is not supported for StringType and Int64Type: state * 2",
+            error("SELECT id FROM employee ORDER BY state * 2")
+        );
+        assert_eq!(
+            "SUM is not supported for StringType: SUM(state)",
+            error("SELECT SUM(state) FROM employee")
+        );
+        assert_eq!(
+            "NOT requires a boolean operand, got Int64Type: NOT id",
+            error("SELECT id FROM employee WHERE NOT id")
+        );
+        assert_eq!(
+            "cannot negate values of type StringType: -state",
+            error("SELECT -state FROM employee")
+        );
+    }
+
+    #[test]
+    fn test_plan_unknown_column() {
+        let ctx = test_context();
+
+        let error = |sql| ctx.sql(sql).err().unwrap().to_string();
+        assert_eq!(
+            "unknown column: nosuch",
+            error("SELECT nosuch FROM employee")
+        );
+        assert_eq!(
+            "unknown column: nosuch",
+            error("SELECT id FROM employee WHERE nosuch = 1")
+        );
+        assert_eq!(
+            "unknown column: employee.nosuch",
+            error("SELECT employee.nosuch FROM employee")
+        );
+        assert_eq!(
+            "unknown column: nosuch",
+            error("SELECT state, MAX(nosuch) FROM employee GROUP BY state")
+        );
+        assert_eq!(
+            "unknown column: nosuch",
+            error("SELECT id FROM employee ORDER BY nosuch")
+        );
+    }
 }
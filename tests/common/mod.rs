@@ -1 +1,109 @@
+use arrow::array::{ArrayRef, Int64Array};
+use rs_query::data_source::{DataSource, TableProvider};
+use rs_query::datatypes::arrow_field_vector::ArrowFieldVector;
+use rs_query::datatypes::arrow_types::ArrowType;
+use rs_query::datatypes::column_vector::ColumnVector;
+use rs_query::datatypes::record_batch::RecordBatch;
+use rs_query::datatypes::schema::{Field, Schema};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
 
+/// Custom data source generating the numbers `0..end` and their squares
+pub struct RangeDataSource {
+    end: i64,
+    batch_size: i64,
+    schema: Arc<Schema>,
+    /// projections of the scans, to check what is pushed down
+    pub scanned: Mutex<Vec<Vec<String>>>,
+    /// columns of the returned batches, to check whether they are released before the next
+    /// batch is read
+    returned: Mutex<Vec<Weak<ArrowFieldVector>>>,
+    /// greatest number of returned batches in use when a batch was read
+    pub max_batches_in_use: Mutex<usize>,
+}
+
+impl RangeDataSource {
+    pub fn new(end: i64) -> Self {
+        let field = |name: &str| {
+            Arc::new(Field {
+                name: name.to_string(),
+                data_type: ArrowType::Int64Type,
+                nullable: false,
+            })
+        };
+        RangeDataSource {
+            end,
+            batch_size: end.max(1),
+            schema: Arc::new(Schema {
+                fields: vec![field("n"), field("square")],
+            }),
+            scanned: Mutex::default(),
+            returned: Mutex::default(),
+            max_batches_in_use: Mutex::default(),
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl DataSource for RangeDataSource {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Vec<&str>) -> Box<dyn Iterator<Item = RecordBatch> + '_> {
+        let projection: Vec<String> = match projection.is_empty() {
+            true => vec!["n".to_string(), "square".to_string()],
+            false => projection.iter().map(|s| s.to_string()).collect(),
+        };
+        self.scanned.lock().unwrap().push(projection.clone());
+
+        let schema = self
+            .schema
+            .select(projection.iter().map(String::as_str).collect())
+            .unwrap();
+        let schema = Arc::new(schema);
+        let batches = (0..self.end)
+            .step_by(self.batch_size as usize)
+            .map(move |start| {
+                let range = start..(start + self.batch_size).min(self.end);
+                let vectors: Vec<Arc<ArrowFieldVector>> = projection
+                    .iter()
+                    .map(|name| {
+                        let values = range.clone().map(|n| if name == "n" { n } else { n * n });
+                        let array: ArrayRef = Arc::new(Int64Array::from_iter_values(values));
+                        Arc::new(ArrowFieldVector::from_array(&array))
+                    })
+                    .collect();
+                let mut returned = self.returned.lock().unwrap();
+                let in_use = returned.iter().filter(|c| c.strong_count() > 0).count();
+                let mut max_in_use = self.max_batches_in_use.lock().unwrap();
+                *max_in_use = in_use.max(*max_in_use);
+                returned.push(Arc::downgrade(&vectors[0]));
+                RecordBatch {
+                    schema: schema.clone(),
+                    fields: vectors
+                        .into_iter()
+                        .map(|v| v as Arc<dyn ColumnVector>)
+                        .collect(),
+                }
+            });
+        Box::new(batches)
+    }
+}
+
+/// Creates range sources, the location is the end of the range
+pub struct RangeTableProvider;
+
+impl TableProvider for RangeTableProvider {
+    fn create(
+        &self,
+        location: &str,
+        _options: &HashMap<String, String>,
+    ) -> anyhow::Result<Arc<dyn DataSource>> {
+        Ok(Arc::new(RangeDataSource::new(location.parse()?)))
+    }
+}
@@ -1,3 +1,8 @@
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use rs_query::data_source::csv_data_source::{CsvOptions, CsvWriteOptions, MalformedRowPolicy};
+use rs_query::data_source::ipc_data_source::IpcFormat;
+use rs_query::data_source::listing_data_source::ListingFormat;
+use rs_query::data_source::parquet_data_source::ParquetWriteOptions;
 use rs_query::datatypes::arrow_types::ArrowType;
 use rs_query::execution::ExecutionContext;
 use rs_query::logical_plan::expressions::aggr_expr::{max, min, sum};
@@ -5,6 +10,7 @@ use rs_query::logical_plan::expressions::binary_expr::{and, or, BooleanBinaryExp
 use rs_query::logical_plan::expressions::literal_expr::{lit_double, lit_long, lit_str};
 use rs_query::logical_plan::expressions::math_expr::MathExprExt;
 use rs_query::logical_plan::expressions::{alias, cast, col};
+use rs_query::logical_plan::join::JoinType;
 use rs_query::logical_plan::LogicalPlanPrinter;
 use rs_query::optimizer::Optimizer;
 use rs_query::physical_plan::PhysicalPlanPrinter;
@@ -12,6 +18,7 @@ use rs_query::query_planner::QueryPlanner;
 use std::any::Any;
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::sync::Arc;
 
 mod common;
 #[test]
@@ -19,10 +26,11 @@ fn project_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .project(vec![col("first_name")]);
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
 
     assert_eq!("Bill\nGregg\nJohn\nVon\n", batch.to_csv().unwrap());
 }
@@ -32,11 +40,12 @@ fn filter_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .filter(col("state").eq(lit_str("CO")))
         .project(vec![alias(col("last_name"), "name"), col("first_name")]);
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
 
     assert_eq!(batch.schema.fields.first().unwrap().name, "name");
     assert_eq!("Langford,Gregg\nTravis,John\n", batch.to_csv().unwrap());
@@ -47,14 +56,15 @@ fn filter_with_or_expression_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .filter(or(
             col("state").eq(lit_str("CO")),
             col("state").eq(lit_str("CA")),
         ))
         .project(vec![alias(col("last_name"), "name"), col("first_name")]);
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
 
     assert_eq!(
         "Hopkins,Bill\nLangford,Gregg\nTravis,John\n",
@@ -67,14 +77,15 @@ fn filter_with_and_expression_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .filter(and(
             col("state").eq(lit_str("CO")),
             cast(col("salary"), ArrowType::Int64Type).eq(lit_long(11500)),
         ))
         .project(vec![alias(col("last_name"), "name"), col("first_name")]);
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
     assert_eq!("Travis,John\n", batch.to_csv().unwrap());
 }
 
@@ -83,10 +94,11 @@ fn filter_with_cast_to_number_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .filter(cast(col("salary"), ArrowType::Int64Type).eq(lit_long(10000)));
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
 
     assert_eq!(
         "2,Gregg,Langford,CO,Driver,10000\n",
@@ -98,30 +110,33 @@ fn filter_with_cast_to_number_from_csv() {
 fn math_expressions_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
-    let df = ctx.csv("testdata/employee.csv", true).project(vec![
-        alias(
-            cast(col("salary"), ArrowType::Int64Type).mult(lit_long(100)),
-            "multi_salary",
-        ),
-        alias(
-            cast(col("salary"), ArrowType::Int64Type).div(lit_long(100)),
-            "div_salary",
-        ),
-        alias(
-            cast(col("salary"), ArrowType::Int64Type).add(lit_long(100)),
-            "add_salary",
-        ),
-        alias(
-            cast(col("salary"), ArrowType::Int64Type).subtract(lit_long(100)),
-            "subs_salary",
-        ),
-        alias(
-            cast(col("salary"), ArrowType::DoubleType).modulus(lit_double(17_f64)),
-            "mod_salary",
-        ),
-    ]);
-
-    let batch = ctx.execute(df, false).next().unwrap();
+    let df = ctx
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
+        .project(vec![
+            alias(
+                cast(col("salary"), ArrowType::Int64Type).mult(lit_long(100)),
+                "multi_salary",
+            ),
+            alias(
+                cast(col("salary"), ArrowType::Int64Type).div(lit_long(100)),
+                "div_salary",
+            ),
+            alias(
+                cast(col("salary"), ArrowType::Int64Type).add(lit_long(100)),
+                "add_salary",
+            ),
+            alias(
+                cast(col("salary"), ArrowType::Int64Type).subtract(lit_long(100)),
+                "subs_salary",
+            ),
+            alias(
+                cast(col("salary"), ArrowType::DoubleType).modulus(lit_double(17_f64)),
+                "mod_salary",
+            ),
+        ]);
+
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
 
     assert_eq!("1200000,120,12100,11900,15\n1000000,100,10100,9900,4\n1150000,115,11600,11400,8\n1150000,115,11600,11400,8\n",
                batch.to_csv().unwrap()
@@ -133,7 +148,8 @@ fn aggregate_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .aggregate(
             vec![col("state")],
             vec![
@@ -144,7 +160,7 @@ fn aggregate_from_csv() {
         )
         .filter(col("state").eq(lit_str("CO"))); // required because otherwise test is flaky since order of states can change
 
-    let batch = ctx.execute(df, false).next().unwrap();
+    let batch = ctx.execute(df, false).unwrap().next().unwrap();
     assert_eq!("CO,21500,3,10000\n", batch.to_csv().unwrap());
 }
 
@@ -153,7 +169,8 @@ fn optimized_multi_plan_query_from_csv() {
     let ctx = ExecutionContext::new(HashMap::default());
 
     let df = ctx
-        .csv("testdata/employee.csv", true)
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
         .filter(col("state").eq(lit_str("CO")))
         // .project(vec![alias(col("state"), "state_alias"), col("salary")])
         .aggregate(
@@ -172,8 +189,801 @@ fn optimized_multi_plan_query_from_csv() {
     //
     // println!("optimized plan: {}", optimized_plan.pretty());
 
-    let batch = ctx.execute(df, true).next().unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
 
     assert_eq!("CO,21500,10000\n", batch.to_csv().unwrap());
     // println!("{}", batch.show().unwrap());
 }
+
+#[test]
+fn sql_filter_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql("SELECT last_name AS name, first_name FROM employee WHERE state = 'CO'")
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!(batch.schema.fields.first().unwrap().name, "name");
+    assert_eq!("Langford,Gregg\nTravis,John\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_aggregate_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT MIN(CAST(salary AS BIGINT)) AS min_salary, state, \
+            SUM(CAST(salary AS DOUBLE)) \
+            FROM employee \
+            WHERE state = 'CO' \
+            GROUP BY state",
+        )
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!(batch.schema.fields.first().unwrap().name, "min_salary");
+    assert_eq!("10000,CO,21500\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_limit_offset_from_csv() {
+    let mut settings = HashMap::new();
+    settings.insert("csv.batch_size".to_string(), "1".to_string());
+    let mut ctx = ExecutionContext::new(settings);
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql("SELECT first_name FROM employee LIMIT 2 OFFSET 1")
+        .unwrap();
+
+    let rows: String = ctx
+        .execute(df, true)
+        .unwrap()
+        .map(|batch| batch.to_csv().unwrap())
+        .collect();
+
+    assert_eq!("Gregg\nJohn\n", rows);
+}
+
+#[test]
+fn sql_count_and_avg_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT COUNT(*), COUNT(DISTINCT salary), AVG(CAST(salary AS DOUBLE)) \
+            FROM employee",
+        )
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!(
+        batch.schema.fields.first().unwrap().data_type,
+        ArrowType::Int64Type
+    );
+    assert_eq!(
+        batch.schema.fields.get(2).unwrap().data_type,
+        ArrowType::DoubleType
+    );
+    assert_eq!("4,3,11250\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_aggregate_empty_input_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT COUNT(*), SUM(salary), AVG(CAST(salary AS DOUBLE)), MIN(salary), MAX(salary) \
+            FROM employee \
+            WHERE 1 = 0",
+        )
+        .unwrap();
+    let batches: Vec<_> = ctx.execute(df, true).unwrap().collect();
+
+    assert_eq!(1, batches.len());
+    assert_eq!(1, batches[0].row_count());
+    assert_eq!("0,,,,\n", batches[0].to_csv().unwrap());
+
+    let df = ctx
+        .sql("SELECT state, COUNT(*) FROM employee WHERE 1 = 0 GROUP BY state")
+        .unwrap();
+    let rows: usize = ctx.execute(df, true).unwrap().map(|b| b.row_count()).sum();
+
+    assert_eq!(0, rows);
+}
+
+#[test]
+fn sql_having_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let rows = |sql: &str| -> String {
+        let df = ctx.sql(sql).unwrap();
+        ctx.execute(df, true)
+            .unwrap()
+            .map(|batch| batch.to_csv().unwrap())
+            .collect()
+    };
+
+    assert_eq!(
+        "CO,2\n",
+        rows("SELECT state, COUNT(id) FROM employee GROUP BY state HAVING COUNT(id) > 1")
+    );
+    assert_eq!(
+        "CO,2\n",
+        rows("SELECT state, COUNT(id) AS cnt FROM employee GROUP BY state HAVING cnt > 1")
+    );
+    // aggregates only used by HAVING are not returned
+    assert_eq!(
+        "CA\n",
+        rows("SELECT state FROM employee GROUP BY state HAVING MAX(salary) > 11500")
+    );
+    assert_eq!(
+        "CO\n",
+        rows("SELECT state FROM employee GROUP BY state HAVING state = 'CO'")
+    );
+}
+
+#[test]
+fn sql_distinct_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let rows = |sql: &str| -> String {
+        let df = ctx.sql(sql).unwrap();
+        ctx.execute(df, true)
+            .unwrap()
+            .map(|batch| batch.to_csv().unwrap())
+            .collect()
+    };
+
+    assert_eq!(
+        "CA\nCO\n\n",
+        rows("SELECT DISTINCT state FROM employee ORDER BY state")
+    );
+    assert_eq!(
+        "10000\n11500\n12000\n",
+        rows("SELECT DISTINCT salary FROM employee ORDER BY salary")
+    );
+    assert_eq!(
+        "1\n2\n",
+        rows("SELECT DISTINCT COUNT(id) AS cnt FROM employee GROUP BY state ORDER BY cnt")
+    );
+}
+
+#[test]
+fn sql_unary_operators_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT first_name, -CAST(salary AS BIGINT) \
+            FROM employee \
+            WHERE NOT state = 'CO' AND state IS NOT NULL",
+        )
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!("Bill,-12000\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_is_null_on_blank_string_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql("SELECT id FROM employee WHERE state IS NULL")
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!("4\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_aggregate_from_parquet() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_parquet("alltypes", "testdata/alltypes_utf8.parquet")
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT COUNT(id), MAX(bigint_col) \
+            FROM alltypes \
+            WHERE CAST(id AS BIGINT) > 3",
+        )
+        .unwrap();
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!("4,10\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn filter_after_projection_from_csv() {
+    let ctx = ExecutionContext::new(HashMap::default());
+
+    let df = ctx
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
+        .project(vec![col("first_name"), alias(col("state"), "st")])
+        .filter(col("st").eq(lit_str("CO")));
+
+    let optimized_plan = Optimizer::optimize(df.clone().logical_plan());
+    assert_eq!(
+        "Projection: first_name, state AS st\n\
+        \tScan: testdata/employee.csv; projection=[\"first_name\", \"state\"]; filters=[state = CO]\n",
+        optimized_plan.pretty()
+    );
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("Gregg,CO\nJohn,CO\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_constant_folding_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT first_name, 2 * 3 \
+            FROM employee \
+            WHERE 1 + 1 = 2 AND state = 'CO' AND NOT NOT TRUE",
+        )
+        .unwrap();
+
+    let optimized = ctx.optimizer().run(df.clone().logical_plan());
+    assert!(!optimized.plan.pretty().contains("1 + 1"));
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!("Gregg,6\nJohn,6\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn join_from_csv() {
+    let ctx = ExecutionContext::new(HashMap::default());
+
+    let employee = ctx
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    let state = ctx
+        .csv("testdata/state.csv", CsvOptions::default())
+        .unwrap();
+    let df = employee
+        .join(state, JoinType::Inner, vec![("state", "state")])
+        .filter(col("name").eq(lit_str("Colorado")))
+        .project(vec![col("first_name"), col("state.state")]);
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+
+    assert_eq!(batch.schema.fields[1].name, "state.state");
+    assert_eq!("Gregg,CO\nJohn,CO\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_self_join_and_anti_join_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    ctx.register_csv("state", "testdata/state.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT e.first_name, c.first_name \
+            FROM employee e JOIN employee c ON e.state = c.state \
+            WHERE e.id < c.id",
+        )
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("Gregg,John\n", batch.to_csv().unwrap());
+
+    let df = ctx
+        .sql("SELECT name FROM state LEFT ANTI JOIN employee ON state.state = employee.state")
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("Texas\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_order_by_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT state, MAX(CAST(salary AS BIGINT)) AS max_salary \
+            FROM employee GROUP BY state \
+            ORDER BY max_salary DESC, state NULLS FIRST",
+        )
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("CA,12000\n,11500\nCO,11500\n", batch.to_csv().unwrap());
+
+    let df = ctx
+        .sql("SELECT first_name FROM employee ORDER BY last_name DESC LIMIT 2")
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("John\nVon\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn csv_inferred_and_overridden_types() {
+    let ctx = ExecutionContext::new(HashMap::default());
+
+    let schema = ctx
+        .infer_csv_schema("testdata/employee.csv", &CsvOptions::default())
+        .unwrap();
+    assert_eq!(ArrowType::Int64Type, schema.fields[5].data_type);
+
+    let df = ctx
+        .csv("testdata/employee.csv", CsvOptions::default())
+        .unwrap()
+        .aggregate(vec![], vec![sum(col("salary"))]);
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("45000\n", batch.to_csv().unwrap());
+
+    let df = ctx
+        .csv_with_types(
+            "testdata/employee.csv",
+            CsvOptions::default(),
+            &[("salary", ArrowType::DoubleType)],
+        )
+        .unwrap();
+    assert_eq!(
+        ArrowType::DoubleType,
+        df.clone().schema().fields[5].data_type
+    );
+    assert_eq!(ArrowType::Int64Type, df.schema().fields[0].data_type);
+    assert!(ctx
+        .csv_with_types(
+            "testdata/employee.csv",
+            CsvOptions::default(),
+            &[("missing", ArrowType::Int8Type)]
+        )
+        .is_err());
+}
+
+#[test]
+fn sql_skipping_malformed_rows_from_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    let df = ctx
+        .csv_with_types(
+            "testdata/malformed.csv",
+            CsvOptions::default().with_on_malformed_row(MalformedRowPolicy::Skip),
+            &[("salary", ArrowType::Int64Type)],
+        )
+        .unwrap();
+    ctx.register("malformed", df);
+
+    let df = ctx.sql("SELECT SUM(salary) FROM malformed").unwrap();
+    let batch = ctx.execute(df.clone(), true).unwrap().next().unwrap();
+    assert_eq!("23500\n", batch.to_csv().unwrap());
+
+    let rejected = ctx.take_rejected_rows(df);
+    assert_eq!(
+        vec![3, 4],
+        rejected.iter().map(|r| r.line).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn missing_files_fail_schema_inference() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+
+    let error = ctx
+        .csv("testdata/missing.csv", CsvOptions::default())
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .starts_with("failed to open testdata/missing.csv"));
+    assert!(ctx.json("testdata/missing.ndjson").is_err());
+    assert!(ctx
+        .register_csv("missing", "testdata/missing.csv", CsvOptions::default())
+        .is_err());
+    assert!(ctx
+        .register_json("missing", "testdata/missing.ndjson")
+        .is_err());
+    assert!(ctx.table("missing").is_none());
+}
+
+#[test]
+fn failing_malformed_row_fails_execution_from_csv() {
+    let ctx = ExecutionContext::new(HashMap::default());
+    let df = ctx
+        .csv_with_types(
+            "testdata/malformed.csv",
+            CsvOptions::default(),
+            &[("salary", ArrowType::Int64Type)],
+        )
+        .unwrap()
+        .project(vec![col("salary")]);
+
+    let result = ctx.execute(df, true).map(|batches| batches.count());
+
+    assert_eq!(
+        "malformed row at testdata/malformed.csv:3: expected 3 fields, got 2",
+        result.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn sql_nested_columns_from_json() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_json("events", "testdata/events.ndjson")
+        .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT \"user.name\", value FROM events \
+            WHERE type = 'click' AND \"user.age\" > 30 ORDER BY id DESC",
+        )
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("John,\nBill,1.5\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_query_output_to_ipc() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_json("events", "testdata/events.ndjson")
+        .unwrap();
+
+    let path = std::env::temp_dir().join(format!("rs_query_events_{}.arrow", std::process::id()));
+    let file_name = path.to_str().unwrap();
+    let df = ctx
+        .sql("SELECT id, \"user.name\", value FROM events WHERE type = 'click'")
+        .unwrap();
+    let rows = ctx.write_ipc(df, file_name, IpcFormat::File).unwrap();
+    assert_eq!(2, rows);
+
+    ctx.register_ipc("clicks", file_name).unwrap();
+    let df = ctx
+        .sql("SELECT \"user.name\", value FROM clicks ORDER BY id DESC")
+        .unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!("John,\nBill,1.5\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_partition_pruning_from_csv_directory() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_listing(
+        "sales",
+        "testdata/sales",
+        ListingFormat::Csv(CsvOptions::default()),
+    )
+    .unwrap();
+
+    let df = ctx
+        .sql(
+            "SELECT region, COUNT(id), SUM(amount) FROM sales \
+            WHERE year = 2023 AND amount > 100.0 GROUP BY region ORDER BY region",
+        )
+        .unwrap();
+    let optimized = ctx.optimizer().run(df.clone().logical_plan()).plan;
+    assert!(optimized.pretty().ends_with(
+        "\t\tScan: testdata/sales; projection=[\"amount\", \"id\", \"region\"]; \
+        filters=[year = 2023, amount > 100]\n"
+    ));
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("eu,1,1200.5\nus,1,310\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_partition_pruning_skips_files_of_other_partitions() {
+    let dir = std::env::temp_dir().join(format!("rs_query_pruning_{}", std::process::id()));
+    for partition in [
+        "year=2023/region=us",
+        "year=2023/region=eu",
+        "year=2024/region=eu",
+    ] {
+        std::fs::create_dir_all(dir.join(partition)).unwrap();
+        std::fs::copy(
+            format!("testdata/sales/{}/part-0.csv", partition),
+            dir.join(partition).join("part-0.csv"),
+        )
+        .unwrap();
+    }
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_listing(
+        "sales",
+        dir.to_str().unwrap(),
+        ListingFormat::Csv(CsvOptions::default()),
+    )
+    .unwrap();
+    // the file is listed but can't be read anymore, so the scan fails if it is opened
+    std::fs::remove_file(dir.join("year=2024/region=eu/part-0.csv")).unwrap();
+
+    let df = ctx
+        .sql("SELECT id FROM sales WHERE year = 2023 ORDER BY id")
+        .unwrap();
+    let rows = ctx.execute(df, true).map(|batches| {
+        batches
+            .map(|batch| batch.to_csv().unwrap())
+            .collect::<String>()
+    });
+
+    // the failed scan of the listed file fails the execution
+    let df = ctx.sql("SELECT id FROM sales WHERE year = 2024").unwrap();
+    let not_pruned = ctx.execute(df, true).map(|batches| batches.count());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!("1\n2\n3\n", rows.unwrap());
+    assert!(not_pruned
+        .unwrap_err()
+        .to_string()
+        .starts_with("failed to open "));
+}
+
+#[test]
+fn sql_filter_from_compressed_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("gzipped", "testdata/employee.csv.gz", CsvOptions::default())
+        .unwrap();
+    ctx.register_csv("zstd", "testdata/employee.csv.zst", CsvOptions::default())
+        .unwrap();
+
+    for table in ["gzipped", "zstd"] {
+        let df = ctx
+            .sql(&format!(
+                "SELECT id, last_name FROM {} WHERE state = 'CO' ORDER BY id",
+                table
+            ))
+            .unwrap();
+        let batch = ctx.execute(df, true).unwrap().next().unwrap();
+        assert_eq!("2,Langford\n3,Travis\n", batch.to_csv().unwrap());
+    }
+}
+
+#[test]
+fn sql_query_custom_data_source() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    let numbers = Arc::new(common::RangeDataSource::new(10));
+    ctx.register_data_source("numbers", numbers.clone());
+
+    let df = ctx.sql("SELECT n FROM numbers WHERE n > 7").unwrap();
+    let optimized = ctx.optimizer().run(df.clone().logical_plan()).plan;
+    assert_eq!(
+        "Projection: n\n\
+        \tSelection: n > 7\n\
+        \t\tScan: numbers; projection=[\"n\"]\n",
+        optimized.pretty()
+    );
+    let physical_plan = QueryPlanner::create_physical_plan(optimized.deref());
+    assert!(physical_plan
+        .pretty()
+        .contains("ScanExec: schema=[n: Int64Type, square: Int64Type], projection=[\"n\"]"));
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("8\n9\n", batch.to_csv().unwrap());
+    assert_eq!(
+        vec![vec!["n".to_string()]],
+        *numbers.scanned.lock().unwrap()
+    );
+}
+
+#[test]
+fn sql_query_table_from_provider() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_table_provider("range", Arc::new(common::RangeTableProvider));
+    ctx.register_table("r", "RANGE", "4", &HashMap::default())
+        .unwrap();
+    assert!(ctx
+        .register_table("missing", "avro", "4", &HashMap::default())
+        .is_err());
+
+    let df = ctx.sql("SELECT SUM(square) FROM r").unwrap();
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("14\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn sql_limit_pushed_into_csv_scan() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+
+    let df = ctx
+        .sql("SELECT first_name FROM employee LIMIT 2 OFFSET 1")
+        .unwrap();
+    let optimized = ctx.optimizer().run(df.clone().logical_plan()).plan;
+    assert!(optimized
+        .pretty()
+        .ends_with("Scan: testdata/employee.csv; projection=[\"first_name\"]; limit=3\n"));
+
+    let batch = ctx.execute(df, true).unwrap().next().unwrap();
+    assert_eq!("Gregg\nJohn\n", batch.to_csv().unwrap());
+}
+
+#[test]
+fn analyze_table_caches_statistics() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    let before = ctx.table("employee").unwrap().logical_plan().statistics();
+    assert_eq!(None, before.row_count);
+
+    let statistics = ctx.analyze("employee").unwrap();
+    assert_eq!(Some(4), statistics.row_count);
+
+    let df = ctx.sql("SELECT state, salary FROM employee").unwrap();
+    let planned = df.logical_plan().statistics();
+    assert_eq!(Some(4), planned.row_count);
+    assert_eq!(Some(2), planned.columns[0].distinct_count);
+    assert_eq!(Some(1), planned.columns[0].null_count);
+    assert_eq!(Some(3), planned.columns[1].distinct_count);
+
+    // another table on the same file uses the cached statistics
+    ctx.register_csv("copy", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    let copy = ctx.table("copy").unwrap().logical_plan().statistics();
+    assert_eq!(statistics, copy);
+    assert!(ctx.analyze("unknown").is_err());
+}
+
+#[test]
+fn analyzed_statistics_expire_when_file_is_modified() {
+    let dir = std::env::temp_dir().join(format!("rs_query_analyze_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("numbers.csv");
+    std::fs::write(&path, "n\n1\n2\n").unwrap();
+    let file_name = path.to_str().unwrap();
+
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("numbers", file_name, CsvOptions::default())
+        .unwrap();
+    assert_eq!(Some(2), ctx.analyze("numbers").unwrap().row_count);
+
+    std::fs::write(&path, "n\n1\n2\n3\n").unwrap();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    let statistics = ctx.table("numbers").unwrap().logical_plan().statistics();
+    assert_eq!(None, statistics.row_count);
+    assert_eq!(Some(3), ctx.analyze("numbers").unwrap().row_count);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn write_query_result_to_csv() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("rs_query_managers_{}.csv", std::process::id()));
+    let file_name = path.to_str().unwrap();
+
+    let df = ctx
+        .sql("SELECT id, job_title, state FROM employee WHERE salary > 11000")
+        .unwrap();
+    let rows = df
+        .write_csv(&ctx, file_name, CsvWriteOptions::default())
+        .unwrap();
+
+    assert_eq!(3, rows);
+    assert_eq!(
+        "id,job_title,state\n1,Manager,CA\n3,\"Manager, Software\",CO\n4,Defensive End,\n",
+        std::fs::read_to_string(&path).unwrap()
+    );
+    // the quoted value is read back as one column
+    let written = ctx.csv(file_name, CsvOptions::default()).unwrap();
+    let batch = ctx.execute(written, true).unwrap().next().unwrap();
+    assert_eq!(
+        "3,Manager, Software,CO\n",
+        batch.slice(1, 1).to_csv().unwrap()
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn write_csv_streams_batches() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    let numbers = Arc::new(common::RangeDataSource::new(100).with_batch_size(10));
+    ctx.register_data_source("numbers", numbers.clone());
+    let path = std::env::temp_dir().join(format!("rs_query_streamed_{}.csv", std::process::id()));
+
+    let df = ctx.sql("SELECT n, square FROM numbers").unwrap();
+    let rows = ctx
+        .write_csv(df, path.to_str().unwrap(), CsvWriteOptions::default())
+        .unwrap();
+
+    assert_eq!(100, rows);
+    // each batch is written and released before the next one is read
+    assert_eq!(0, *numbers.max_batches_in_use.lock().unwrap());
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(101, content.lines().count());
+    assert!(content.ends_with("\n99,9801\n"));
+}
+
+#[test]
+fn write_partitioned_parquet() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    ctx.register_csv("employee", "testdata/employee.csv", CsvOptions::default())
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("rs_query_partitioned_{}", std::process::id()));
+    let dir = path.to_str().unwrap();
+    let options = ParquetWriteOptions::default().with_partition_columns(vec!["state"]);
+
+    let df = ctx
+        .sql("SELECT id, first_name, state FROM employee WHERE id < 4")
+        .unwrap();
+    assert_eq!(3, ctx.write_parquet(df, dir, options.clone()).unwrap());
+    assert!(path.join("state=CO").join("part-0.parquet").is_file());
+
+    let written = ctx.listing(dir, ListingFormat::Parquet).unwrap();
+    let df = written
+        .filter(col("state").eq(lit_str("CO")))
+        .project(vec![col("first_name"), col("state")]);
+    let result: String = ctx
+        .execute(df, true)
+        .unwrap()
+        .map(|batch| batch.to_csv().unwrap())
+        .collect();
+    assert_eq!("Gregg,CO\nJohn,CO\n", result);
+
+    // the empty state of the last employee can't be used as directory name
+    let df = ctx.sql("SELECT id, state FROM employee").unwrap();
+    assert!(ctx.write_parquet(df, dir, options).is_err());
+    std::fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn write_parquet_streams_batches_into_row_groups() {
+    let mut ctx = ExecutionContext::new(HashMap::default());
+    let numbers = Arc::new(common::RangeDataSource::new(100).with_batch_size(10));
+    ctx.register_data_source("numbers", numbers.clone());
+    let path =
+        std::env::temp_dir().join(format!("rs_query_streamed_{}.parquet", std::process::id()));
+    let options = ParquetWriteOptions::default().with_row_group_size(25);
+
+    let df = ctx.sql("SELECT n, square FROM numbers").unwrap();
+    let rows = ctx
+        .write_parquet(df, path.to_str().unwrap(), options)
+        .unwrap();
+
+    assert_eq!(100, rows);
+    assert_eq!(0, *numbers.max_batches_in_use.lock().unwrap());
+    let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    let row_groups: Vec<i64> = reader
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|row_group| row_group.num_rows())
+        .collect();
+    assert_eq!(vec![25, 25, 25, 25], row_groups);
+}